@@ -10,4 +10,26 @@ pub enum EscrowError {
     InvalidMintA,
     #[msg("Invalid mint b")]
     InvalidMintB,
-}
\ No newline at end of file
+    #[msg("Invalid arbiter")]
+    InvalidArbiter,
+    #[msg("Invalid taker")]
+    InvalidTaker,
+    #[msg("Escrow already funded")]
+    AlreadyFunded,
+    #[msg("Escrow not funded")]
+    NotFunded,
+    #[msg("Release not confirmed")]
+    NotConfirmed,
+    #[msg("Unauthorized")]
+    Unauthorized,
+    #[msg("Escrow account is not in the v1 layout")]
+    NotV1Escrow,
+    #[msg("Escrow receive amount differs from the expected amount")]
+    UnexpectedReceive,
+    #[msg("Vault holds less token A than the minimum")]
+    VaultBelowMinimum,
+    #[msg("Rent recipient is not the escrow's")]
+    InvalidRentRecipient,
+    #[msg("Escrow is still open; refund it instead")]
+    EscrowStillOpen,
+}
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::state::ArbitratedEscrow;
+use crate::errors::EscrowError;
+
+#[derive(Accounts)]
+pub struct ConfirmArbitrated<'info> {
+    pub party: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"arbitrated", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        constraint = escrow.is_funded() @ EscrowError::NotFunded,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+}
+
+pub fn handler(ctx: Context<ConfirmArbitrated>) -> Result<()> {
+    let party = ctx.accounts.party.key();
+    let escrow = &mut ctx.accounts.escrow;
+
+    // Record the confirmation of whichever side signed
+    if party == escrow.maker {
+        escrow.maker_confirmed = true;
+    } else if party == escrow.taker {
+        escrow.taker_confirmed = true;
+    } else {
+        return err!(EscrowError::Unauthorized);
+    }
+
+    Ok(())
+}
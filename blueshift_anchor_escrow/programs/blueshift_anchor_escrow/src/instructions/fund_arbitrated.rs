@@ -0,0 +1,76 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+
+use crate::state::ArbitratedEscrow;
+use crate::errors::EscrowError;
+
+#[derive(Accounts)]
+pub struct FundArbitrated<'info> {
+    #[account(mut)]
+    pub taker: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"arbitrated", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = mint_b @ EscrowError::InvalidMintB,
+        constraint = !escrow.is_funded() @ EscrowError::AlreadyFunded,
+    )]
+    pub escrow: Box<Account<'info, ArbitratedEscrow>>,
+
+    /// Token Accounts
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = taker,
+        associated_token::token_program = token_program
+    )]
+    pub taker_ata_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Programs
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> FundArbitrated<'info> {
+    /// Transfer the agreed Token B amount into the escrow's second vault
+    fn deposit_tokens(&mut self) -> Result<()> {
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.taker_ata_b.to_account_info(),
+                    mint: self.mint_b.to_account_info(),
+                    to: self.vault_b.to_account_info(),
+                    authority: self.taker.to_account_info(),
+                },
+            ),
+            self.escrow.receive,
+            self.mint_b.decimals,
+        )?;
+
+        self.escrow.taker = self.taker.key();
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<FundArbitrated>) -> Result<()> {
+    // Neither the maker nor the arbiter can take the other side
+    let taker = ctx.accounts.taker.key();
+    require!(
+        taker != ctx.accounts.escrow.maker && taker != ctx.accounts.escrow.arbiter,
+        EscrowError::InvalidTaker
+    );
+
+    // Deposit Token B and record the taker
+    ctx.accounts.deposit_tokens()?;
+
+    Ok(())
+}
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
 use anchor_spl::associated_token::AssociatedToken;
 
-use crate::state::Escrow;
+use crate::state::{Escrow, ESCROW_VERSION};
 use crate::errors::EscrowError;
 
 
@@ -13,7 +13,7 @@ pub struct Make<'info> {
     pub maker: Signer<'info>,
     #[account(
         init,
-        payer = maker,
+        payer = payer.as_ref().unwrap_or(&maker),
         space = Escrow::INIT_SPACE + Escrow::DISCRIMINATOR.len(),
         seeds = [b"escrow", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
         bump,
@@ -38,7 +38,7 @@ pub struct Make<'info> {
     pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
     #[account(
         init,
-        payer = maker,
+        payer = payer.as_ref().unwrap_or(&maker),
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
         associated_token::token_program = token_program
@@ -49,12 +49,20 @@ pub struct Make<'info> {
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
+    /// Funds the escrow and vault instead of the maker when a relayer sponsors the trade
+    #[account(mut)]
+    pub payer: Option<Signer<'info>>,
 }
 
 
 impl<'info> Make<'info> {
+    /// # The account paying rent: the payer if given, else the maker
+    fn payer(&self) -> &Signer<'info> {
+        self.payer.as_ref().unwrap_or(&self.maker)
+    }
+
     /// # Create the Escrow
-    fn populate_escrow(&mut self, seed: u64, amount: u64, bump: u8) -> Result<()> {
+    fn populate_escrow(&mut self, seed: u64, amount: u64, bump: u8, rent_recipient: Option<Pubkey>) -> Result<()> {
         self.escrow.set_inner(Escrow {
             seed,
             maker: self.maker.key(),
@@ -62,6 +70,8 @@ impl<'info> Make<'info> {
             mint_b: self.mint_b.key(),
             receive: amount,
             bump,
+            version: ESCROW_VERSION,
+            rent_recipient: rent_recipient.unwrap_or(self.payer().key()),
         });
 
         Ok(())
@@ -87,13 +97,13 @@ impl<'info> Make<'info> {
     }
 }
 
-pub fn handler(ctx: Context<Make>, seed: u64, receive: u64, amount: u64) -> Result<()> {
+pub fn handler(ctx: Context<Make>, seed: u64, receive: u64, amount: u64, rent_recipient: Option<Pubkey>) -> Result<()> {
     // Validate the amount
     require_gt!(receive, 0, EscrowError::InvalidAmount);
     require_gt!(amount, 0, EscrowError::InvalidAmount);
 
-    // Save the Escrow Data
-    ctx.accounts.populate_escrow(seed, receive, ctx.bumps.escrow)?;
+    // Save the Escrow Data; rent goes back to the payer unless someone else is named
+    ctx.accounts.populate_escrow(seed, receive, ctx.bumps.escrow, rent_recipient)?;
 
     // Deposit Tokens
     ctx.accounts.deposit_tokens(amount)?;
@@ -0,0 +1,118 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::ArbitratedEscrow;
+use crate::errors::EscrowError;
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct MakeArbitrated<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    #[account(
+        init,
+        payer = maker,
+        space = ArbitratedEscrow::INIT_SPACE + ArbitratedEscrow::DISCRIMINATOR.len(),
+        seeds = [b"arbitrated", maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow: Account<'info, ArbitratedEscrow>,
+
+    /// Token Accounts
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init,
+        payer = maker,
+        associated_token::mint = mint_b,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> MakeArbitrated<'info> {
+    /// # Create the Escrow
+    fn populate_escrow(&mut self, seed: u64, receive: u64, arbiter: Pubkey, bump: u8) -> Result<()> {
+        self.escrow.set_inner(ArbitratedEscrow {
+            seed,
+            maker: self.maker.key(),
+            taker: Pubkey::default(),
+            arbiter,
+            mint_a: self.mint_a.key(),
+            mint_b: self.mint_b.key(),
+            receive,
+            maker_confirmed: false,
+            taker_confirmed: false,
+            bump,
+        });
+
+        Ok(())
+    }
+
+    /// # Deposit the tokens
+    fn deposit_tokens(&self, amount: u64) -> Result<()> {
+        transfer_checked(
+            CpiContext::new(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.maker_ata_a.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                    to: self.vault_a.to_account_info(),
+                    authority: self.maker.to_account_info(),
+                },
+            ),
+            amount,
+            self.mint_a.decimals,
+        )?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<MakeArbitrated>, seed: u64, receive: u64, amount: u64, arbiter: Pubkey) -> Result<()> {
+    // Validate the amount
+    require_gt!(receive, 0, EscrowError::InvalidAmount);
+    require_gt!(amount, 0, EscrowError::InvalidAmount);
+
+    // The arbiter must be an independent party
+    require!(
+        arbiter != Pubkey::default() && arbiter != ctx.accounts.maker.key(),
+        EscrowError::InvalidArbiter
+    );
+
+    // Save the Escrow Data
+    ctx.accounts.populate_escrow(seed, receive, arbiter, ctx.bumps.escrow)?;
+
+    // Deposit Tokens
+    ctx.accounts.deposit_tokens(amount)?;
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program::{transfer, Transfer};
+
+use crate::state::{Escrow, EscrowV1, ESCROW_VERSION};
+use crate::errors::EscrowError;
+
+#[derive(Accounts)]
+pub struct Migrate<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    /// CHECK: a v1 escrow, which `Account<Escrow>` can no longer deserialize. The
+    /// discriminator, layout, maker and PDA are checked in the handler.
+    #[account(mut, owner = crate::ID)]
+    pub escrow: UncheckedAccount<'info>,
+
+    /// Programs
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Migrate<'info> {
+    /// # Read the v1 Escrow
+    fn load_v1(&self) -> Result<EscrowV1> {
+        let data = self.escrow.try_borrow_data()?;
+        require_eq!(
+            data.len(),
+            Escrow::DISCRIMINATOR.len() + EscrowV1::INIT_SPACE,
+            EscrowError::NotV1Escrow
+        );
+        require!(
+            data.starts_with(Escrow::DISCRIMINATOR),
+            EscrowError::NotV1Escrow
+        );
+        let escrow = EscrowV1::try_from_slice(&data[Escrow::DISCRIMINATOR.len()..])?;
+
+        require_keys_eq!(escrow.maker, self.maker.key(), EscrowError::InvalidMaker);
+        let address = Pubkey::create_program_address(
+            &[
+                b"escrow",
+                escrow.maker.as_ref(),
+                &escrow.seed.to_le_bytes()[..],
+                &[escrow.bump],
+            ],
+            &crate::ID,
+        )
+        .map_err(|_| ErrorCode::ConstraintSeeds)?;
+        require_keys_eq!(address, self.escrow.key(), ErrorCode::ConstraintSeeds);
+
+        Ok(escrow)
+    }
+
+    /// # Resize the account, topping up rent from the maker
+    fn grow(&self, space: usize) -> Result<()> {
+        let lamports = Rent::get()?.minimum_balance(space);
+        let shortfall = lamports.saturating_sub(self.escrow.lamports());
+        if shortfall > 0 {
+            transfer(
+                CpiContext::new(
+                    self.system_program.to_account_info(),
+                    Transfer {
+                        from: self.maker.to_account_info(),
+                        to: self.escrow.to_account_info(),
+                    },
+                ),
+                shortfall,
+            )?;
+        }
+
+        self.escrow.to_account_info().resize(space)?;
+
+        Ok(())
+    }
+
+    /// # Write the current Escrow, filling new fields with defaults
+    fn write_current(&self, v1: EscrowV1) -> Result<()> {
+        let escrow = Escrow {
+            seed: v1.seed,
+            maker: v1.maker,
+            mint_a: v1.mint_a,
+            mint_b: v1.mint_b,
+            receive: v1.receive,
+            bump: v1.bump,
+            version: ESCROW_VERSION,
+            rent_recipient: v1.maker,
+        };
+        let mut data = self.escrow.try_borrow_mut_data()?;
+        escrow.try_serialize(&mut &mut data[..])?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<Migrate>) -> Result<()> {
+    let escrow = ctx.accounts.load_v1()?;
+
+    ctx.accounts.grow(Escrow::DISCRIMINATOR.len() + Escrow::INIT_SPACE)?;
+
+    ctx.accounts.write_current(escrow)?;
+
+    Ok(())
+}
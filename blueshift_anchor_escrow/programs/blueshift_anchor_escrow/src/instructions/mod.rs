@@ -1,7 +1,23 @@
 pub mod make;
 pub mod take;
 pub mod refund;
+pub mod make_arbitrated;
+pub mod fund_arbitrated;
+pub mod confirm_arbitrated;
+pub mod release_arbitrated;
+pub mod refund_arbitrated;
+pub mod migrate;
+pub mod quote;
+pub mod sweep;
 
 pub use make::*;
 pub use take::*;
 pub use refund::*;
+pub use make_arbitrated::*;
+pub use fund_arbitrated::*;
+pub use confirm_arbitrated::*;
+pub use release_arbitrated::*;
+pub use refund_arbitrated::*;
+pub use migrate::*;
+pub use quote::*;
+pub use sweep::*;
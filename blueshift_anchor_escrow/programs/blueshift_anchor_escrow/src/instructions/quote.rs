@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::state::Escrow;
+use crate::errors::EscrowError;
+
+/// Fixed-point scale of `EscrowQuote::price`.
+pub const QUOTE_PRICE_SCALE: u64 = 1_000_000_000;
+
+/// An escrow's terms, returned by `quote`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct EscrowQuote {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Token A on offer.
+    pub vault_amount: u64,
+    /// Token B the taker pays.
+    pub receive: u64,
+    /// `receive` per unit of token A in base units, scaled by `QUOTE_PRICE_SCALE`
+    /// (saturating); 0 when the vault is empty.
+    pub price: u64,
+}
+
+#[derive(Accounts)]
+pub struct Quote<'info> {
+    #[account(
+        seeds = [b"escrow", escrow.maker.as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = mint_a @ EscrowError::InvalidMintA,
+    )]
+    pub escrow: Box<Account<'info, Escrow>>,
+
+    /// Token Accounts
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Programs
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Quote<'info> {
+    fn quote(&self) -> EscrowQuote {
+        let vault_amount = self.vault.amount;
+        let price = match vault_amount {
+            0 => 0,
+            _ => (self.escrow.receive as u128 * QUOTE_PRICE_SCALE as u128 / vault_amount as u128)
+                .min(u64::MAX as u128) as u64,
+        };
+
+        EscrowQuote {
+            seed: self.escrow.seed,
+            maker: self.escrow.maker,
+            mint_a: self.escrow.mint_a,
+            mint_b: self.escrow.mint_b,
+            vault_amount,
+            receive: self.escrow.receive,
+            price,
+        }
+    }
+}
+
+/// Return the escrow's terms and vault balance without changing any account.
+pub fn handler(ctx: Context<Quote>) -> Result<EscrowQuote> {
+    Ok(ctx.accounts.quote())
+}
@@ -11,13 +11,19 @@ pub struct Refund<'info> {
     pub maker: Signer<'info>,
     #[account(
         mut,
-        close = maker,
+        close = rent_recipient,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
         has_one = maker @ EscrowError::InvalidMaker,
         has_one = mint_a @ EscrowError::InvalidMintA,
     )]
     pub escrow: Box<Account<'info, Escrow>>,
+    /// CHECK: only receives the escrow's and vault's rent; must be the one named at make
+    #[account(
+        mut,
+        address = escrow.rent_recipient @ EscrowError::InvalidRentRecipient,
+    )]
+    pub rent_recipient: UncheckedAccount<'info>,
 
     /// Token Accounts
     pub mint_a: Box<InterfaceAccount<'info, Mint>>,
@@ -70,13 +76,13 @@ impl<'info> Refund<'info> {
             self.mint_a.decimals,
         )?;
 
-        // Close the vault account and send remaining lamports to maker
+        // Close the vault account and send remaining lamports to the rent recipient
         close_account(CpiContext::new_with_signer(
             self.token_program.to_account_info(),
             CloseAccount {
                 account: self.vault.to_account_info(),
                 authority: self.escrow.to_account_info(),
-                destination: self.maker.to_account_info(),
+                destination: self.rent_recipient.to_account_info(),
             },
             &signer_seeds,
         ))?;
@@ -90,7 +96,7 @@ pub fn handler(ctx: Context<Refund>) -> Result<()> {
     ctx.accounts.refund_and_close_vault()?;
 
     // The escrow account will be automatically closed by Anchor
-    // because of the `close = maker` constraint
+    // because of the `close = rent_recipient` constraint
 
     Ok(())
 }
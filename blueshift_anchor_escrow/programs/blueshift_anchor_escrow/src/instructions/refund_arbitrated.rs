@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, close_account, CloseAccount, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::ArbitratedEscrow;
+use crate::errors::EscrowError;
+
+#[derive(Accounts)]
+pub struct RefundArbitrated<'info> {
+    /// The arbiter, or the maker while no taker has funded the escrow
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = maker,
+        seeds = [b"arbitrated", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        has_one = maker @ EscrowError::InvalidMaker,
+        has_one = mint_a @ EscrowError::InvalidMintA,
+        has_one = mint_b @ EscrowError::InvalidMintB,
+    )]
+    pub escrow: Box<Account<'info, ArbitratedEscrow>>,
+
+    /// Token Accounts
+    pub mint_a: Box<InterfaceAccount<'info, Mint>>,
+    pub mint_b: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        mut,
+        associated_token::mint = mint_b,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault_b: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = authority,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: Box<InterfaceAccount<'info, TokenAccount>>,
+    /// Required once the escrow is funded: receives the taker's Token B back
+    #[account(
+        mut,
+        token::mint = mint_b,
+        token::token_program = token_program,
+        constraint = taker_ata_b.owner == escrow.taker @ EscrowError::InvalidTaker,
+    )]
+    pub taker_ata_b: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// Programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RefundArbitrated<'info> {
+    /// Return both vaults to the parties that funded them and close them
+    fn refund_and_close_vaults(&mut self) -> Result<()> {
+        // Create the signer seeds for the Escrow PDA
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            b"arbitrated",
+            self.maker.to_account_info().key.as_ref(),
+            &self.escrow.seed.to_le_bytes()[..],
+            &[self.escrow.bump],
+        ]];
+
+        // Transfer all Token A from Vault A back to maker
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                TransferChecked {
+                    from: self.vault_a.to_account_info(),
+                    to: self.maker_ata_a.to_account_info(),
+                    mint: self.mint_a.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                },
+                &signer_seeds,
+            ),
+            self.vault_a.amount,
+            self.mint_a.decimals,
+        )?;
+
+        // Transfer all Token B from Vault B back to taker
+        if self.escrow.is_funded() {
+            let taker_ata_b = self.taker_ata_b.as_ref().ok_or(EscrowError::InvalidTaker)?;
+
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.vault_b.to_account_info(),
+                        to: taker_ata_b.to_account_info(),
+                        mint: self.mint_b.to_account_info(),
+                        authority: self.escrow.to_account_info(),
+                    },
+                    &signer_seeds,
+                ),
+                self.vault_b.amount,
+                self.mint_b.decimals,
+            )?;
+        }
+
+        // Close both Vaults
+        for vault in [&self.vault_a, &self.vault_b] {
+            close_account(CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                CloseAccount {
+                    account: vault.to_account_info(),
+                    authority: self.escrow.to_account_info(),
+                    destination: self.maker.to_account_info(),
+                },
+                &signer_seeds,
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<RefundArbitrated>) -> Result<()> {
+    // The arbiter can always refund; the maker only before the taker funds
+    let authority = ctx.accounts.authority.key();
+    let escrow = &ctx.accounts.escrow;
+    require!(
+        authority == escrow.arbiter || (authority == escrow.maker && !escrow.is_funded()),
+        EscrowError::Unauthorized
+    );
+
+    // Refund tokens and close vaults
+    ctx.accounts.refund_and_close_vaults()?;
+
+    // The escrow account will be automatically closed by Anchor
+    // because of the `close = maker` constraint
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{transfer_checked, close_account, CloseAccount, TransferChecked, Mint, TokenAccount, TokenInterface};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::{ArbitratedEscrow, Escrow};
+use crate::errors::EscrowError;
+
+/// Which of the maker's escrow PDAs owns the swept token account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowKind {
+    /// [b"escrow", maker, seed], from `make`.
+    Escrow,
+    /// [b"arbitrated", maker, seed], from `make_arbitrated`.
+    Arbitrated,
+}
+
+impl EscrowKind {
+    /// Seed prefix of this kind's PDA.
+    pub fn prefix(self) -> &'static [u8] {
+        match self {
+            Self::Escrow => b"escrow",
+            Self::Arbitrated => b"arbitrated",
+        }
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(seed: u64, kind: EscrowKind)]
+pub struct Sweep<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+    /// CHECK: the maker's escrow PDA of `kind`; only signs for its token accounts.
+    /// It may already be closed; if it is still open, the handler keeps its vaults
+    /// out of reach.
+    #[account(
+        seeds = [kind.prefix(), maker.key().as_ref(), seed.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub escrow: UncheckedAccount<'info>,
+
+    /// Token Accounts
+    #[account(
+        mint::token_program = token_program
+    )]
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = escrow,
+        token::token_program = token_program
+    )]
+    pub token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(
+        init_if_needed,
+        payer = maker,
+        associated_token::mint = mint,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// Programs
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Sweep<'info> {
+    /// # Keep an open escrow's vaults for its own settlement
+    fn check_not_open_escrow_mint(&self, kind: EscrowKind) -> Result<()> {
+        if self.escrow.owner != &crate::ID {
+            return Ok(());
+        }
+        let data = self.escrow.try_borrow_data()?;
+        let mint = self.mint.key();
+        match kind {
+            EscrowKind::Escrow => {
+                if !data.starts_with(Escrow::DISCRIMINATOR) {
+                    return Ok(());
+                }
+                // mint_a follows the seed and maker in both the v1 and current layouts
+                let mint_a = Escrow::DISCRIMINATOR.len() + 8 + 32;
+                require!(
+                    data.get(mint_a..mint_a + 32) != Some(mint.as_ref()),
+                    EscrowError::EscrowStillOpen
+                );
+            }
+            EscrowKind::Arbitrated => {
+                if !data.starts_with(ArbitratedEscrow::DISCRIMINATOR) {
+                    return Ok(());
+                }
+                // Both legs are held until release or refund
+                let escrow = ArbitratedEscrow::try_deserialize(&mut &data[..])?;
+                require!(
+                    mint != escrow.mint_a && mint != escrow.mint_b,
+                    EscrowError::EscrowStillOpen
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Return the stray tokens to the maker and close the account
+    fn sweep_and_close(&self, seed: u64, kind: EscrowKind, bump: u8) -> Result<()> {
+        let signer_seeds: [&[&[u8]]; 1] = [&[
+            kind.prefix(),
+            self.maker.to_account_info().key.as_ref(),
+            &seed.to_le_bytes()[..],
+            &[bump],
+        ]];
+
+        if self.token_account.amount > 0 {
+            transfer_checked(
+                CpiContext::new_with_signer(
+                    self.token_program.to_account_info(),
+                    TransferChecked {
+                        from: self.token_account.to_account_info(),
+                        to: self.maker_token_account.to_account_info(),
+                        mint: self.mint.to_account_info(),
+                        authority: self.escrow.to_account_info(),
+                    },
+                    &signer_seeds,
+                ),
+                self.token_account.amount,
+                self.mint.decimals,
+            )?;
+        }
+
+        close_account(CpiContext::new_with_signer(
+            self.token_program.to_account_info(),
+            CloseAccount {
+                account: self.token_account.to_account_info(),
+                authority: self.escrow.to_account_info(),
+                destination: self.maker.to_account_info(),
+            },
+            &signer_seeds,
+        ))?;
+
+        Ok(())
+    }
+}
+
+pub fn handler(ctx: Context<Sweep>, seed: u64, kind: EscrowKind) -> Result<()> {
+    // An open escrow's vaults are settled by its own instructions
+    ctx.accounts.check_not_open_escrow_mint(kind)?;
+
+    // Recover the tokens and the account's rent
+    ctx.accounts.sweep_and_close(seed, kind, ctx.bumps.escrow)?;
+
+    Ok(())
+}
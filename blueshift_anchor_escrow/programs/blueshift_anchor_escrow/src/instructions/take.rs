@@ -13,7 +13,7 @@ pub struct Take<'info> {
   pub maker: SystemAccount<'info>,
   #[account(
       mut,
-      close = maker,
+      close = rent_recipient,
       seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
       bump = escrow.bump,
       has_one = maker @ EscrowError::InvalidMaker,
@@ -21,6 +21,12 @@ pub struct Take<'info> {
       has_one = mint_b @ EscrowError::InvalidMintB,
   )]
   pub escrow: Box<Account<'info, Escrow>>,
+  /// CHECK: only receives the escrow's and vault's rent; must be the one named at make
+  #[account(
+      mut,
+      address = escrow.rent_recipient @ EscrowError::InvalidRentRecipient,
+  )]
+  pub rent_recipient: UncheckedAccount<'info>,
 
   /// Token Accounts
   pub mint_a: Box<InterfaceAccount<'info, Mint>>,
@@ -64,6 +70,18 @@ pub struct Take<'info> {
 
 
 impl<'info> Take<'info> {
+    /// # Check the terms the taker signed for
+    fn check_expected_terms(&self, expected_receive: Option<u64>, min_amount_a: Option<u64>) -> Result<()> {
+        if let Some(expected_receive) = expected_receive {
+            require_eq!(self.escrow.receive, expected_receive, EscrowError::UnexpectedReceive);
+        }
+        if let Some(min_amount_a) = min_amount_a {
+            require_gte!(self.vault.amount, min_amount_a, EscrowError::VaultBelowMinimum);
+        }
+
+        Ok(())
+    }
+
     fn transfer_to_maker(&mut self) -> Result<()> {
         transfer_checked(
             CpiContext::new(
@@ -113,7 +131,7 @@ impl<'info> Take<'info> {
             CloseAccount {
                 account: self.vault.to_account_info(),
                 authority: self.escrow.to_account_info(),
-                destination: self.maker.to_account_info(),
+                destination: self.rent_recipient.to_account_info(),
             },
             &signer_seeds,
         ))?;
@@ -122,7 +140,10 @@ impl<'info> Take<'info> {
     }
 }
 
-pub fn handler(ctx: Context<Take>) -> Result<()> {
+pub fn handler(ctx: Context<Take>, expected_receive: Option<u64>, min_amount_a: Option<u64>) -> Result<()> {
+    // Fail if the escrow or vault changed since the taker quoted it
+    ctx.accounts.check_expected_terms(expected_receive, min_amount_a)?;
+
     // Transfer Token B to Maker
     ctx.accounts.transfer_to_maker()?;
 
@@ -12,17 +12,73 @@ pub mod blueshift_anchor_escrow {
     use super::*;
 
     #[instruction(discriminator = 0)]
-    pub fn make(ctx: Context<Make>, seed: u64, receive: u64, amount: u64) -> Result<()> {
-        instructions::make::handler(ctx, seed, receive, amount)
+    pub fn make(
+        ctx: Context<Make>,
+        seed: u64,
+        receive: u64,
+        amount: u64,
+        rent_recipient: Option<Pubkey>,
+    ) -> Result<()> {
+        instructions::make::handler(ctx, seed, receive, amount, rent_recipient)
     }
 
     #[instruction(discriminator = 1)]
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        instructions::take::handler(ctx)
+    pub fn take(
+        ctx: Context<Take>,
+        expected_receive: Option<u64>,
+        min_amount_a: Option<u64>,
+    ) -> Result<()> {
+        instructions::take::handler(ctx, expected_receive, min_amount_a)
     }
 
     #[instruction(discriminator = 2)]
     pub fn refund(ctx: Context<Refund>) -> Result<()> {
         instructions::refund::handler(ctx)
     }
+
+    #[instruction(discriminator = 3)]
+    pub fn make_arbitrated(
+        ctx: Context<MakeArbitrated>,
+        seed: u64,
+        receive: u64,
+        amount: u64,
+        arbiter: Pubkey,
+    ) -> Result<()> {
+        instructions::make_arbitrated::handler(ctx, seed, receive, amount, arbiter)
+    }
+
+    #[instruction(discriminator = 4)]
+    pub fn fund_arbitrated(ctx: Context<FundArbitrated>) -> Result<()> {
+        instructions::fund_arbitrated::handler(ctx)
+    }
+
+    #[instruction(discriminator = 5)]
+    pub fn confirm_arbitrated(ctx: Context<ConfirmArbitrated>) -> Result<()> {
+        instructions::confirm_arbitrated::handler(ctx)
+    }
+
+    #[instruction(discriminator = 6)]
+    pub fn release_arbitrated(ctx: Context<ReleaseArbitrated>) -> Result<()> {
+        instructions::release_arbitrated::handler(ctx)
+    }
+
+    #[instruction(discriminator = 7)]
+    pub fn refund_arbitrated(ctx: Context<RefundArbitrated>) -> Result<()> {
+        instructions::refund_arbitrated::handler(ctx)
+    }
+
+    #[instruction(discriminator = 8)]
+    pub fn migrate(ctx: Context<Migrate>) -> Result<()> {
+        instructions::migrate::handler(ctx)
+    }
+
+    #[instruction(discriminator = 9)]
+    pub fn quote(ctx: Context<Quote>) -> Result<EscrowQuote> {
+        instructions::quote::handler(ctx)
+    }
+
+    #[instruction(discriminator = 10)]
+    pub fn sweep(ctx: Context<Sweep>, seed: u64, kind: EscrowKind) -> Result<()> {
+        instructions::sweep::handler(ctx, seed, kind)
+    }
 }
\ No newline at end of file
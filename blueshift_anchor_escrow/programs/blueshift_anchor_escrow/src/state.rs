@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 
+/// Current `Escrow` layout version. v1 accounts predate the version field and are
+/// read with `EscrowV1`.
+pub const ESCROW_VERSION: u8 = 2;
+
 #[derive(InitSpace)]
 #[account(discriminator = 1)]
 pub struct Escrow {
@@ -9,4 +13,43 @@ pub struct Escrow {
     pub mint_b: Pubkey,
     pub receive: u64,
     pub bump: u8,
-}
\ No newline at end of file
+    pub version: u8,
+    /// Receives the escrow's rent when it is closed.
+    pub rent_recipient: Pubkey,
+}
+
+/// `Escrow` as written before layout versioning, under the same discriminator.
+/// Only read by `migrate`, which rewrites the account as the current `Escrow`.
+#[derive(AnchorSerialize, AnchorDeserialize, InitSpace)]
+pub struct EscrowV1 {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: u8,
+}
+
+/// Three-party escrow: both legs are held by the program until the maker and
+/// taker both confirm, or the arbiter settles it either way.
+#[derive(InitSpace)]
+#[account(discriminator = 2)]
+pub struct ArbitratedEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    /// Set when the taker funds the Token B vault; default until then.
+    pub taker: Pubkey,
+    pub arbiter: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub maker_confirmed: bool,
+    pub taker_confirmed: bool,
+    pub bump: u8,
+}
+
+impl ArbitratedEscrow {
+    pub fn is_funded(&self) -> bool {
+        self.taker != Pubkey::default()
+    }
+}
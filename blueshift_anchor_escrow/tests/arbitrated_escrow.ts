@@ -0,0 +1,282 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BlueshiftAnchorEscrow } from "../target/types/blueshift_anchor_escrow";
+import {
+  TOKEN_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  getAssociatedTokenAddressSync,
+  createMint,
+  createAssociatedTokenAccount,
+  mintTo,
+  getAccount,
+} from "@solana/spl-token";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+
+describe("blueshift_anchor_escrow: arbitrated", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.blueshiftAnchorEscrow as Program<BlueshiftAnchorEscrow>;
+
+  let maker: Keypair;
+  let taker: Keypair;
+  let arbiter: Keypair;
+  let mintA: PublicKey;
+  let mintB: PublicKey;
+  let makerAtaA: PublicKey;
+  let makerAtaB: PublicKey;
+  let takerAtaA: PublicKey;
+  let takerAtaB: PublicKey;
+
+  const depositAmount = new anchor.BN(1000 * 10 ** 6);
+  const receiveAmount = new anchor.BN(500 * 10 ** 6);
+
+  const findEscrow = (seed: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("arbitrated"),
+        maker.publicKey.toBuffer(),
+        seed.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    )[0];
+
+  const isClosed = async (address: PublicKey) =>
+    (await provider.connection.getAccountInfo(address)) === null;
+
+  // Make an arbitrated escrow and have the taker fund it
+  const makeAndFund = async (seed: anchor.BN) => {
+    const escrow = findEscrow(seed);
+    const vaultA = getAssociatedTokenAddressSync(mintA, escrow, true);
+    const vaultB = getAssociatedTokenAddressSync(mintB, escrow, true);
+
+    await program.methods
+      .makeArbitrated(seed, receiveAmount, depositAmount, arbiter.publicKey)
+      .accounts({
+        maker: maker.publicKey,
+        escrow,
+        mintA,
+        mintB,
+        makerAtaA,
+        vaultA,
+        vaultB,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([maker])
+      .rpc();
+
+    await program.methods
+      .fundArbitrated()
+      .accounts({
+        taker: taker.publicKey,
+        escrow,
+        mintB,
+        takerAtaB,
+        vaultB,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .signers([taker])
+      .rpc();
+
+    return { escrow, vaultA, vaultB };
+  };
+
+  const releaseAccounts = (authority: PublicKey, escrow: PublicKey, vaultA: PublicKey, vaultB: PublicKey) => ({
+    authority,
+    maker: maker.publicKey,
+    taker: taker.publicKey,
+    escrow,
+    mintA,
+    mintB,
+    vaultA,
+    vaultB,
+    takerAtaA,
+    makerAtaB,
+    associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+    tokenProgram: TOKEN_PROGRAM_ID,
+    systemProgram: SystemProgram.programId,
+  });
+
+  before(async () => {
+    maker = Keypair.generate();
+    taker = Keypair.generate();
+    arbiter = Keypair.generate();
+
+    for (const kp of [maker, taker, arbiter]) {
+      const sig = await provider.connection.requestAirdrop(
+        kp.publicKey,
+        2 * anchor.web3.LAMPORTS_PER_SOL
+      );
+      await provider.connection.confirmTransaction(sig);
+    }
+
+    mintA = await createMint(provider.connection, maker, maker.publicKey, null, 6);
+    mintB = await createMint(provider.connection, taker, taker.publicKey, null, 6);
+
+    makerAtaA = await createAssociatedTokenAccount(provider.connection, maker, mintA, maker.publicKey);
+    makerAtaB = getAssociatedTokenAddressSync(mintB, maker.publicKey);
+    takerAtaA = getAssociatedTokenAddressSync(mintA, taker.publicKey);
+    takerAtaB = await createAssociatedTokenAccount(provider.connection, taker, mintB, taker.publicKey);
+
+    await mintTo(provider.connection, maker, mintA, makerAtaA, maker, depositAmount.toNumber() * 3);
+    await mintTo(provider.connection, taker, mintB, takerAtaB, taker, receiveAmount.toNumber() * 3);
+  });
+
+  it("Release: rejects a party before both have confirmed", async () => {
+    const { escrow, vaultA, vaultB } = await makeAndFund(new anchor.BN(1));
+
+    await program.methods
+      .confirmArbitrated()
+      .accounts({ party: maker.publicKey, escrow })
+      .signers([maker])
+      .rpc();
+
+    let failed = false;
+    try {
+      await program.methods
+        .releaseArbitrated()
+        .accounts(releaseAccounts(maker.publicKey, escrow, vaultA, vaultB))
+        .signers([maker])
+        .rpc();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("NotConfirmed");
+    }
+    expect(failed).to.equal(true);
+  });
+
+  it("Release: swaps both legs once maker and taker confirm", async () => {
+    const seed = new anchor.BN(2);
+    const { escrow, vaultA, vaultB } = await makeAndFund(seed);
+
+    for (const party of [maker, taker]) {
+      await program.methods
+        .confirmArbitrated()
+        .accounts({ party: party.publicKey, escrow })
+        .signers([party])
+        .rpc();
+    }
+
+    await program.methods
+      .releaseArbitrated()
+      .accounts(releaseAccounts(taker.publicKey, escrow, vaultA, vaultB))
+      .signers([taker])
+      .rpc();
+
+    expect(Number((await getAccount(provider.connection, takerAtaA)).amount)).to.equal(depositAmount.toNumber());
+    expect(Number((await getAccount(provider.connection, makerAtaB)).amount)).to.equal(receiveAmount.toNumber());
+    expect(await isClosed(vaultA)).to.equal(true);
+    expect(await isClosed(vaultB)).to.equal(true);
+    expect(await isClosed(escrow)).to.equal(true);
+  });
+
+  it("Refund: arbiter returns both legs of a funded escrow", async () => {
+    const { escrow, vaultA, vaultB } = await makeAndFund(new anchor.BN(3));
+
+    const makerABefore = Number((await getAccount(provider.connection, makerAtaA)).amount);
+    const takerBBefore = Number((await getAccount(provider.connection, takerAtaB)).amount);
+
+    await program.methods
+      .refundArbitrated()
+      .accounts({
+        authority: arbiter.publicKey,
+        maker: maker.publicKey,
+        escrow,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        makerAtaA,
+        takerAtaB,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([arbiter])
+      .rpc();
+
+    const makerAAfter = Number((await getAccount(provider.connection, makerAtaA)).amount);
+    const takerBAfter = Number((await getAccount(provider.connection, takerAtaB)).amount);
+    expect(makerAAfter - makerABefore).to.equal(depositAmount.toNumber());
+    expect(takerBAfter - takerBBefore).to.equal(receiveAmount.toNumber());
+    expect(await isClosed(escrow)).to.equal(true);
+  });
+
+  it("Sweep: keeps an open escrow's vaults, recovers strays once it closes", async () => {
+    const seed = new anchor.BN(4);
+    const { escrow, vaultA, vaultB } = await makeAndFund(seed);
+    const sweep = (mint: PublicKey, tokenAccount: PublicKey, makerTokenAccount: PublicKey) =>
+      program.methods
+        .sweep(seed, { arbitrated: {} })
+        .accounts({
+          maker: maker.publicKey,
+          escrow,
+          mint,
+          tokenAccount,
+          makerTokenAccount,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+
+    for (const [mint, vault, makerAta] of [
+      [mintA, vaultA, makerAtaA],
+      [mintB, vaultB, makerAtaB],
+    ]) {
+      let failed = false;
+      try {
+        await sweep(mint, vault, makerAta);
+      } catch (err: any) {
+        failed = true;
+        expect(err.error.errorCode.code).to.equal("EscrowStillOpen");
+      }
+      expect(failed).to.equal(true);
+    }
+
+    await program.methods
+      .refundArbitrated()
+      .accounts({
+        authority: arbiter.publicKey,
+        maker: maker.publicKey,
+        escrow,
+        mintA,
+        mintB,
+        vaultA,
+        vaultB,
+        makerAtaA,
+        takerAtaB,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([arbiter])
+      .rpc();
+
+    // Token B sent to the closed escrow's address
+    const stray = await createAssociatedTokenAccount(
+      provider.connection,
+      taker,
+      mintB,
+      escrow,
+      undefined,
+      TOKEN_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      true
+    );
+    await mintTo(provider.connection, taker, mintB, stray, taker, 100);
+    const makerBBefore = await getAccount(provider.connection, makerAtaB)
+      .then((account) => Number(account.amount))
+      .catch(() => 0);
+
+    await sweep(mintB, stray, makerAtaB);
+
+    const makerBAfter = Number((await getAccount(provider.connection, makerAtaB)).amount);
+    expect(makerBAfter - makerBBefore).to.equal(100);
+    expect(await isClosed(stray)).to.equal(true);
+  });
+});
@@ -7,6 +7,7 @@ import {
   getAssociatedTokenAddressSync,
   createMint,
   createAccount,
+  createAssociatedTokenAccount,
   mintTo,
   getAccount,
   getMint,
@@ -142,7 +143,7 @@ describe("blueshift_anchor_escrow", () => {
     const makerBalanceBefore = Number(makerAtaABefore.amount);
 
     const tx = await program.methods
-      .make(seed, receiveAmount, depositAmount)
+      .make(seed, receiveAmount, depositAmount, null)
       .accounts({
         maker: maker.publicKey,
         escrow: escrow,
@@ -153,6 +154,7 @@ describe("blueshift_anchor_escrow", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        payer: null,
       })
       .signers([maker])
       .rpc();
@@ -176,6 +178,90 @@ describe("blueshift_anchor_escrow", () => {
     expect(escrowAccount.seed.toString()).to.equal(seed.toString());
   });
 
+  it("Quote: Returns the escrow terms and vault balance", async () => {
+    const quote = await program.methods
+      .quote()
+      .accounts({
+        escrow: escrow,
+        mintA: mintA,
+        vault: vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .view();
+
+    expect(quote.seed.toString()).to.equal(seed.toString());
+    expect(quote.maker.toString()).to.equal(maker.publicKey.toString());
+    expect(quote.mintA.toString()).to.equal(mintA.toString());
+    expect(quote.mintB.toString()).to.equal(mintB.toString());
+    expect(quote.vaultAmount.toString()).to.equal(depositAmount.toString());
+    expect(quote.receive.toString()).to.equal(receiveAmount.toString());
+    // 500 token B for 1000 token A, scaled by 1e9
+    expect(quote.price.toString()).to.equal("500000000");
+  });
+
+  it("Quote: Rejects a vault that is not the escrow's", async () => {
+    let failed = false;
+    try {
+      await program.methods
+        .quote()
+        .accounts({
+          escrow: escrow,
+          mintA: mintA,
+          vault: makerAtaA,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .view();
+    } catch (err: any) {
+      failed = true;
+      // view() simulates, so the Anchor error is only in the program logs
+      const logs: string[] = err.simulationResponse?.logs ?? err.logs ?? [];
+      expect(logs.join("\n")).to.include("ConstraintAssociated");
+    }
+    expect(failed).to.equal(true);
+  });
+
+  it("Take: Rejects terms that differ from the escrow", async () => {
+    const takeWith = (expectedReceive: anchor.BN | null, minAmountA: anchor.BN | null) =>
+      program.methods
+        .take(expectedReceive, minAmountA)
+        .accounts({
+          taker: taker.publicKey,
+          maker: maker.publicKey,
+          escrow: escrow,
+          rentRecipient: maker.publicKey,
+          mintA: mintA,
+          mintB: mintB,
+          vault: vault,
+          takerAtaA: takerAtaA,
+          takerAtaB: takerAtaB,
+          makerAtaB: makerAtaB,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([taker])
+        .rpc();
+
+    const cases: [anchor.BN | null, anchor.BN | null, string][] = [
+      [receiveAmount.subn(1), null, "UnexpectedReceive"],
+      [null, depositAmount.addn(1), "VaultBelowMinimum"],
+    ];
+    for (const [expectedReceive, minAmountA, code] of cases) {
+      let failed = false;
+      try {
+        await takeWith(expectedReceive, minAmountA);
+      } catch (err: any) {
+        failed = true;
+        expect(err.error.errorCode.code).to.equal(code);
+      }
+      expect(failed).to.equal(true);
+    }
+
+    // Nothing moved
+    const vaultAccount = await getAccount(provider.connection, vault);
+    expect(Number(vaultAccount.amount)).to.equal(depositAmount.toNumber());
+  });
+
   it("Take: Completes the escrow exchange", async () => {
     const takerAtaBBefore = await getAccount(provider.connection, takerAtaB);
     const makerAtaBBefore = await getAccount(provider.connection, makerAtaB);
@@ -183,11 +269,12 @@ describe("blueshift_anchor_escrow", () => {
     const makerBalanceBBefore = Number(makerAtaBBefore.amount || 0);
 
     const tx = await program.methods
-      .take()
+      .take(receiveAmount, depositAmount)
       .accounts({
         taker: taker.publicKey,
         maker: maker.publicKey,
         escrow: escrow,
+        rentRecipient: maker.publicKey,
         mintA: mintA,
         mintB: mintB,
         vault: vault,
@@ -237,7 +324,7 @@ describe("blueshift_anchor_escrow", () => {
     expect(escrowClosed).to.equal(true);
   });
 
-  it("Refund: Allows maker to cancel escrow and get tokens back", async () => {
+  it("Refund: Returns tokens to the maker and rent to the named recipient", async () => {
     // Mint more Token A to maker for the refund test
     await mintTo(
       provider.connection,
@@ -248,7 +335,8 @@ describe("blueshift_anchor_escrow", () => {
       depositAmount.toNumber()
     );
 
-    // First, create a new escrow for refund test
+    // First, create a new escrow for refund test, with its rent refunded to a relayer
+    const relayer = Keypair.generate();
     const refundSeed = new anchor.BN(67890);
     const [refundEscrow] = PublicKey.findProgramAddressSync(
       [
@@ -262,7 +350,7 @@ describe("blueshift_anchor_escrow", () => {
 
     // Make a new escrow
     await program.methods
-      .make(refundSeed, receiveAmount, depositAmount)
+      .make(refundSeed, receiveAmount, depositAmount, relayer.publicKey)
       .accounts({
         maker: maker.publicKey,
         escrow: refundEscrow,
@@ -273,6 +361,7 @@ describe("blueshift_anchor_escrow", () => {
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         tokenProgram: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
+        payer: null,
       })
       .signers([maker])
       .rpc();
@@ -285,20 +374,42 @@ describe("blueshift_anchor_escrow", () => {
     const vaultBeforeRefund = await getAccount(provider.connection, refundVault);
     expect(Number(vaultBeforeRefund.amount)).to.equal(depositAmount.toNumber());
 
-    const refundTx = await program.methods
-      .refund()
-      .accounts({
-        maker: maker.publicKey,
-        escrow: refundEscrow,
-        mintA: mintA,
-        vault: refundVault,
-        makerAtaA: makerAtaA,
-        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
-        systemProgram: SystemProgram.programId,
-      })
-      .signers([maker])
-      .rpc();
+    const escrowAccount = await program.account.escrow.fetch(refundEscrow);
+    expect(escrowAccount.rentRecipient.toString()).to.equal(relayer.publicKey.toString());
+
+    const refundTo = (rentRecipient: PublicKey) =>
+      program.methods
+        .refund()
+        .accounts({
+          maker: maker.publicKey,
+          escrow: refundEscrow,
+          rentRecipient: rentRecipient,
+          mintA: mintA,
+          vault: refundVault,
+          makerAtaA: makerAtaA,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+
+    // The maker can't reclaim rent the relayer paid for
+    let failed = false;
+    try {
+      await refundTo(maker.publicKey);
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("InvalidRentRecipient");
+    }
+    expect(failed).to.equal(true);
+
+    const rent =
+      (await provider.connection.getBalance(refundEscrow)) +
+      (await provider.connection.getBalance(refundVault));
+    const relayerBalanceBefore = await provider.connection.getBalance(relayer.publicKey);
+
+    const refundTx = await refundTo(relayer.publicKey);
 
     console.log("Refund transaction signature:", refundTx);
 
@@ -307,6 +418,10 @@ describe("blueshift_anchor_escrow", () => {
     const makerBalanceAfterRefund = Number(makerAtaAAfterRefund.amount);
     expect(makerBalanceAfterRefund - makerBalanceBeforeRefund).to.equal(depositAmount.toNumber());
 
+    // Verify the escrow's and vault's rent went to the relayer
+    const relayerBalanceAfter = await provider.connection.getBalance(relayer.publicKey);
+    expect(relayerBalanceAfter - relayerBalanceBefore).to.equal(rent);
+
     // Verify vault was closed
     let refundVaultClosed = false;
     try {
@@ -327,4 +442,138 @@ describe("blueshift_anchor_escrow", () => {
     }
     expect(refundEscrowClosed).to.equal(true);
   });
+
+  it("Make: A payer funds the escrow for a maker without SOL", async () => {
+    // A fresh maker holding only Token A; `maker` sponsors the trade
+    const sponsored = Keypair.generate();
+    const sponsoredAtaA = await createAccount(provider.connection, maker, mintA, sponsored.publicKey);
+    await mintTo(provider.connection, maker, mintA, sponsoredAtaA, maker, depositAmount.toNumber());
+
+    const sponsoredSeed = new anchor.BN(24680);
+    const [sponsoredEscrow] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        sponsored.publicKey.toBuffer(),
+        sponsoredSeed.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const sponsoredVault = getAssociatedTokenAddressSync(mintA, sponsoredEscrow, true);
+
+    await program.methods
+      .make(sponsoredSeed, receiveAmount, depositAmount, null)
+      .accounts({
+        maker: sponsored.publicKey,
+        escrow: sponsoredEscrow,
+        mintA: mintA,
+        mintB: mintB,
+        makerAtaA: sponsoredAtaA,
+        vault: sponsoredVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        payer: maker.publicKey,
+      })
+      .signers([sponsored, maker])
+      .rpc();
+
+    // The maker only moved tokens; the payer is recorded for the rent refund
+    expect(await provider.connection.getBalance(sponsored.publicKey)).to.equal(0);
+    const vaultAccount = await getAccount(provider.connection, sponsoredVault);
+    expect(Number(vaultAccount.amount)).to.equal(depositAmount.toNumber());
+    const escrowAccount = await program.account.escrow.fetch(sponsoredEscrow);
+    expect(escrowAccount.maker.toString()).to.equal(sponsored.publicKey.toString());
+    expect(escrowAccount.rentRecipient.toString()).to.equal(maker.publicKey.toString());
+  });
+
+  it("Sweep: Recovers tokens sent to a taken escrow's address", async () => {
+    // Token B sent to the first escrow's PDA after it was taken and closed
+    const stray = await createAssociatedTokenAccount(
+      provider.connection,
+      taker,
+      mintB,
+      escrow,
+      undefined,
+      TOKEN_PROGRAM_ID,
+      ASSOCIATED_TOKEN_PROGRAM_ID,
+      true
+    );
+    await mintTo(provider.connection, taker, mintB, stray, taker, 100);
+    const makerAtaBBefore = await getAccount(provider.connection, makerAtaB);
+
+    await program.methods
+      .sweep(seed, { escrow: {} })
+      .accounts({
+        maker: maker.publicKey,
+        escrow: escrow,
+        mint: mintB,
+        tokenAccount: stray,
+        makerTokenAccount: makerAtaB,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([maker])
+      .rpc();
+
+    const makerAtaBAfter = await getAccount(provider.connection, makerAtaB);
+    expect(Number(makerAtaBAfter.amount) - Number(makerAtaBBefore.amount)).to.equal(100);
+    expect(await provider.connection.getAccountInfo(stray)).to.equal(null);
+  });
+
+  it("Sweep: Rejects an open escrow's vault", async () => {
+    const openSeed = new anchor.BN(13579);
+    const [openEscrow] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("escrow"),
+        maker.publicKey.toBuffer(),
+        openSeed.toArrayLike(Buffer, "le", 8),
+      ],
+      program.programId
+    );
+    const openVault = getAssociatedTokenAddressSync(mintA, openEscrow, true);
+
+    await program.methods
+      .make(openSeed, receiveAmount, depositAmount, null)
+      .accounts({
+        maker: maker.publicKey,
+        escrow: openEscrow,
+        mintA: mintA,
+        mintB: mintB,
+        makerAtaA: makerAtaA,
+        vault: openVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        payer: null,
+      })
+      .signers([maker])
+      .rpc();
+
+    let failed = false;
+    try {
+      await program.methods
+        .sweep(openSeed, { escrow: {} })
+        .accounts({
+          maker: maker.publicKey,
+          escrow: openEscrow,
+          mint: mintA,
+          tokenAccount: openVault,
+          makerTokenAccount: makerAtaA,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([maker])
+        .rpc();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("EscrowStillOpen");
+    }
+    expect(failed).to.equal(true);
+
+    // Nothing moved
+    const vaultAccount = await getAccount(provider.connection, openVault);
+    expect(Number(vaultAccount.amount)).to.equal(depositAmount.toNumber());
+  });
 });
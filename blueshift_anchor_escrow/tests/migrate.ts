@@ -0,0 +1,101 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { BlueshiftAnchorEscrow } from "../target/types/blueshift_anchor_escrow";
+import { PublicKey, Keypair, SystemProgram } from "@solana/web3.js";
+import { expect } from "chai";
+import * as fs from "fs";
+import * as path from "path";
+
+describe("blueshift_anchor_escrow: migrate", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.blueshiftAnchorEscrow as Program<BlueshiftAnchorEscrow>;
+
+  // The v1 escrow in tests/fixtures/escrow_v1.json is preloaded by the validator
+  // (see Anchor.toml) and belongs to this maker.
+  const maker = Keypair.fromSecretKey(
+    Uint8Array.from(
+      JSON.parse(fs.readFileSync(path.join(__dirname, "fixtures", "migrate_maker.json"), "utf8"))
+    )
+  );
+  const seed = new anchor.BN(7);
+  const mintA = new PublicKey("Bswb3UyeD1pUTaGiE6WvqwFpJZsQSEY1xhJePCDTHdvp");
+  const mintB = new PublicKey("D2ZcUbtpG5sKq7XLeB4YnpNnTGSptKCxTddoNeydzJQq");
+  const receiveAmount = new anchor.BN(500 * 10 ** 6);
+
+  const V1_LEN = 1 + 113;
+  const V2_LEN = V1_LEN + 1 + 32;
+
+  const escrow = PublicKey.findProgramAddressSync(
+    [Buffer.from("escrow"), maker.publicKey.toBuffer(), seed.toArrayLike(Buffer, "le", 8)],
+    program.programId
+  )[0];
+
+  const migrate = (signer: Keypair) =>
+    program.methods
+      .migrate()
+      .accounts({
+        maker: signer.publicKey,
+        escrow,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([signer])
+      .rpc();
+
+  const expectError = async (promise: Promise<unknown>, code: string) => {
+    let failed = false;
+    try {
+      await promise;
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal(code);
+    }
+    expect(failed).to.equal(true);
+  };
+
+  before(async () => {
+    const sig = await provider.connection.requestAirdrop(
+      maker.publicKey,
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(sig);
+  });
+
+  it("Migrate: fixture is a v1 escrow", async () => {
+    const info = await provider.connection.getAccountInfo(escrow);
+    expect(info.data.length).to.equal(V1_LEN);
+    expect(info.owner.toBase58()).to.equal(program.programId.toBase58());
+  });
+
+  it("Migrate: rejects anyone but the maker", async () => {
+    const other = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(other.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+
+    await expectError(migrate(other), "InvalidMaker");
+  });
+
+  it("Migrate: upgrades a v1 escrow in place", async () => {
+    await migrate(maker);
+
+    const info = await provider.connection.getAccountInfo(escrow);
+    expect(info.data.length).to.equal(V2_LEN);
+    expect(info.lamports).to.be.at.least(
+      await provider.connection.getMinimumBalanceForRentExemption(V2_LEN)
+    );
+
+    const state = await program.account.escrow.fetch(escrow);
+    expect(state.seed.toNumber()).to.equal(seed.toNumber());
+    expect(state.maker.toBase58()).to.equal(maker.publicKey.toBase58());
+    expect(state.mintA.toBase58()).to.equal(mintA.toBase58());
+    expect(state.mintB.toBase58()).to.equal(mintB.toBase58());
+    expect(state.receive.toNumber()).to.equal(receiveAmount.toNumber());
+    expect(state.version).to.equal(2);
+    expect(state.rentRecipient.toBase58()).to.equal(maker.publicKey.toBase58());
+  });
+
+  it("Migrate: rejects an already migrated escrow", async () => {
+    await expectError(migrate(maker), "NotV1Escrow");
+  });
+});
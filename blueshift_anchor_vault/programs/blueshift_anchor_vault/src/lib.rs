@@ -1,5 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{transfer, Transfer};
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{
+    transfer_checked, Mint, TokenAccount, TokenInterface, TransferChecked,
+};
 
 declare_id!("22222222222222222222222222222222222222222222");
 
@@ -8,19 +12,17 @@ pub mod blueshift_anchor_vault {
     use super::*;
 
     pub fn deposit(ctx: Context<VaultAction>, amount: u64) -> Result<()> {
-        // Check if vault is empty
-        require_eq!(
-            ctx.accounts.vault.lamports(),
-            0,
-            VaultError::VaultAlreadyExists
-        );
-
-        // Ensure amount exceeds rent-exempt minimum
-        require_gt!(
-            amount,
-            Rent::get()?.minimum_balance(0),
-            VaultError::InvalidAmount
-        );
+        // The first deposit must exceed the rent-exempt minimum; top-ups of a
+        // funded vault may be any nonzero amount.
+        if ctx.accounts.vault.lamports() == 0 {
+            require_gt!(
+                amount,
+                Rent::get()?.minimum_balance(0),
+                VaultError::InvalidAmount
+            );
+        } else {
+            require_gt!(amount, 0, VaultError::InvalidAmount);
+        }
 
         transfer(
             CpiContext::new(
@@ -33,20 +35,100 @@ pub mod blueshift_anchor_vault {
             amount,
         )?;
 
-        // deposit logic
-        Ok(())
+        VaultState::update_if_initialized(ctx.accounts.state.as_deref(), |state| {
+            state.total_deposited = state
+                .total_deposited
+                .checked_add(amount)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            state.last_active = Clock::get()?.unix_timestamp;
+            Ok(())
+        })
     }
 
     pub fn withdraw(ctx: Context<VaultAction>) -> Result<()> {
         // Check if vault has any lamports
         require_neq!(ctx.accounts.vault.lamports(), 0, VaultError::InvalidAmount);
 
+        let lamports = ctx.accounts.vault.lamports();
+        VaultState::update_if_initialized(ctx.accounts.state.as_deref(), |state| {
+            // With a delay set, lamports leave only via `request_withdraw` and
+            // `execute_withdraw`.
+            require_eq!(state.delay, 0, VaultError::WithdrawalLocked);
+            state.total_withdrawn = state
+                .total_withdrawn
+                .checked_add(lamports)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            state.last_active = Clock::get()?.unix_timestamp;
+            Ok(())
+        })?;
+
         // withdraw logic
         // Create PDA signer seeds
         let signer_key = ctx.accounts.signer.key();
         let signer_seeds = &[b"vault", signer_key.as_ref(), &[ctx.bumps.vault]];
 
-        // Transfer all lamports from vault to signer
+        // Transfer all lamports from vault to the recipient, or the signer
+        let to = match &ctx.accounts.recipient {
+            Some(recipient) => recipient.to_account_info(),
+            None => ctx.accounts.signer.to_account_info(),
+        };
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to,
+                },
+                &[&signer_seeds[..]],
+            ),
+            lamports,
+        )
+    }
+
+    /// Create the signer's vault state account. Its totals count every deposit
+    /// and withdrawal that passes it from now on.
+    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.state.set_inner(VaultState {
+            owner: ctx.accounts.signer.key(),
+            created_at: now,
+            total_deposited: 0,
+            total_withdrawn: 0,
+            spender: Pubkey::default(),
+            spending_limit: 0,
+            period: 0,
+            window_start: 0,
+            window_spent: 0,
+            beneficiary: Pubkey::default(),
+            inactivity_period: 0,
+            last_active: now,
+            delay: 0,
+            pending_amount: 0,
+            unlock_at: 0,
+            vault_bump: ctx.bumps.vault,
+            bump: ctx.bumps.state,
+        });
+
+        Ok(())
+    }
+
+    /// Drain the vault to the signer and close its state account. With a
+    /// withdrawal delay set, the vault must first be emptied through
+    /// `request_withdraw` and `execute_withdraw`.
+    pub fn close(ctx: Context<Close>) -> Result<()> {
+        let lamports = ctx.accounts.vault.lamports();
+        if lamports == 0 {
+            return Ok(());
+        }
+        require_eq!(ctx.accounts.state.delay, 0, VaultError::WithdrawalLocked);
+
+        let signer_key = ctx.accounts.signer.key();
+        let signer_seeds = &[
+            b"vault",
+            signer_key.as_ref(),
+            &[ctx.accounts.state.vault_bump],
+        ];
+
         transfer(
             CpiContext::new_with_signer(
                 ctx.accounts.system_program.to_account_info(),
@@ -56,9 +138,319 @@ pub mod blueshift_anchor_vault {
                 },
                 &[&signer_seeds[..]],
             ),
-            ctx.accounts.vault.lamports(),
+            lamports,
+        )
+    }
+
+    /// Let `spender` take up to `limit` lamports per `period` seconds from the
+    /// signer's vault on its own, starting a fresh window. A zero limit turns
+    /// spending off; once a withdrawal delay is set, spending is unavailable and
+    /// only that is allowed.
+    pub fn set_spending_limit(
+        ctx: Context<SetSpendingLimit>,
+        spender: Pubkey,
+        limit: u64,
+        period: i64,
+    ) -> Result<()> {
+        if limit != 0 {
+            require_gt!(period, 0, VaultError::InvalidAmount);
+        }
+
+        let state = &mut ctx.accounts.state;
+        // `spend` is refused under a withdrawal delay.
+        if state.delay != 0 {
+            require_eq!(limit, 0, VaultError::WithdrawalLocked);
+        }
+        state.spender = spender;
+        state.spending_limit = limit;
+        state.period = period;
+        state.window_start = Clock::get()?.unix_timestamp;
+        state.window_spent = 0;
+        state.last_active = state.window_start;
+
+        Ok(())
+    }
+
+    /// The vault's spender takes `amount` lamports, to the recipient if one is
+    /// passed. Past the window's limit the owner must co-sign. Counts as a
+    /// withdrawal in the state's totals. Refused while a withdrawal delay is set.
+    pub fn spend(ctx: Context<Spend>, amount: u64) -> Result<()> {
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+
+        let remaining = ctx
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(VaultError::InvalidAmount)?;
+        if remaining != 0 {
+            require_gte!(
+                remaining,
+                Rent::get()?.minimum_balance(0),
+                VaultError::InvalidAmount
+            );
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.state;
+        // As with `withdraw`, a delay leaves `request_withdraw` as the only way out.
+        require_eq!(state.delay, 0, VaultError::WithdrawalLocked);
+        if now >= state.window_start.saturating_add(state.period) {
+            state.window_start = now;
+            state.window_spent = 0;
+        }
+        let spent = state
+            .window_spent
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if spent > state.spending_limit && !ctx.accounts.owner.is_signer {
+            return err!(VaultError::SpendingLimitExceeded);
+        }
+        state.window_spent = spent;
+        // The owner co-signing is owner activity; the spender alone is not.
+        if ctx.accounts.owner.is_signer {
+            state.last_active = now;
+        }
+        state.total_withdrawn = state
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+
+        let owner_key = ctx.accounts.owner.key();
+        let signer_seeds = &[
+            b"vault",
+            owner_key.as_ref(),
+            &[ctx.accounts.state.vault_bump],
+        ];
+        let to = match &ctx.accounts.recipient {
+            Some(recipient) => recipient.to_account_info(),
+            None => ctx.accounts.spender.to_account_info(),
+        };
+
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to,
+                },
+                &[&signer_seeds[..]],
+            ),
+            amount,
+        )
+    }
+
+    /// Let `beneficiary` claim the signer's vault once `inactivity_period` seconds
+    /// pass without owner activity (deposits, withdrawals and settings that pass
+    /// the state). A zero period turns recovery off; calling this again with the
+    /// same values just marks the owner active. Once a withdrawal delay is set,
+    /// recovery is unavailable and only turning it off is allowed.
+    pub fn set_beneficiary(
+        ctx: Context<SetBeneficiary>,
+        beneficiary: Pubkey,
+        inactivity_period: i64,
+    ) -> Result<()> {
+        require_gte!(inactivity_period, 0, VaultError::InvalidAmount);
+
+        let state = &mut ctx.accounts.state;
+        // `claim` is refused under a withdrawal delay.
+        if state.delay != 0 {
+            require_eq!(inactivity_period, 0, VaultError::WithdrawalLocked);
+        }
+        state.beneficiary = beneficiary;
+        state.inactivity_period = inactivity_period;
+        state.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
+    /// The beneficiary drains an inactive owner's vault and closes its state. Like
+    /// `close`, refused while a withdrawal delay is set and the vault holds lamports.
+    pub fn claim(ctx: Context<Claim>) -> Result<()> {
+        let state = &ctx.accounts.state;
+        require!(
+            state.inactivity_period > 0
+                && Clock::get()?.unix_timestamp
+                    >= state.last_active.saturating_add(state.inactivity_period),
+            VaultError::OwnerStillActive
+        );
+
+        let lamports = ctx.accounts.vault.lamports();
+        if lamports == 0 {
+            return Ok(());
+        }
+        require_eq!(state.delay, 0, VaultError::WithdrawalLocked);
+
+        let owner_key = ctx.accounts.owner.key();
+        let signer_seeds = &[b"vault", owner_key.as_ref(), &[state.vault_bump]];
+
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.beneficiary.to_account_info(),
+                },
+                &[&signer_seeds[..]],
+            ),
+            lamports,
+        )
+    }
+
+    /// Send `amount` tokens of `mint` (SPL Token or Token-2022) to the signer's
+    /// token vault, creating the vault's associated token account if needed.
+    pub fn deposit_token(ctx: Context<TokenVaultAction>, amount: u64) -> Result<()> {
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+
+        transfer_checked(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.signer_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.vault_token_account.to_account_info(),
+                    authority: ctx.accounts.signer.to_account_info(),
+                },
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
         )?;
 
+        VaultState::update_if_initialized(ctx.accounts.state.as_deref(), |state| {
+            state.last_active = Clock::get()?.unix_timestamp;
+            Ok(())
+        })
+    }
+
+    /// Take `amount` tokens, or all of them, from the signer's token vault, to the
+    /// recipient's token account if one is passed.
+    pub fn withdraw_token(ctx: Context<TokenVaultAction>, amount: Option<u64>) -> Result<()> {
+        let balance = ctx.accounts.vault_token_account.amount;
+        let amount = amount.unwrap_or(balance);
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+        require_gte!(balance, amount, VaultError::InvalidAmount);
+
+        let signer_key = ctx.accounts.signer.key();
+        let mint_key = ctx.accounts.mint.key();
+        let signer_seeds = &[
+            b"vault",
+            signer_key.as_ref(),
+            mint_key.as_ref(),
+            &[ctx.bumps.vault],
+        ];
+
+        let to = match &ctx.accounts.recipient_token_account {
+            Some(recipient) => recipient.to_account_info(),
+            None => ctx.accounts.signer_token_account.to_account_info(),
+        };
+        transfer_checked(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                TransferChecked {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to,
+                    authority: ctx.accounts.vault.to_account_info(),
+                },
+                &[&signer_seeds[..]],
+            ),
+            amount,
+            ctx.accounts.mint.decimals,
+        )?;
+
+        VaultState::update_if_initialized(ctx.accounts.state.as_deref(), |state| {
+            state.last_active = Clock::get()?.unix_timestamp;
+            Ok(())
+        })
+    }
+
+    /// Hold the signer's withdrawals for `delay` seconds: from now on lamports
+    /// leave the vault only through `request_withdraw` and, once the delay has
+    /// passed, `execute_withdraw`. The delay can be raised but never lowered.
+    pub fn set_timelock(ctx: Context<TimelockAction>, delay: i64) -> Result<()> {
+        require_gt!(delay, 0, VaultError::InvalidAmount);
+
+        let state = &mut ctx.accounts.state;
+        require_gte!(delay, state.delay, VaultError::WithdrawalLocked);
+        state.delay = delay;
+        state.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+
+    /// Start the delay for withdrawing `amount`, replacing any pending request.
+    pub fn request_withdraw(ctx: Context<TimelockAction>, amount: u64) -> Result<()> {
+        require_gt!(amount, 0, VaultError::InvalidAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.state;
+        require_neq!(state.delay, 0, VaultError::TimelockNotSet);
+        state.pending_amount = amount;
+        state.unlock_at = now.saturating_add(state.delay);
+        state.last_active = now;
+
+        Ok(())
+    }
+
+    /// Pay the pending amount from the vault to the signer once unlocked. As with
+    /// `spend`, whatever is left must stay rent-exempt.
+    pub fn execute_withdraw(ctx: Context<ExecuteWithdraw>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let state = &mut ctx.accounts.state;
+        let amount = state.pending_amount;
+        require_neq!(amount, 0, VaultError::NoPendingWithdrawal);
+        require_gte!(now, state.unlock_at, VaultError::WithdrawalLocked);
+
+        let remaining = ctx
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(VaultError::InvalidAmount)?;
+        if remaining != 0 {
+            require_gte!(
+                remaining,
+                Rent::get()?.minimum_balance(0),
+                VaultError::InvalidAmount
+            );
+        }
+
+        state.pending_amount = 0;
+        state.unlock_at = 0;
+        state.total_withdrawn = state
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        state.last_active = now;
+
+        let signer_key = ctx.accounts.signer.key();
+        let signer_seeds = &[
+            b"vault",
+            signer_key.as_ref(),
+            &[ctx.accounts.state.vault_bump],
+        ];
+
+        transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.vault.to_account_info(),
+                    to: ctx.accounts.signer.to_account_info(),
+                },
+                &[&signer_seeds[..]],
+            ),
+            amount,
+        )
+    }
+
+    /// Drop the pending withdrawal request.
+    pub fn cancel_withdraw(ctx: Context<TimelockAction>) -> Result<()> {
+        let state = &mut ctx.accounts.state;
+        require_neq!(state.pending_amount, 0, VaultError::NoPendingWithdrawal);
+        state.pending_amount = 0;
+        state.unlock_at = 0;
+        state.last_active = Clock::get()?.unix_timestamp;
+
         Ok(())
     }
 }
@@ -74,12 +466,286 @@ pub struct VaultAction<'info> {
     )]
     pub vault: SystemAccount<'info>,
     pub system_program: Program<'info, System>,
+    /// CHECK: the vault state PDA, optional until `initialize` creates it;
+    /// records the deposit or withdrawal from then on.
+    #[account(
+        mut,
+        seeds = [b"state", signer.key().as_ref()],
+        bump,
+    )]
+    pub state: Option<UncheckedAccount<'info>>,
+    /// Where `withdraw` sends the lamports instead of the signer; unused by
+    /// `deposit`.
+    #[account(mut)]
+    pub recipient: Option<SystemAccount<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        seeds = [b"vault", signer.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        init,
+        payer = signer,
+        space = VaultState::INIT_SPACE + VaultState::DISCRIMINATOR.len(),
+        seeds = [b"state", signer.key().as_ref()],
+        bump,
+    )]
+    pub state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct Close<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", signer.key().as_ref()],
+        bump = state.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = signer,
+        seeds = [b"state", signer.key().as_ref()],
+        bump = state.bump,
+    )]
+    pub state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetSpendingLimit<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", signer.key().as_ref()],
+        bump = state.bump,
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+#[derive(Accounts)]
+pub struct Spend<'info> {
+    #[account(mut)]
+    pub spender: Signer<'info>,
+    /// CHECK: the vault's owner, named by `state`; must sign only for spends past
+    /// the limit.
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = state.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", owner.key().as_ref()],
+        bump = state.bump,
+        has_one = owner,
+        has_one = spender,
+    )]
+    pub state: Account<'info, VaultState>,
+    /// Where the lamports go instead of the spender.
+    #[account(mut)]
+    pub recipient: Option<SystemAccount<'info>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetBeneficiary<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", signer.key().as_ref()],
+        bump = state.bump,
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    #[account(mut)]
+    pub beneficiary: Signer<'info>,
+    /// CHECK: the vault's owner, named by `state`.
+    pub owner: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", owner.key().as_ref()],
+        bump = state.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        close = beneficiary,
+        seeds = [b"state", owner.key().as_ref()],
+        bump = state.bump,
+        has_one = owner,
+        has_one = beneficiary,
+    )]
+    pub state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+/// A token vault: the PDA [b"vault", signer, mint] owns an associated token
+/// account for `mint` and holds nothing itself.
+#[derive(Accounts)]
+pub struct TokenVaultAction<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(mint::token_program = token_program)]
+    pub mint: InterfaceAccount<'info, Mint>,
+    #[account(
+        seeds = [b"vault", signer.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = signer,
+        associated_token::token_program = token_program,
+    )]
+    pub signer_token_account: InterfaceAccount<'info, TokenAccount>,
+    #[account(
+        init_if_needed,
+        payer = signer,
+        associated_token::mint = mint,
+        associated_token::authority = vault,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the vault state PDA, as for `VaultAction`; records the signer's
+    /// activity once `initialize` created it.
+    #[account(
+        mut,
+        seeds = [b"state", signer.key().as_ref()],
+        bump,
+    )]
+    pub state: Option<UncheckedAccount<'info>>,
+    /// Where `withdraw_token` sends the tokens instead of the signer's token
+    /// account; unused by `deposit_token`.
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program,
+    )]
+    pub recipient_token_account: Option<InterfaceAccount<'info, TokenAccount>>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TimelockAction<'info> {
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", signer.key().as_ref()],
+        bump = state.bump,
+    )]
+    pub state: Account<'info, VaultState>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteWithdraw<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault", signer.key().as_ref()],
+        bump = state.vault_bump,
+    )]
+    pub vault: SystemAccount<'info>,
+    #[account(
+        mut,
+        seeds = [b"state", signer.key().as_ref()],
+        bump = state.bump,
+    )]
+    pub state: Account<'info, VaultState>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Optional bookkeeping for a vault, at [b"state", owner]. Once created, clients
+/// pass it to every deposit and withdrawal. It also holds the withdrawal delay set by
+/// `set_timelock`, under which `withdraw`, `spend` and `claim` are refused.
+#[account]
+#[derive(InitSpace)]
+pub struct VaultState {
+    pub owner: Pubkey,
+    /// Unix timestamp of `initialize`.
+    pub created_at: i64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    /// Key allowed to `spend` up to `spending_limit` per window.
+    pub spender: Pubkey,
+    /// Lamports `spender` may take per window alone; 0 when spending is off.
+    pub spending_limit: u64,
+    /// Window length in seconds.
+    pub period: i64,
+    /// Unix timestamp the current window began.
+    pub window_start: i64,
+    /// Lamports spent in the current window.
+    pub window_spent: u64,
+    /// Key allowed to `claim` the vault after `inactivity_period`.
+    pub beneficiary: Pubkey,
+    /// Seconds of owner inactivity before the beneficiary can claim; 0 when
+    /// recovery is off.
+    pub inactivity_period: i64,
+    /// Unix timestamp of the owner's last action that passed this state, token
+    /// vault ones included.
+    pub last_active: i64,
+    /// Seconds between a withdrawal request and when it can execute; 0 when
+    /// withdrawals are immediate.
+    pub delay: i64,
+    /// Lamports requested; 0 when no withdrawal is pending.
+    pub pending_amount: u64,
+    /// Unix timestamp from which the pending withdrawal can execute.
+    pub unlock_at: i64,
+    /// Bump of the vault PDA [b"vault", owner].
+    pub vault_bump: u8,
+    pub bump: u8,
+}
+
+impl VaultState {
+    /// Apply `update` to the vault state at `state`, the [b"state", owner] PDA,
+    /// if it is passed and the owner has initialized it; leave the uninitialized
+    /// PDA untouched.
+    pub fn update_if_initialized(
+        state: Option<&AccountInfo>,
+        update: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        let Some(state) = state.filter(|state| state.owner == &crate::ID) else {
+            return Ok(());
+        };
+        let mut vault_state = Self::try_deserialize(&mut &state.try_borrow_data()?[..])?;
+        update(&mut vault_state)?;
+        vault_state.try_serialize(&mut &mut state.try_borrow_mut_data()?[..])
+    }
 }
 
 #[error_code]
 pub enum VaultError {
+    /// No longer returned: deposits top up an existing vault. Kept so later error
+    /// codes don't shift.
     #[msg("Vault already exists")]
     VaultAlreadyExists,
     #[msg("Invalid amount")]
     InvalidAmount,
+    #[msg("Withdrawal is still time-locked")]
+    WithdrawalLocked,
+    #[msg("No withdrawal is pending")]
+    NoPendingWithdrawal,
+    #[msg("Spend exceeds the spending limit without the owner's signature")]
+    SpendingLimitExceeded,
+    #[msg("The owner has been active within the inactivity period")]
+    OwnerStillActive,
+    #[msg("No withdrawal delay is set")]
+    TimelockNotSet,
 }
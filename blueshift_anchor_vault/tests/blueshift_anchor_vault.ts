@@ -1,16 +1,591 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
 import { BlueshiftAnchorVault } from "../target/types/blueshift_anchor_vault";
+import {
+  TOKEN_2022_PROGRAM_ID,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+} from "@solana/spl-token";
+import {
+  Keypair,
+  LAMPORTS_PER_SOL,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+} from "@solana/web3.js";
+import { expect } from "chai";
 
 describe("blueshift_anchor_vault", () => {
   // Configure the client to use the local cluster.
-  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
   const program = anchor.workspace.blueshiftAnchorVault as Program<BlueshiftAnchorVault>;
 
+  const signer = provider.wallet.publicKey;
+  const [vault] = PublicKey.findProgramAddressSync(
+    [Buffer.from("vault"), signer.toBuffer()],
+    program.programId
+  );
+  const [state] = PublicKey.findProgramAddressSync(
+    [Buffer.from("state"), signer.toBuffer()],
+    program.programId
+  );
+
   it("Is initialized!", async () => {
     // Add your test here.
     const tx = await program.methods.initialize().rpc();
     console.log("Your transaction signature", tx);
   });
+
+  it("Deposit and withdraw add to the vault state's totals", async () => {
+    const before = await program.account.vaultState.fetch(state);
+    expect(before.owner.toBase58()).to.equal(signer.toBase58());
+    expect(before.totalDeposited.toNumber()).to.equal(0);
+
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ signer, vault, state })
+      .rpc();
+    // A top-up of an already funded vault.
+    await program.methods
+      .deposit(new anchor.BN(1000))
+      .accountsPartial({ signer, vault, state })
+      .rpc();
+
+    let account = await program.account.vaultState.fetch(state);
+    expect(account.totalDeposited.toNumber()).to.equal(LAMPORTS_PER_SOL + 1000);
+
+    await program.methods.withdraw().accountsPartial({ signer, vault, state }).rpc();
+
+    account = await program.account.vaultState.fetch(state);
+    expect(account.totalWithdrawn.toNumber()).to.equal(LAMPORTS_PER_SOL + 1000);
+    expect(await provider.connection.getBalance(vault)).to.equal(0);
+  });
+
+  it("Deposit: The vault state is optional, but must be the signer's", async () => {
+    // An owner without a vault state leaves it out.
+    const owner = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: signer,
+          toPubkey: owner.publicKey,
+          lamports: 2 * LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const [ownerVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const accounts = { signer: owner.publicKey, vault: ownerVault, state: null };
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial(accounts)
+      .signers([owner])
+      .rpc();
+    await program.methods.withdraw().accountsPartial(accounts).signers([owner]).rpc();
+    expect(await provider.connection.getBalance(ownerVault)).to.equal(0);
+
+    let failed = false;
+    try {
+      await program.methods
+        .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+        .accountsPartial({ signer, vault, state: Keypair.generate().publicKey })
+        .rpc();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("ConstraintSeeds");
+    }
+    expect(failed).to.be.true;
+  });
+
+  it("Deposit: Tops up a funded vault by any amount", async () => {
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+
+    // The first deposit must exceed the rent-exempt minimum.
+    let failed = false;
+    try {
+      await program.methods
+        .deposit(new anchor.BN(rent))
+        .accountsPartial({ signer, vault, state })
+        .rpc();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("InvalidAmount");
+    }
+    expect(failed).to.be.true;
+
+    await program.methods
+      .deposit(new anchor.BN(rent + 1))
+      .accountsPartial({ signer, vault, state })
+      .rpc();
+    for (const amount of [1, rent]) {
+      await program.methods
+        .deposit(new anchor.BN(amount))
+        .accountsPartial({ signer, vault, state })
+        .rpc();
+    }
+    expect(await provider.connection.getBalance(vault)).to.equal(2 * rent + 2);
+
+    await program.methods.withdraw().accountsPartial({ signer, vault, state }).rpc();
+    expect(await provider.connection.getBalance(vault)).to.equal(0);
+  });
+
+  it("Close: Drains the vault and closes its state", async () => {
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ signer, vault, state })
+      .rpc();
+
+    await program.methods.close().accountsPartial({ signer, vault, state }).rpc();
+
+    expect(await provider.connection.getBalance(vault)).to.equal(0);
+    expect(await provider.connection.getAccountInfo(state)).to.be.null;
+  });
+
+  it("Withdraw: Pays a recipient instead of the signer", async () => {
+    const recipient = Keypair.generate().publicKey;
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ signer, vault, state })
+      .rpc();
+
+    await program.methods
+      .withdraw()
+      .accountsPartial({ signer, vault, state, recipient })
+      .rpc();
+
+    expect(await provider.connection.getBalance(recipient)).to.equal(LAMPORTS_PER_SOL);
+    expect(await provider.connection.getBalance(vault)).to.equal(0);
+  });
+
+  it("Spend: Limits the spender per window unless the owner co-signs", async () => {
+    // A separate owner, so the provider wallet paying fees doesn't co-sign.
+    const owner = Keypair.generate();
+    const bot = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: signer,
+          toPubkey: owner.publicKey,
+          lamports: 2 * LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const [ownerVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const [ownerState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("state"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initialize()
+      .accountsPartial({ signer: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ signer: owner.publicKey, vault: ownerVault, state: ownerState })
+      .signers([owner])
+      .rpc();
+    await program.methods
+      .setSpendingLimit(bot.publicKey, new anchor.BN(LAMPORTS_PER_SOL / 10), new anchor.BN(3600))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+
+    const accounts = {
+      spender: bot.publicKey,
+      owner: owner.publicKey,
+      vault: ownerVault,
+      state: ownerState,
+    };
+    await program.methods
+      .spend(new anchor.BN(LAMPORTS_PER_SOL / 20))
+      .accountsPartial(accounts)
+      .signers([bot])
+      .rpc();
+    expect(await provider.connection.getBalance(bot.publicKey)).to.equal(LAMPORTS_PER_SOL / 20);
+
+    let failed = false;
+    try {
+      await program.methods
+        .spend(new anchor.BN(LAMPORTS_PER_SOL / 10))
+        .accountsPartial(accounts)
+        .signers([bot])
+        .rpc();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("SpendingLimitExceeded");
+    }
+    expect(failed).to.be.true;
+
+    // The owner co-signs the larger spend.
+    await program.methods
+      .spend(new anchor.BN(LAMPORTS_PER_SOL / 10))
+      .accountsPartial(accounts)
+      .signers([bot, owner])
+      .rpc();
+    const state = await program.account.vaultState.fetch(ownerState);
+    expect(state.windowSpent.toNumber()).to.equal((3 * LAMPORTS_PER_SOL) / 20);
+  });
+
+  it("Claim: Beneficiary takes the vault after the owner goes inactive", async () => {
+    const owner = Keypair.generate();
+    const heir = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: signer,
+          toPubkey: owner.publicKey,
+          lamports: 2 * LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const [ownerVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const [ownerState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("state"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .initialize()
+      .accountsPartial({ signer: owner.publicKey })
+      .signers([owner])
+      .rpc();
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial({ signer: owner.publicKey, vault: ownerVault, state: ownerState })
+      .signers([owner])
+      .rpc();
+
+    const claim = () =>
+      program.methods
+        .claim()
+        .accountsPartial({
+          beneficiary: heir.publicKey,
+          owner: owner.publicKey,
+          vault: ownerVault,
+          state: ownerState,
+        })
+        .signers([heir])
+        .rpc();
+
+    await program.methods
+      .setBeneficiary(heir.publicKey, new anchor.BN(3600))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+    let failed = false;
+    try {
+      await claim();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("OwnerStillActive");
+    }
+    expect(failed).to.be.true;
+
+    // With a one-second period, wait it out.
+    await program.methods
+      .setBeneficiary(heir.publicKey, new anchor.BN(1))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await claim();
+
+    expect(await provider.connection.getBalance(ownerVault)).to.equal(0);
+    expect(await provider.connection.getBalance(heir.publicKey)).to.be.greaterThan(
+      LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getAccountInfo(ownerState)).to.be.null;
+  });
+
+  it("Timelock: Holds a requested withdrawal until the delay passes", async () => {
+    const owner = Keypair.generate();
+    const bot = Keypair.generate();
+    const heir = Keypair.generate();
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: signer,
+          toPubkey: owner.publicKey,
+          lamports: 2 * LAMPORTS_PER_SOL,
+        })
+      )
+    );
+    const [ownerVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const [ownerState] = PublicKey.findProgramAddressSync(
+      [Buffer.from("state"), owner.publicKey.toBuffer()],
+      program.programId
+    );
+    const accounts = { signer: owner.publicKey, vault: ownerVault, state: ownerState };
+
+    await program.methods.initialize().accountsPartial(accounts).signers([owner]).rpc();
+    await program.methods
+      .deposit(new anchor.BN(LAMPORTS_PER_SOL))
+      .accountsPartial(accounts)
+      .signers([owner])
+      .rpc();
+    // A spender and a beneficiary, named before the delay is set.
+    await program.methods
+      .setSpendingLimit(bot.publicKey, new anchor.BN(LAMPORTS_PER_SOL / 10), new anchor.BN(3600))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+    await program.methods
+      .setBeneficiary(heir.publicKey, new anchor.BN(1))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+    await program.methods
+      .setTimelock(new anchor.BN(1))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+
+    const expectError = async (call: Promise<unknown>, code: string) => {
+      let failed = false;
+      try {
+        await call;
+      } catch (err: any) {
+        failed = true;
+        expect(err.error.errorCode.code).to.equal(code);
+      }
+      expect(failed).to.be.true;
+    };
+    const request = () =>
+      program.methods
+        .requestWithdraw(new anchor.BN(LAMPORTS_PER_SOL / 2))
+        .accountsPartial({ signer: owner.publicKey, state: ownerState })
+        .signers([owner])
+        .rpc();
+    const execute = () =>
+      program.methods.executeWithdraw().accountsPartial(accounts).signers([owner]).rpc();
+
+    // With a delay set, the vault can't be drained in one go, nor spent from.
+    await expectError(
+      program.methods.withdraw().accountsPartial(accounts).signers([owner]).rpc(),
+      "WithdrawalLocked"
+    );
+    await expectError(
+      program.methods
+        .spend(new anchor.BN(LAMPORTS_PER_SOL / 20))
+        .accountsPartial({
+          spender: bot.publicKey,
+          owner: owner.publicKey,
+          vault: ownerVault,
+          state: ownerState,
+        })
+        .signers([bot])
+        .rpc(),
+      "WithdrawalLocked"
+    );
+
+    await request();
+    const requested = await program.account.vaultState.fetch(ownerState);
+    expect(requested.pendingAmount.toNumber()).to.equal(LAMPORTS_PER_SOL / 2);
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await execute();
+    expect(await provider.connection.getBalance(ownerVault)).to.equal(LAMPORTS_PER_SOL / 2);
+
+    // Raising the delay is allowed; the next request waits the full hour.
+    await program.methods
+      .setTimelock(new anchor.BN(3600))
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+    await request();
+    await expectError(execute(), "WithdrawalLocked");
+
+    // The owner cancels; nothing is left to execute.
+    await program.methods
+      .cancelWithdraw()
+      .accountsPartial({ signer: owner.publicKey, state: ownerState })
+      .signers([owner])
+      .rpc();
+    await expectError(execute(), "NoPendingWithdrawal");
+
+    // The delay can't be lowered to get at the lamports sooner.
+    await expectError(
+      program.methods
+        .setTimelock(new anchor.BN(1))
+        .accountsPartial({ signer: owner.publicKey, state: ownerState })
+        .signers([owner])
+        .rpc(),
+      "WithdrawalLocked"
+    );
+
+    // A spending limit can only be turned off.
+    const setSpendingLimit = (limit: number) =>
+      program.methods
+        .setSpendingLimit(bot.publicKey, new anchor.BN(limit), new anchor.BN(3600))
+        .accountsPartial({ signer: owner.publicKey, state: ownerState })
+        .signers([owner])
+        .rpc();
+    await expectError(setSpendingLimit(LAMPORTS_PER_SOL / 10), "WithdrawalLocked");
+    await setSpendingLimit(0);
+    // Likewise a beneficiary.
+    const setBeneficiary = (period: number) =>
+      program.methods
+        .setBeneficiary(heir.publicKey, new anchor.BN(period))
+        .accountsPartial({ signer: owner.publicKey, state: ownerState })
+        .signers([owner])
+        .rpc();
+    await expectError(setBeneficiary(1), "WithdrawalLocked");
+
+    // Nor can the beneficiary claim it once the owner goes quiet.
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    await expectError(
+      program.methods
+        .claim()
+        .accountsPartial({
+          beneficiary: heir.publicKey,
+          owner: owner.publicKey,
+          vault: ownerVault,
+          state: ownerState,
+        })
+        .signers([heir])
+        .rpc(),
+      "WithdrawalLocked"
+    );
+  });
+
+  it("Token vault: Deposits and withdraws Token-2022 tokens", async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const mint = await createMint(
+      provider.connection,
+      payer,
+      signer,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const signerTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      mint,
+      signer,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      mint,
+      signerTokenAccount.address,
+      signer,
+      1_000_000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const [tokenVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from("vault"), signer.toBuffer(), mint.toBuffer()],
+      program.programId
+    );
+    const vaultTokenAccount = getAssociatedTokenAddressSync(
+      mint,
+      tokenVault,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const accounts = {
+      signer,
+      mint,
+      vault: tokenVault,
+      tokenProgram: TOKEN_2022_PROGRAM_ID,
+    };
+    const balance = async (address: PublicKey) =>
+      Number(
+        (
+          await getAccount(
+            provider.connection,
+            address,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ).amount
+      );
+
+    // The vault state was closed above; token vault actions count as activity
+    // once it exists again.
+    await program.methods.initialize().rpc();
+    const { createdAt } = await program.account.vaultState.fetch(state);
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    await program.methods
+      .depositToken(new anchor.BN(600_000))
+      .accountsPartial(accounts)
+      .rpc();
+    expect(await balance(vaultTokenAccount)).to.equal(600_000);
+    const deposited = await program.account.vaultState.fetch(state);
+    expect(deposited.lastActive.toNumber()).to.be.greaterThan(createdAt.toNumber());
+
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await program.methods
+      .withdrawToken(new anchor.BN(100_000))
+      .accountsPartial(accounts)
+      .rpc();
+    expect(await balance(vaultTokenAccount)).to.equal(500_000);
+    const withdrawn = await program.account.vaultState.fetch(state);
+    expect(withdrawn.lastActive.toNumber()).to.be.greaterThan(
+      deposited.lastActive.toNumber()
+    );
+
+    let failed = false;
+    try {
+      await program.methods
+        .withdrawToken(new anchor.BN(500_001))
+        .accountsPartial(accounts)
+        .rpc();
+    } catch (err: any) {
+      failed = true;
+      expect(err.error.errorCode.code).to.equal("InvalidAmount");
+    }
+    expect(failed).to.be.true;
+
+    // Part of it to another wallet's token account.
+    const recipientTokenAccount = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      payer,
+      mint,
+      Keypair.generate().publicKey,
+      false,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await program.methods
+      .withdrawToken(new anchor.BN(200_000))
+      .accountsPartial({
+        ...accounts,
+        recipientTokenAccount: recipientTokenAccount.address,
+      })
+      .rpc();
+    expect(await balance(recipientTokenAccount.address)).to.equal(200_000);
+
+    await program.methods.withdrawToken(null).accountsPartial(accounts).rpc();
+    expect(await balance(vaultTokenAccount)).to.equal(0);
+    expect(await balance(signerTokenAccount.address)).to.equal(800_000);
+  });
 });
@@ -0,0 +1,352 @@
+//! Instructions for the Anchor and Pinocchio escrow programs, as built by the
+//! `escrow` command-line tool.
+//!
+//! Make, Take and Refund have the same discriminators (0-2) in both programs; from
+//! 3 up the tables differ, so Sweep is 10 in the Anchor program and 11 in the
+//! Pinocchio one. The programs also differ in account order, in how optional
+//! arguments are encoded, and in which token accounts they create. Take and Refund
+//! follow the layout of the escrow account they act on.
+
+use blueshift_escrow_indexer::escrow::ASSOCIATED_TOKEN_PROGRAM_ID;
+use blueshift_escrow_indexer::{Escrow, Layout, Pubkey};
+use solana_instruction::{AccountMeta, Instruction};
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = Pubkey::new_from_array([0; 32]);
+
+/// Derive the escrow PDA: [b"escrow", maker, seed].
+pub fn escrow_address(program_id: &Pubkey, maker: &Pubkey, seed: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"escrow", maker.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+    .0
+}
+
+/// Derive the associated token account of `wallet` for `mint` under `token_program`.
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Create `wallet`'s associated token account for `mint` unless it exists.
+pub fn create_associated_token_account_idempotent(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction::new_with_bytes(
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        &[1],
+        vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(wallet, mint, token_program), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+/// Create an escrow and deposit `amount` token A into its vault.
+pub struct Make {
+    pub program_id: Pubkey,
+    pub layout: Layout,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Token program of mint A.
+    pub token_program: Pubkey,
+    pub seed: u64,
+    pub receive: u64,
+    pub amount: u64,
+    /// Funds the escrow and vault instead of the maker; must also sign.
+    pub payer: Option<Pubkey>,
+    /// Receives the escrow's and vault's rent on close; the payer if `None`.
+    pub rent_recipient: Option<Pubkey>,
+}
+
+impl Make {
+    pub fn escrow(&self) -> Pubkey {
+        escrow_address(&self.program_id, &self.maker, self.seed)
+    }
+
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let escrow = self.escrow();
+        let mut data = vec![0];
+        data.extend_from_slice(&self.seed.to_le_bytes());
+        data.extend_from_slice(&self.receive.to_le_bytes());
+        data.extend_from_slice(&self.amount.to_le_bytes());
+        match (self.layout, self.rent_recipient) {
+            (Layout::Anchor, None) => data.push(0),
+            (Layout::Anchor, Some(rent_recipient)) => {
+                data.push(1);
+                data.extend_from_slice(rent_recipient.as_ref());
+            }
+            (Layout::Pinocchio, None) => {}
+            (Layout::Pinocchio, Some(rent_recipient)) => {
+                data.extend_from_slice(rent_recipient.as_ref())
+            }
+        }
+
+        let mut accounts = vec![
+            AccountMeta::new(self.maker, true),
+            AccountMeta::new(escrow, false),
+            AccountMeta::new_readonly(self.mint_a, false),
+            AccountMeta::new_readonly(self.mint_b, false),
+            AccountMeta::new(
+                associated_token_address(&self.maker, &self.mint_a, &self.token_program),
+                false,
+            ),
+            AccountMeta::new(
+                associated_token_address(&escrow, &self.mint_a, &self.token_program),
+                false,
+            ),
+        ];
+        accounts.extend(match self.layout {
+            Layout::Anchor => [
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+            Layout::Pinocchio => [
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ],
+        });
+        // Both layouts take the payer as an optional last account.
+        if let Some(payer) = self.payer {
+            accounts.push(AccountMeta::new(payer, true));
+        }
+
+        vec![Instruction::new_with_bytes(
+            self.program_id,
+            &data,
+            accounts,
+        )]
+    }
+}
+
+/// Take `escrow`: pay its `receive` in token B for the vault's token A.
+pub struct Take {
+    pub escrow: Escrow,
+    pub taker: Pubkey,
+    /// Token program of both mints.
+    pub token_program: Pubkey,
+    /// Fail unless the escrow's `receive` still equals this.
+    pub expected_receive: Option<u64>,
+    /// Fail unless the vault still holds at least this much token A.
+    pub min_amount_a: Option<u64>,
+}
+
+impl Take {
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let escrow = &self.escrow;
+        let ata = |wallet: &Pubkey, mint: &Pubkey| {
+            associated_token_address(wallet, mint, &self.token_program)
+        };
+
+        let mut data = vec![1];
+        match escrow.layout {
+            Layout::Anchor => {
+                for limit in [self.expected_receive, self.min_amount_a] {
+                    match limit {
+                        Some(value) => {
+                            data.push(1);
+                            data.extend_from_slice(&value.to_le_bytes());
+                        }
+                        None => data.push(0),
+                    }
+                }
+            }
+            Layout::Pinocchio if self.expected_receive.is_some() || self.min_amount_a.is_some() => {
+                data.extend_from_slice(&self.expected_receive.unwrap_or(0).to_le_bytes());
+                data.extend_from_slice(&self.min_amount_a.unwrap_or(0).to_le_bytes());
+            }
+            Layout::Pinocchio => {}
+        }
+
+        let mut accounts = vec![
+            AccountMeta::new(self.taker, true),
+            AccountMeta::new(escrow.maker, false),
+            AccountMeta::new(escrow.address, false),
+        ];
+        if escrow.layout == Layout::Anchor {
+            accounts.push(AccountMeta::new(escrow.rent_recipient, false));
+        }
+        accounts.extend([
+            AccountMeta::new_readonly(escrow.mint_a, false),
+            AccountMeta::new_readonly(escrow.mint_b, false),
+            AccountMeta::new(ata(&escrow.address, &escrow.mint_a), false),
+            AccountMeta::new(ata(&self.taker, &escrow.mint_a), false),
+            AccountMeta::new(ata(&self.taker, &escrow.mint_b), false),
+            AccountMeta::new(ata(&escrow.maker, &escrow.mint_b), false),
+        ]);
+        let mut instructions = Vec::new();
+        match escrow.layout {
+            Layout::Anchor => accounts.extend([
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ]),
+            Layout::Pinocchio => {
+                // Anchor creates these with init_if_needed; the Pinocchio program expects them.
+                instructions.push(create_associated_token_account_idempotent(
+                    &self.taker,
+                    &self.taker,
+                    &escrow.mint_a,
+                    &self.token_program,
+                ));
+                instructions.push(create_associated_token_account_idempotent(
+                    &self.taker,
+                    &escrow.maker,
+                    &escrow.mint_b,
+                    &self.token_program,
+                ));
+                accounts.extend([
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(self.token_program, false),
+                    AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+                    AccountMeta::new(escrow.rent_recipient, false),
+                ]);
+            }
+        }
+
+        instructions.push(Instruction::new_with_bytes(
+            escrow.program_id,
+            &data,
+            accounts,
+        ));
+        instructions
+    }
+}
+
+/// Refund `escrow`: return the vault's token A to the maker.
+pub struct Refund {
+    pub escrow: Escrow,
+    /// Token program of mint A.
+    pub token_program: Pubkey,
+}
+
+impl Refund {
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let escrow = &self.escrow;
+        let mut accounts = vec![
+            AccountMeta::new(escrow.maker, true),
+            AccountMeta::new(escrow.address, false),
+        ];
+        if escrow.layout == Layout::Anchor {
+            accounts.push(AccountMeta::new(escrow.rent_recipient, false));
+        }
+        accounts.extend([
+            AccountMeta::new_readonly(escrow.mint_a, false),
+            AccountMeta::new(
+                associated_token_address(&escrow.address, &escrow.mint_a, &self.token_program),
+                false,
+            ),
+            AccountMeta::new(
+                associated_token_address(&escrow.maker, &escrow.mint_a, &self.token_program),
+                false,
+            ),
+        ]);
+        let mut instructions = Vec::new();
+        match escrow.layout {
+            Layout::Anchor => accounts.extend([
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ]),
+            Layout::Pinocchio => {
+                instructions.push(create_associated_token_account_idempotent(
+                    &escrow.maker,
+                    &escrow.maker,
+                    &escrow.mint_a,
+                    &self.token_program,
+                ));
+                accounts.extend([
+                    AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+                    AccountMeta::new_readonly(self.token_program, false),
+                    AccountMeta::new(escrow.rent_recipient, false),
+                ]);
+            }
+        }
+
+        instructions.push(Instruction::new_with_bytes(
+            escrow.program_id,
+            &[2],
+            accounts,
+        ));
+        instructions
+    }
+}
+
+/// Sweep `token_account`, owned by the maker's escrow PDA for `seed`, back to the
+/// maker and close it. The escrow may already be closed.
+pub struct Sweep {
+    pub program_id: Pubkey,
+    pub layout: Layout,
+    pub maker: Pubkey,
+    pub seed: u64,
+    pub mint: Pubkey,
+    pub token_account: Pubkey,
+    /// Token program of `mint`.
+    pub token_program: Pubkey,
+}
+
+impl Sweep {
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let discriminator = match self.layout {
+            Layout::Anchor => 10,
+            Layout::Pinocchio => 11,
+        };
+        let mut data = vec![discriminator];
+        data.extend_from_slice(&self.seed.to_le_bytes());
+        // The escrow kind: Anchor requires it, Pinocchio defaults to the escrow.
+        if self.layout == Layout::Anchor {
+            data.push(0);
+        }
+
+        let mut accounts = vec![
+            AccountMeta::new(self.maker, true),
+            AccountMeta::new_readonly(
+                escrow_address(&self.program_id, &self.maker, self.seed),
+                false,
+            ),
+            AccountMeta::new_readonly(self.mint, false),
+            AccountMeta::new(self.token_account, false),
+            AccountMeta::new(
+                associated_token_address(&self.maker, &self.mint, &self.token_program),
+                false,
+            ),
+        ];
+        let mut instructions = Vec::new();
+        match self.layout {
+            Layout::Anchor => accounts.extend([
+                AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+                AccountMeta::new_readonly(self.token_program, false),
+                AccountMeta::new_readonly(SYSTEM_PROGRAM_ID, false),
+            ]),
+            Layout::Pinocchio => {
+                instructions.push(create_associated_token_account_idempotent(
+                    &self.maker,
+                    &self.maker,
+                    &self.mint,
+                    &self.token_program,
+                ));
+                accounts.push(AccountMeta::new_readonly(self.token_program, false));
+            }
+        }
+
+        instructions.push(Instruction::new_with_bytes(
+            self.program_id,
+            &data,
+            accounts,
+        ));
+        instructions
+    }
+}
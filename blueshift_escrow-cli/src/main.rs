@@ -0,0 +1,420 @@
+//! `escrow`: drive the Anchor and Pinocchio escrow programs from the command line.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::process::ExitCode;
+use std::thread::sleep;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blueshift_escrow_cli::{associated_token_address, Make, Refund, Sweep, Take};
+use blueshift_escrow_indexer::rpc::RpcClient;
+use blueshift_escrow_indexer::token::TokenAccount;
+use blueshift_escrow_indexer::{
+    AccountSnapshot, Escrow, Layout, OrderBook, Pubkey, ESCROW_PROGRAM_ID,
+};
+use serde_json::json;
+use solana_hash::Hash;
+use solana_instruction::Instruction;
+use solana_keypair::{read_keypair_file, Keypair};
+use solana_signer::Signer;
+use solana_transaction::Transaction;
+
+type Result<T> = std::result::Result<T, Box<dyn Error>>;
+
+const USAGE: &str = "\
+Usage: escrow [--url URL] [--keypair FILE] [--program ID] <COMMAND> [OPTIONS]
+
+Commands:
+  make --mint-a MINT --mint-b MINT --amount N --receive N [--seed N] [--layout anchor|pinocchio]
+       [--payer FILE] [--rent-recipient PUBKEY]
+      Offer N token A for N token B. The seed defaults to the current time; the
+      layout (which escrow program is deployed at --program) to anchor. --payer
+      is a keypair that funds the escrow and fee instead of the maker; the
+      escrow's rent is refunded on close to the payer unless --rent-recipient.
+  take --escrow ADDRESS [--expected-receive N] [--min-amount-a N]
+      Take an escrow. The limits default to its current terms.
+  refund --escrow ADDRESS
+      Refund one of your escrows.
+  sweep --seed N --account ADDRESS [--layout anchor|pinocchio]
+      Recover a token account owned by your escrow PDA for seed N, e.g. tokens
+      sent to it after the escrow closed.
+  show (--escrow ADDRESS | --maker PUBKEY --seed N)
+      Print an escrow and its vault.
+  list [--pair MINT_A MINT_B]
+      Print open escrows by mint pair.
+
+Options:
+  --url URL        JSON-RPC endpoint [default: http://127.0.0.1:8899]
+  --keypair FILE   signer keypair [default: ~/.config/solana/id.json]
+  --program ID     escrow program [default: 2222...2222]";
+
+/// How long to wait for a sent transaction to be confirmed.
+const CONFIRM_TIMEOUT: Duration = Duration::from_secs(30);
+
+struct Args {
+    url: String,
+    keypair: String,
+    program_id: Pubkey,
+    command: String,
+    options: HashMap<String, Vec<String>>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> std::result::Result<Args, String> {
+    let mut parsed = Args {
+        url: "http://127.0.0.1:8899".into(),
+        keypair: "~/.config/solana/id.json".into(),
+        program_id: ESCROW_PROGRAM_ID,
+        command: String::new(),
+        options: HashMap::new(),
+    };
+    while let Some(arg) = args.next() {
+        let Some(flag) = arg.strip_prefix("--") else {
+            if !parsed.command.is_empty() {
+                return Err(format!("unexpected argument {arg:?}"));
+            }
+            parsed.command = arg;
+            continue;
+        };
+        if flag == "help" {
+            return Err(String::new());
+        }
+        // --pair takes two values; every other option one.
+        let arity = if flag == "pair" { 2 } else { 1 };
+        let values: Vec<String> = args.by_ref().take(arity).collect();
+        if values.len() != arity {
+            return Err(format!("--{flag} needs {arity} value(s)"));
+        }
+        match flag {
+            "url" => parsed.url = values[0].clone(),
+            "keypair" => parsed.keypair = values[0].clone(),
+            "program" => parsed.program_id = parse(flag, &values[0])?,
+            _ => {
+                parsed.options.insert(flag.to_string(), values);
+            }
+        }
+    }
+    if parsed.command.is_empty() {
+        return Err("missing command".into());
+    }
+    Ok(parsed)
+}
+
+fn parse<T: std::str::FromStr>(flag: &str, value: &str) -> std::result::Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("--{flag}: invalid value {value:?}"))
+}
+
+impl Args {
+    fn get<T: std::str::FromStr>(&self, flag: &str) -> std::result::Result<Option<T>, String> {
+        self.options
+            .get(flag)
+            .map(|values| parse(flag, &values[0]))
+            .transpose()
+    }
+
+    fn require<T: std::str::FromStr>(&self, flag: &str) -> std::result::Result<T, String> {
+        self.get(flag)?
+            .ok_or_else(|| format!("{} needs --{flag}", self.command))
+    }
+
+    fn signer(&self) -> Result<Keypair> {
+        read_keypair(&self.keypair)
+    }
+}
+
+fn read_keypair(path: &str) -> Result<Keypair> {
+    let path = match (path.strip_prefix("~/"), std::env::var("HOME")) {
+        (Some(rest), Ok(home)) => format!("{home}/{rest}"),
+        _ => path.to_string(),
+    };
+    read_keypair_file(&path).map_err(|e| format!("reading keypair {path}: {e}").into())
+}
+
+fn fetch(client: &RpcClient, address: &Pubkey) -> Result<AccountSnapshot> {
+    client
+        .get_multiple_accounts(&[*address])?
+        .pop()
+        .ok_or_else(|| format!("account {address} not found").into())
+}
+
+fn fetch_escrow(client: &RpcClient, address: &Pubkey) -> Result<Escrow> {
+    Escrow::decode(&fetch(client, address)?)
+        .ok_or_else(|| format!("{address} is not an escrow").into())
+}
+
+/// The token program a mint lives under.
+fn token_program(client: &RpcClient, mint: &Pubkey) -> Result<Pubkey> {
+    Ok(fetch(client, mint)?.owner)
+}
+
+/// The escrow's vault address and balance, if the vault exists.
+fn vault(
+    client: &RpcClient,
+    escrow: &Escrow,
+    token_program: &Pubkey,
+) -> Result<(Pubkey, Option<u64>)> {
+    let address = associated_token_address(&escrow.address, &escrow.mint_a, token_program);
+    let amount = client
+        .get_multiple_accounts(&[address])?
+        .first()
+        .and_then(TokenAccount::decode)
+        .map(|vault| vault.amount);
+    Ok((address, amount))
+}
+
+/// Sign with `signers`, the first paying the fee, and send.
+fn send(client: &RpcClient, signers: &[&Keypair], instructions: &[Instruction]) -> Result<String> {
+    let blockhash = client.call("getLatestBlockhash", json!([{ "commitment": "confirmed" }]))?;
+    let blockhash: Hash = blockhash["value"]["blockhash"]
+        .as_str()
+        .ok_or("getLatestBlockhash: no blockhash")?
+        .parse()
+        .map_err(|_| "getLatestBlockhash: invalid blockhash")?;
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&signers[0].pubkey()),
+        signers,
+        blockhash,
+    );
+
+    let signature = client.call(
+        "sendTransaction",
+        json!([
+            STANDARD.encode(bincode::serialize(&transaction)?),
+            { "encoding": "base64", "preflightCommitment": "confirmed" }
+        ]),
+    )?;
+    let signature = signature
+        .as_str()
+        .ok_or("sendTransaction: no signature")?
+        .to_string();
+
+    let started = SystemTime::now();
+    loop {
+        let statuses = client.call("getSignatureStatuses", json!([[signature]]))?;
+        let status = &statuses["value"][0];
+        if !status.is_null() {
+            if !status["err"].is_null() {
+                return Err(format!("transaction {signature} failed: {}", status["err"]).into());
+            }
+            if matches!(
+                status["confirmationStatus"].as_str(),
+                Some("confirmed" | "finalized")
+            ) {
+                return Ok(signature);
+            }
+        }
+        if started.elapsed()? > CONFIRM_TIMEOUT {
+            return Err(
+                format!("transaction {signature} not confirmed after {CONFIRM_TIMEOUT:?}").into(),
+            );
+        }
+        sleep(Duration::from_millis(500));
+    }
+}
+
+fn print_escrow(escrow: &Escrow, vault: &Pubkey, vault_amount: Option<u64>) {
+    println!("escrow          {}", escrow.address);
+    println!(
+        "program         {} ({} v{})",
+        escrow.program_id, escrow.layout, escrow.version
+    );
+    println!("seed            {}", escrow.seed);
+    println!("maker           {}", escrow.maker);
+    println!("mint a          {}", escrow.mint_a);
+    println!("mint b          {}", escrow.mint_b);
+    println!("receive         {}", escrow.receive);
+    println!("rent recipient  {}", escrow.rent_recipient);
+    match vault_amount {
+        Some(amount) => println!("vault           {vault} ({amount})"),
+        None => println!("vault           {vault} (missing)"),
+    }
+}
+
+impl Args {
+    /// Which escrow program is deployed at --program; defaults to anchor.
+    fn layout(&self) -> std::result::Result<Layout, String> {
+        match self.get::<String>("layout")?.as_deref() {
+            None | Some("anchor") => Ok(Layout::Anchor),
+            Some("pinocchio") => Ok(Layout::Pinocchio),
+            Some(other) => Err(format!(
+                "--layout: expected anchor or pinocchio, got {other:?}"
+            )),
+        }
+    }
+}
+
+fn make(args: &Args, client: &RpcClient) -> Result<()> {
+    let signer = args.signer()?;
+    let payer = args
+        .get::<String>("payer")?
+        .map(|path| read_keypair(&path))
+        .transpose()?;
+    let mint_a: Pubkey = args.require("mint-a")?;
+    let layout = args.layout()?;
+    let seed = match args.get("seed")? {
+        Some(seed) => seed,
+        None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+    };
+    let make = Make {
+        program_id: args.program_id,
+        layout,
+        maker: signer.pubkey(),
+        mint_a,
+        mint_b: args.require("mint-b")?,
+        token_program: token_program(client, &mint_a)?,
+        seed,
+        receive: args.require("receive")?,
+        amount: args.require("amount")?,
+        payer: payer.as_ref().map(Keypair::pubkey),
+        rent_recipient: args.get("rent-recipient")?,
+    };
+
+    // A sponsoring payer also pays the transaction fee.
+    let signers = match &payer {
+        Some(payer) => vec![payer, &signer],
+        None => vec![&signer],
+    };
+    let signature = send(client, &signers, &make.instructions())?;
+    println!("made escrow {} (seed {seed}): {signature}", make.escrow());
+    Ok(())
+}
+
+fn take(args: &Args, client: &RpcClient) -> Result<()> {
+    let signer = args.signer()?;
+    let escrow = fetch_escrow(client, &args.require("escrow")?)?;
+    let token_program = token_program(client, &escrow.mint_a)?;
+    let (_, vault_amount) = vault(client, &escrow, &token_program)?;
+    let take = Take {
+        taker: signer.pubkey(),
+        token_program,
+        expected_receive: Some(args.get("expected-receive")?.unwrap_or(escrow.receive)),
+        min_amount_a: args.get("min-amount-a")?.or(vault_amount),
+        escrow,
+    };
+
+    let signature = send(client, &[&signer], &take.instructions())?;
+    println!("took escrow {}: {signature}", take.escrow.address);
+    Ok(())
+}
+
+fn refund(args: &Args, client: &RpcClient) -> Result<()> {
+    let signer = args.signer()?;
+    let escrow = fetch_escrow(client, &args.require("escrow")?)?;
+    if escrow.maker != signer.pubkey() {
+        return Err(format!(
+            "escrow {} belongs to {}, not {}",
+            escrow.address,
+            escrow.maker,
+            signer.pubkey()
+        )
+        .into());
+    }
+    let refund = Refund {
+        token_program: token_program(client, &escrow.mint_a)?,
+        escrow,
+    };
+
+    let signature = send(client, &[&signer], &refund.instructions())?;
+    println!("refunded escrow {}: {signature}", refund.escrow.address);
+    Ok(())
+}
+
+fn sweep(args: &Args, client: &RpcClient) -> Result<()> {
+    let signer = args.signer()?;
+    let address: Pubkey = args.require("account")?;
+    let account = fetch(client, &address)?;
+    let token_account = TokenAccount::decode(&account)
+        .ok_or_else(|| format!("{address} is not a token account"))?;
+    let sweep = Sweep {
+        program_id: args.program_id,
+        layout: args.layout()?,
+        maker: signer.pubkey(),
+        seed: args.require("seed")?,
+        mint: token_account.mint,
+        token_account: address,
+        token_program: account.owner,
+    };
+    let escrow = blueshift_escrow_cli::escrow_address(&sweep.program_id, &sweep.maker, sweep.seed);
+    if token_account.owner != escrow {
+        return Err(format!(
+            "{address} belongs to {}, not your escrow {escrow}",
+            token_account.owner
+        )
+        .into());
+    }
+
+    let signature = send(client, &[&signer], &sweep.instructions())?;
+    println!(
+        "swept {} token(s) from {address}: {signature}",
+        token_account.amount
+    );
+    Ok(())
+}
+
+fn show(args: &Args, client: &RpcClient) -> Result<()> {
+    let address = match args.get("escrow")? {
+        Some(address) => address,
+        None => blueshift_escrow_cli::escrow_address(
+            &args.program_id,
+            &args.require("maker")?,
+            args.require("seed")?,
+        ),
+    };
+    let escrow = fetch_escrow(client, &address)?;
+    let (vault, amount) = vault(client, &escrow, &token_program(client, &escrow.mint_a)?)?;
+    print_escrow(&escrow, &vault, amount);
+    Ok(())
+}
+
+fn list(args: &Args, client: &RpcClient) -> Result<()> {
+    let accounts = client.snapshot(&[args.program_id])?;
+    let book = OrderBook::from_accounts(&accounts, &[args.program_id]);
+    let pairs: Vec<(Pubkey, Pubkey)> = match args.options.get("pair") {
+        Some(pair) => vec![(parse("pair", &pair[0])?, parse("pair", &pair[1])?)],
+        None => book.pairs().copied().collect(),
+    };
+    for (mint_a, mint_b) in pairs {
+        let orders = book.pair(&mint_a, &mint_b);
+        println!("{mint_a} -> {mint_b} ({} orders)", orders.len());
+        for order in orders {
+            println!("  {order}");
+        }
+    }
+    Ok(())
+}
+
+fn run(args: Args) -> Result<()> {
+    let client = RpcClient::new(&args.url)?;
+    match args.command.as_str() {
+        "make" => make(&args, &client),
+        "take" => take(&args, &client),
+        "refund" => refund(&args, &client),
+        "sweep" => sweep(&args, &client),
+        "show" => show(&args, &client),
+        "list" => list(&args, &client),
+        other => Err(format!("unknown command {other:?}").into()),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            if !error.is_empty() {
+                eprintln!("error: {error}\n");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,345 @@
+//! Instruction data and account order for both escrow layouts, checked against
+//! the programs' Make/Take/Refund/Sweep account structs.
+
+use blueshift_escrow_cli::{
+    associated_token_address, escrow_address, Make, Refund, Sweep, Take, SYSTEM_PROGRAM_ID,
+};
+use blueshift_escrow_indexer::escrow::ASSOCIATED_TOKEN_PROGRAM_ID;
+use blueshift_escrow_indexer::token::TOKEN_PROGRAM_ID;
+use blueshift_escrow_indexer::{Escrow, Layout, Pubkey, ESCROW_PROGRAM_ID};
+use solana_instruction::Instruction;
+
+const MAKER: Pubkey = Pubkey::new_from_array([0x11; 32]);
+const TAKER: Pubkey = Pubkey::new_from_array([0x22; 32]);
+const RELAYER: Pubkey = Pubkey::new_from_array([0x33; 32]);
+const MINT_A: Pubkey = Pubkey::new_from_array([0xa1; 32]);
+const MINT_B: Pubkey = Pubkey::new_from_array([0xb2; 32]);
+
+fn ata(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    associated_token_address(wallet, mint, &TOKEN_PROGRAM_ID)
+}
+
+fn keys(instruction: &Instruction) -> Vec<Pubkey> {
+    instruction
+        .accounts
+        .iter()
+        .map(|meta| meta.pubkey)
+        .collect()
+}
+
+fn escrow(layout: Layout) -> Escrow {
+    let address = escrow_address(&ESCROW_PROGRAM_ID, &MAKER, 7);
+    Escrow {
+        address,
+        program_id: ESCROW_PROGRAM_ID,
+        layout,
+        version: 2,
+        seed: 7,
+        maker: MAKER,
+        mint_a: MINT_A,
+        mint_b: MINT_B,
+        receive: 500,
+        bump: Pubkey::find_program_address(
+            &[b"escrow", MAKER.as_ref(), &7u64.to_le_bytes()],
+            &ESCROW_PROGRAM_ID,
+        )
+        .1,
+        rent_recipient: RELAYER,
+    }
+}
+
+fn make(layout: Layout) -> Make {
+    Make {
+        program_id: ESCROW_PROGRAM_ID,
+        layout,
+        maker: MAKER,
+        mint_a: MINT_A,
+        mint_b: MINT_B,
+        token_program: TOKEN_PROGRAM_ID,
+        seed: 7,
+        receive: 500,
+        amount: 1000,
+        payer: None,
+        rent_recipient: None,
+    }
+}
+
+#[test]
+fn make_data_and_accounts() {
+    let escrow = escrow_address(&ESCROW_PROGRAM_ID, &MAKER, 7);
+    let mut data = vec![0];
+    for value in [7u64, 500, 1000] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    let head = [
+        MAKER,
+        escrow,
+        MINT_A,
+        MINT_B,
+        ata(&MAKER, &MINT_A),
+        ata(&escrow, &MINT_A),
+    ];
+
+    let [anchor] = &make(Layout::Anchor).instructions()[..] else {
+        panic!("one instruction")
+    };
+    assert_eq!(anchor.data, [&data[..], &[0]].concat());
+    assert_eq!(keys(anchor)[..6], head);
+    assert_eq!(
+        keys(anchor)[6..],
+        [
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID
+        ]
+    );
+    assert!(anchor.accounts[0].is_signer && anchor.accounts[0].is_writable);
+
+    let [pinocchio] = &make(Layout::Pinocchio).instructions()[..] else {
+        panic!("one instruction")
+    };
+    assert_eq!(pinocchio.data, data);
+    assert_eq!(keys(pinocchio)[..6], head);
+    assert_eq!(
+        keys(pinocchio)[6..],
+        [
+            TOKEN_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID
+        ]
+    );
+}
+
+#[test]
+fn make_passes_sponsoring_payer() {
+    let sponsored = |layout| {
+        let make = Make {
+            payer: Some(RELAYER),
+            ..make(layout)
+        };
+        make.instructions().remove(0)
+    };
+
+    // Both layouts take the payer as an optional last account.
+    for layout in [Layout::Anchor, Layout::Pinocchio] {
+        let sponsored = sponsored(layout);
+        assert_eq!(sponsored.accounts.len(), 10);
+        assert_eq!(keys(&sponsored)[9], RELAYER);
+        assert!(sponsored.accounts[9].is_signer && sponsored.accounts[9].is_writable);
+    }
+}
+
+#[test]
+fn make_encodes_rent_recipient_per_layout() {
+    let with_relayer = |layout| {
+        let make = Make {
+            rent_recipient: Some(RELAYER),
+            ..make(layout)
+        };
+        make.instructions().remove(0).data
+    };
+
+    // Anchor: a borsh Option<Pubkey>; Pinocchio: the bare key, omitted for the maker.
+    let anchor = with_relayer(Layout::Anchor);
+    assert_eq!(anchor.len(), 25 + 33);
+    assert_eq!(anchor[25..], [&[1][..], RELAYER.as_ref()].concat());
+    let pinocchio = with_relayer(Layout::Pinocchio);
+    assert_eq!(pinocchio.len(), 25 + 32);
+    assert_eq!(pinocchio[25..], *RELAYER.as_ref());
+}
+
+#[test]
+fn take_encodes_limits_per_layout() {
+    let take = |layout, expected_receive, min_amount_a| Take {
+        escrow: escrow(layout),
+        taker: TAKER,
+        token_program: TOKEN_PROGRAM_ID,
+        expected_receive,
+        min_amount_a,
+    };
+
+    // Anchor: two borsh Option<u64>s.
+    let instructions = take(Layout::Anchor, Some(500), None).instructions();
+    let mut data = vec![1, 1];
+    data.extend_from_slice(&500u64.to_le_bytes());
+    data.push(0);
+    assert_eq!(instructions.last().unwrap().data, data);
+    assert_eq!(
+        take(Layout::Anchor, None, None).instructions()[0].data,
+        [1, 0, 0]
+    );
+
+    // Pinocchio: no limits, or both as u64s with 0 unchecked.
+    let instructions = take(Layout::Pinocchio, None, Some(1000)).instructions();
+    let mut data = vec![1];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    data.extend_from_slice(&1000u64.to_le_bytes());
+    assert_eq!(instructions.last().unwrap().data, data);
+    assert_eq!(
+        take(Layout::Pinocchio, None, None)
+            .instructions()
+            .last()
+            .unwrap()
+            .data,
+        [1]
+    );
+}
+
+#[test]
+fn take_accounts() {
+    let escrow = escrow(Layout::Anchor);
+    let [taker, maker, escrow, tokens @ ..] = [
+        TAKER,
+        MAKER,
+        escrow.address,
+        MINT_A,
+        MINT_B,
+        ata(&escrow.address, &MINT_A),
+        ata(&TAKER, &MINT_A),
+        ata(&TAKER, &MINT_B),
+        ata(&MAKER, &MINT_B),
+    ];
+    let take = |layout| {
+        Take {
+            escrow: self::escrow(layout),
+            taker: TAKER,
+            token_program: TOKEN_PROGRAM_ID,
+            expected_receive: None,
+            min_amount_a: None,
+        }
+        .instructions()
+    };
+
+    let [anchor] = &take(Layout::Anchor)[..] else {
+        panic!("one instruction")
+    };
+    assert_eq!(keys(anchor)[..4], [taker, maker, escrow, RELAYER]);
+    assert_eq!(keys(anchor)[4..10], tokens);
+    assert_eq!(
+        keys(anchor)[10..],
+        [
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID
+        ]
+    );
+
+    // The Pinocchio program doesn't create the taker's A or the maker's B account.
+    let [create_taker_a, create_maker_b, pinocchio] = &take(Layout::Pinocchio)[..] else {
+        panic!("three instructions")
+    };
+    assert_eq!(create_taker_a.program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+    assert_eq!(
+        keys(create_taker_a)[..3],
+        [TAKER, ata(&TAKER, &MINT_A), TAKER]
+    );
+    assert_eq!(
+        keys(create_maker_b)[..3],
+        [TAKER, ata(&MAKER, &MINT_B), MAKER]
+    );
+    assert_eq!(keys(pinocchio)[..3], [taker, maker, escrow]);
+    assert_eq!(keys(pinocchio)[3..9], tokens);
+    assert_eq!(
+        keys(pinocchio)[9..],
+        [
+            SYSTEM_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            RELAYER
+        ]
+    );
+}
+
+#[test]
+fn refund_accounts() {
+    let escrow = escrow(Layout::Anchor);
+    let [maker, escrow, tokens @ ..] = [
+        MAKER,
+        escrow.address,
+        MINT_A,
+        ata(&escrow.address, &MINT_A),
+        ata(&MAKER, &MINT_A),
+    ];
+    let refund = |layout| {
+        Refund {
+            escrow: self::escrow(layout),
+            token_program: TOKEN_PROGRAM_ID,
+        }
+        .instructions()
+    };
+
+    let [anchor] = &refund(Layout::Anchor)[..] else {
+        panic!("one instruction")
+    };
+    assert_eq!(anchor.data, [2]);
+    assert_eq!(keys(anchor)[..3], [maker, escrow, RELAYER]);
+    assert_eq!(keys(anchor)[3..6], tokens);
+    assert_eq!(
+        keys(anchor)[6..],
+        [
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID
+        ]
+    );
+
+    let [create_maker_a, pinocchio] = &refund(Layout::Pinocchio)[..] else {
+        panic!("two instructions")
+    };
+    assert_eq!(
+        keys(create_maker_a)[..3],
+        [MAKER, ata(&MAKER, &MINT_A), MAKER]
+    );
+    assert_eq!(pinocchio.data, [2]);
+    assert_eq!(keys(pinocchio)[..2], [maker, escrow]);
+    assert_eq!(keys(pinocchio)[2..5], tokens);
+    assert_eq!(
+        keys(pinocchio)[5..],
+        [SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID, RELAYER]
+    );
+}
+
+#[test]
+fn sweep_data_and_accounts() {
+    let escrow = escrow_address(&ESCROW_PROGRAM_ID, &MAKER, 7);
+    let stray = ata(&escrow, &MINT_B);
+    let sweep = |layout| {
+        Sweep {
+            program_id: ESCROW_PROGRAM_ID,
+            layout,
+            maker: MAKER,
+            seed: 7,
+            mint: MINT_B,
+            token_account: stray,
+            token_program: TOKEN_PROGRAM_ID,
+        }
+        .instructions()
+    };
+    let head = [MAKER, escrow, MINT_B, stray, ata(&MAKER, &MINT_B)];
+
+    let [anchor] = &sweep(Layout::Anchor)[..] else {
+        panic!("one instruction")
+    };
+    assert_eq!(anchor.data, [&[10][..], &7u64.to_le_bytes(), &[0]].concat());
+    assert_eq!(keys(anchor)[..5], head);
+    assert_eq!(
+        keys(anchor)[5..],
+        [
+            ASSOCIATED_TOKEN_PROGRAM_ID,
+            TOKEN_PROGRAM_ID,
+            SYSTEM_PROGRAM_ID
+        ]
+    );
+
+    let [create_maker_b, pinocchio] = &sweep(Layout::Pinocchio)[..] else {
+        panic!("two instructions")
+    };
+    assert_eq!(
+        keys(create_maker_b)[..3],
+        [MAKER, ata(&MAKER, &MINT_B), MAKER]
+    );
+    assert_eq!(pinocchio.data, [&[11][..], &7u64.to_le_bytes()].concat());
+    assert_eq!(keys(pinocchio)[..5], head);
+    assert_eq!(keys(pinocchio)[5..], [TOKEN_PROGRAM_ID]);
+    assert!(pinocchio.accounts[0].is_signer && !pinocchio.accounts[1].is_writable);
+}
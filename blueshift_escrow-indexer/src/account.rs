@@ -0,0 +1,91 @@
+//! Account snapshots and the JSON dumps they are read from.
+//!
+//! A dump is any of:
+//! - one keyed account, as written by `solana account --output json`:
+//!   `{"pubkey": "...", "account": {"lamports": .., "owner": "...", "data": ["<base64>", "base64"], ..}}`
+//! - an array of keyed accounts, as returned by `getProgramAccounts`
+//! - a whole JSON-RPC response whose `result` (or `result.value`) is one of the above
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{Error, Pubkey, Result};
+
+/// An account's address, owner, lamports and data at the time of the snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountSnapshot {
+    pub pubkey: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+/// `account` of a keyed account in RPC JSON.
+#[derive(Deserialize)]
+pub(crate) struct UiAccount {
+    lamports: u64,
+    owner: String,
+    data: (String, String),
+}
+
+#[derive(Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: UiAccount,
+}
+
+pub(crate) fn parse_pubkey(s: &str) -> Result<Pubkey> {
+    s.parse()
+        .map_err(|_| Error::Format(format!("invalid pubkey {s:?}")))
+}
+
+impl UiAccount {
+    pub(crate) fn into_snapshot(self, pubkey: Pubkey) -> Result<AccountSnapshot> {
+        let (data, encoding) = self.data;
+        if encoding != "base64" {
+            return Err(Error::Format(format!(
+                "account {pubkey}: unsupported data encoding {encoding:?}, expected base64"
+            )));
+        }
+
+        Ok(AccountSnapshot {
+            pubkey,
+            owner: parse_pubkey(&self.owner)?,
+            lamports: self.lamports,
+            data: STANDARD.decode(data)?,
+        })
+    }
+}
+
+fn keyed_account(value: Value) -> Result<AccountSnapshot> {
+    let keyed: KeyedAccount = serde_json::from_value(value)?;
+    let pubkey = parse_pubkey(&keyed.pubkey)?;
+    keyed.account.into_snapshot(pubkey)
+}
+
+pub(crate) fn accounts_from_value(value: Value) -> Result<Vec<AccountSnapshot>> {
+    match value {
+        Value::Array(items) => items.into_iter().map(keyed_account).collect(),
+        Value::Object(mut map) => match map.remove("result") {
+            Some(Value::Object(mut result)) if result.contains_key("value") => {
+                accounts_from_value(result.remove("value").unwrap())
+            }
+            Some(result) => accounts_from_value(result),
+            None => Ok(vec![keyed_account(Value::Object(map))?]),
+        },
+        _ => Err(Error::Format(
+            "expected an account or a list of accounts".into(),
+        )),
+    }
+}
+
+/// Parse a JSON account dump.
+pub fn parse_accounts(json: &str) -> Result<Vec<AccountSnapshot>> {
+    accounts_from_value(serde_json::from_str(json)?)
+}
+
+/// Read and parse a JSON account dump from `path`.
+pub fn read_accounts(path: impl AsRef<std::path::Path>) -> Result<Vec<AccountSnapshot>> {
+    parse_accounts(&std::fs::read_to_string(path)?)
+}
@@ -0,0 +1,145 @@
+//! Open escrows joined with their vaults, by mint pair.
+
+use core::fmt;
+use std::collections::{BTreeMap, HashMap};
+
+use crate::token::{TokenAccount, TOKEN_PROGRAM_ID, TOKEN_PROGRAM_IDS};
+use crate::{AccountSnapshot, Escrow, Pubkey};
+
+/// Fixed-point scale of [`Order::price`], the same as the escrows' `quote`.
+pub const PRICE_SCALE: u64 = 1_000_000_000;
+
+/// An escrow and its vault.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Order {
+    pub escrow: Escrow,
+    pub vault: Pubkey,
+    /// Token A on offer; `None` if the vault is not in the snapshot.
+    pub vault_amount: Option<u64>,
+}
+
+impl Order {
+    /// `receive` per unit of token A in base units, scaled by [`PRICE_SCALE`]
+    /// (saturating); `None` unless the vault holds token A.
+    pub fn price(&self) -> Option<u64> {
+        match self.vault_amount {
+            Some(amount) if amount > 0 => Some(
+                (self.escrow.receive as u128 * PRICE_SCALE as u128 / amount as u128)
+                    .min(u64::MAX as u128) as u64,
+            ),
+            _ => None,
+        }
+    }
+}
+
+/// One line: escrow, layout and version, maker, seed, token A offered, token B
+/// asked and price, with `-` for an unknown vault.
+impl fmt::Display for Order {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let escrow = &self.escrow;
+        write!(
+            f,
+            "{} {} v{} maker {} seed {} offers ",
+            escrow.address, escrow.layout, escrow.version, escrow.maker, escrow.seed
+        )?;
+        match self.vault_amount {
+            Some(amount) => write!(f, "{amount}")?,
+            None => f.write_str("-")?,
+        }
+        write!(f, " asks {} price ", escrow.receive)?;
+        match self.price() {
+            Some(price) => write!(f, "{}.{:09}", price / PRICE_SCALE, price % PRICE_SCALE),
+            None => f.write_str("-"),
+        }
+    }
+}
+
+/// Orders keyed by (mint A, mint B): the mint offered and the mint asked for.
+#[derive(Clone, Debug, Default)]
+pub struct OrderBook {
+    pairs: BTreeMap<(Pubkey, Pubkey), Vec<Order>>,
+}
+
+impl OrderBook {
+    /// Decode the escrows owned by one of `program_ids` in `accounts` and join
+    /// each with its vault, looked up among the same accounts.
+    pub fn from_accounts(accounts: &[AccountSnapshot], program_ids: &[Pubkey]) -> Self {
+        let token_accounts: HashMap<Pubkey, TokenAccount> = accounts
+            .iter()
+            .filter_map(|account| Some((account.pubkey, TokenAccount::decode(account)?)))
+            .collect();
+
+        let mut book = OrderBook::default();
+        for escrow in accounts
+            .iter()
+            .filter(|account| program_ids.contains(&account.owner))
+            .filter_map(Escrow::decode)
+        {
+            let vault = TOKEN_PROGRAM_IDS.iter().find_map(|token_program| {
+                let address = escrow.vault_address(token_program);
+                let vault = token_accounts.get(&address)?;
+                (vault.mint == escrow.mint_a && vault.owner == escrow.address)
+                    .then_some((address, vault.amount))
+            });
+            let order = match vault {
+                Some((vault, amount)) => Order {
+                    escrow,
+                    vault,
+                    vault_amount: Some(amount),
+                },
+                None => Order {
+                    vault: escrow.vault_address(&TOKEN_PROGRAM_ID),
+                    escrow,
+                    vault_amount: None,
+                },
+            };
+            book.insert(order);
+        }
+
+        for orders in book.pairs.values_mut() {
+            // Best price for the taker first; empty and unknown vaults last.
+            orders.sort_by_key(|order| {
+                (order.price().is_none(), order.price(), order.escrow.address)
+            });
+        }
+        book
+    }
+
+    fn insert(&mut self, order: Order) {
+        self.pairs
+            .entry((order.escrow.mint_a, order.escrow.mint_b))
+            .or_default()
+            .push(order);
+    }
+
+    /// Orders offering `mint_a` for `mint_b`, cheapest first. The other side of
+    /// the market is `pair(mint_b, mint_a)`.
+    pub fn pair(&self, mint_a: &Pubkey, mint_b: &Pubkey) -> &[Order] {
+        self.pairs
+            .get(&(*mint_a, *mint_b))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The (mint A, mint B) pairs with at least one order.
+    pub fn pairs(&self) -> impl Iterator<Item = &(Pubkey, Pubkey)> {
+        self.pairs.keys()
+    }
+
+    /// All orders, grouped by pair.
+    pub fn orders(&self) -> impl Iterator<Item = &Order> {
+        self.pairs.values().flatten()
+    }
+
+    /// The order for the escrow at `address`.
+    pub fn get(&self, address: &Pubkey) -> Option<&Order> {
+        self.orders().find(|order| order.escrow.address == *address)
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
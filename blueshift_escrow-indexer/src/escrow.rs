@@ -0,0 +1,138 @@
+//! Escrow account layouts of the two programs.
+//!
+//! | program   | v1 (before versioning) | v2 (current)                   |
+//! |-----------|------------------------|--------------------------------|
+//! | Anchor    | `[1]` + fields (114)   | `[1]` + fields + v2 (147)      |
+//! | Pinocchio | fields (113)           | fields + v2 + `[0; 6]` (152)   |
+//!
+//! where fields are seed (u64), maker, mint_a, mint_b, receive (u64) and bump
+//! (u8), and v2 adds the version byte and `rent_recipient`, all little-endian and
+//! unaligned. Pinocchio pads v2 with six zero bytes so it is never as long as its
+//! attested escrow (146), which is not decoded.
+
+use core::fmt;
+
+use crate::{AccountSnapshot, Pubkey};
+
+/// Discriminator of the Anchor program's `Escrow` account.
+pub const ANCHOR_ESCROW_DISCRIMINATOR: u8 = 1;
+
+/// Version byte of the current layout.
+pub const ESCROW_VERSION: u8 = 2;
+
+const V1_LEN: usize = 8 + 32 + 32 + 32 + 8 + 1;
+const V2_LEN: usize = V1_LEN + 1 + 32;
+const PINOCCHIO_V2_LEN: usize = V2_LEN + 6;
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    solana_pubkey::pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Which program wrote the account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Layout {
+    Anchor,
+    Pinocchio,
+}
+
+impl fmt::Display for Layout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Layout::Anchor => "anchor",
+            Layout::Pinocchio => "pinocchio",
+        })
+    }
+}
+
+/// A decoded escrow. v1 accounts report version 1 and the maker as
+/// `rent_recipient`, which is what migrating them would write.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Escrow {
+    pub address: Pubkey,
+    pub program_id: Pubkey,
+    pub layout: Layout,
+    pub version: u8,
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: u8,
+    pub rent_recipient: Pubkey,
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fn pubkey_at(bytes: &[u8], offset: usize) -> Pubkey {
+    Pubkey::new_from_array(bytes[offset..offset + 32].try_into().unwrap())
+}
+
+impl Escrow {
+    /// Decode `account` as an escrow of either program, or `None` if it is some
+    /// other account. Only accounts at their escrow PDA are accepted.
+    pub fn decode(account: &AccountSnapshot) -> Option<Self> {
+        let data = &account.data[..];
+        let (layout, fields) = match data.len() {
+            V1_LEN => (Layout::Pinocchio, data),
+            PINOCCHIO_V2_LEN => (Layout::Pinocchio, &data[..V2_LEN]),
+            len if (len == V1_LEN + 1 || len == V2_LEN + 1)
+                && data[0] == ANCHOR_ESCROW_DISCRIMINATOR =>
+            {
+                (Layout::Anchor, &data[1..])
+            }
+            _ => return None,
+        };
+
+        let (version, rent_recipient) = match fields.len() {
+            V2_LEN => (fields[V1_LEN], pubkey_at(fields, V1_LEN + 1)),
+            _ => (1, pubkey_at(fields, 8)),
+        };
+        if fields.len() == V2_LEN && version != ESCROW_VERSION {
+            return None;
+        }
+
+        let escrow = Escrow {
+            address: account.pubkey,
+            program_id: account.owner,
+            layout,
+            version,
+            seed: u64_at(fields, 0),
+            maker: pubkey_at(fields, 8),
+            mint_a: pubkey_at(fields, 40),
+            mint_b: pubkey_at(fields, 72),
+            receive: u64_at(fields, 104),
+            bump: fields[112],
+            rent_recipient,
+        };
+        (escrow.pda() == Some(account.pubkey)).then_some(escrow)
+    }
+
+    /// The address [b"escrow", maker, seed] derives with the stored bump.
+    pub fn pda(&self) -> Option<Pubkey> {
+        Pubkey::create_program_address(
+            &[
+                b"escrow",
+                self.maker.as_ref(),
+                &self.seed.to_le_bytes(),
+                &[self.bump],
+            ],
+            &self.program_id,
+        )
+        .ok()
+    }
+
+    /// The vault: the escrow's associated token account for mint A under
+    /// `token_program`.
+    pub fn vault_address(&self, token_program: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                self.address.as_ref(),
+                token_program.as_ref(),
+                self.mint_a.as_ref(),
+            ],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        )
+        .0
+    }
+}
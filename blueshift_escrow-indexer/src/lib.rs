@@ -0,0 +1,69 @@
+//! Off-chain indexer for the Anchor and Pinocchio escrow programs.
+//!
+//! Works from account snapshots: a JSON dump in the `solana account --output
+//! json` / `getProgramAccounts` shape (see [`account`]), or a validator queried
+//! over JSON-RPC (see [`rpc`]). Escrow accounts of either layout are decoded by
+//! [`escrow`], joined with their vault balance, and served by mint pair from an
+//! [`OrderBook`].
+
+pub mod account;
+pub mod book;
+pub mod escrow;
+pub mod rpc;
+pub mod token;
+
+pub use account::AccountSnapshot;
+pub use book::{Order, OrderBook, PRICE_SCALE};
+pub use escrow::{Escrow, Layout};
+pub use solana_pubkey::Pubkey;
+
+use core::fmt;
+
+/// The escrow programs' ID (both declare the same one).
+pub const ESCROW_PROGRAM_ID: Pubkey =
+    solana_pubkey::pubkey!("22222222222222222222222222222222222222222222");
+
+#[derive(Debug)]
+pub enum Error {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    Base64(base64::DecodeError),
+    /// A malformed snapshot or RPC response.
+    Format(String),
+    /// An error returned by the RPC node.
+    Rpc(String),
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => write!(f, "i/o error: {e}"),
+            Error::Json(e) => write!(f, "invalid JSON: {e}"),
+            Error::Base64(e) => write!(f, "invalid base64 account data: {e}"),
+            Error::Format(msg) => write!(f, "{msg}"),
+            Error::Rpc(msg) => write!(f, "RPC error: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Error::Json(e)
+    }
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(e: base64::DecodeError) -> Self {
+        Error::Base64(e)
+    }
+}
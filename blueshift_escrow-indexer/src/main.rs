@@ -0,0 +1,141 @@
+//! `escrow-indexer`: list open escrows by mint pair from an account dump or a
+//! validator.
+
+use std::process::ExitCode;
+
+use blueshift_escrow_indexer::{
+    account, rpc::RpcClient, Order, OrderBook, Pubkey, ESCROW_PROGRAM_ID,
+};
+use serde_json::{json, Value};
+
+const USAGE: &str = "\
+Usage: escrow-indexer (--rpc URL | --dump FILE...) [--program ID]... [--pair MINT_A MINT_B] [--json]
+
+Lists the open escrows of the Anchor and Pinocchio escrow programs by mint pair.
+
+  --rpc URL              read accounts from a validator, e.g. http://127.0.0.1:8899
+  --dump FILE            read accounts from a JSON dump (repeatable)
+  --program ID           escrow program to index (repeatable; default 2222...2222)
+  --pair MINT_A MINT_B   only orders offering MINT_A for MINT_B
+  --json                 print orders as JSON";
+
+#[derive(Default)]
+struct Args {
+    rpc: Option<String>,
+    dumps: Vec<String>,
+    programs: Vec<Pubkey>,
+    pair: Option<(Pubkey, Pubkey)>,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let pubkey = |value: Option<String>, flag: &str| -> Result<Pubkey, String> {
+        let value = value.ok_or_else(|| format!("{flag} needs a value"))?;
+        value
+            .parse()
+            .map_err(|_| format!("{flag}: invalid pubkey {value:?}"))
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--rpc" => parsed.rpc = Some(args.next().ok_or("--rpc needs a URL")?),
+            "--dump" => parsed.dumps.push(args.next().ok_or("--dump needs a file")?),
+            "--program" => parsed.programs.push(pubkey(args.next(), "--program")?),
+            "--pair" => {
+                parsed.pair = Some((
+                    pubkey(args.next(), "--pair")?,
+                    pubkey(args.next(), "--pair")?,
+                ))
+            }
+            "--json" => parsed.json = true,
+            "-h" | "--help" => return Err(String::new()),
+            other => return Err(format!("unexpected argument {other:?}")),
+        }
+    }
+    if let (Some(_), false) | (None, true) = (&parsed.rpc, parsed.dumps.is_empty()) {
+        return Err("pass either --rpc or --dump".into());
+    }
+    if parsed.programs.is_empty() {
+        parsed.programs.push(ESCROW_PROGRAM_ID);
+    }
+    Ok(parsed)
+}
+
+fn order_json(order: &Order) -> Value {
+    let escrow = &order.escrow;
+    json!({
+        "escrow": escrow.address.to_string(),
+        "program": escrow.program_id.to_string(),
+        "layout": escrow.layout.to_string(),
+        "version": escrow.version,
+        "seed": escrow.seed,
+        "maker": escrow.maker.to_string(),
+        "mint_a": escrow.mint_a.to_string(),
+        "mint_b": escrow.mint_b.to_string(),
+        "receive": escrow.receive,
+        "rent_recipient": escrow.rent_recipient.to_string(),
+        "vault": order.vault.to_string(),
+        "vault_amount": order.vault_amount,
+        "price": order.price(),
+    })
+}
+
+fn print_text<'a>(book: &'a OrderBook, pairs: impl Iterator<Item = &'a (Pubkey, Pubkey)>) {
+    for (mint_a, mint_b) in pairs {
+        let orders = book.pair(mint_a, mint_b);
+        println!("{mint_a} -> {mint_b} ({} orders)", orders.len());
+        for order in orders {
+            println!("  {order}");
+        }
+    }
+}
+
+fn run(args: Args) -> blueshift_escrow_indexer::Result<()> {
+    let accounts = match &args.rpc {
+        Some(url) => RpcClient::new(url)?.snapshot(&args.programs)?,
+        None => {
+            let mut accounts = Vec::new();
+            for dump in &args.dumps {
+                accounts.extend(account::read_accounts(dump)?);
+            }
+            accounts
+        }
+    };
+    let book = OrderBook::from_accounts(&accounts, &args.programs);
+
+    let pairs: Vec<(Pubkey, Pubkey)> = match args.pair {
+        Some(pair) => vec![pair],
+        None => book.pairs().copied().collect(),
+    };
+    if args.json {
+        let orders: Vec<Value> = pairs
+            .iter()
+            .flat_map(|(mint_a, mint_b)| book.pair(mint_a, mint_b))
+            .map(order_json)
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&orders)?);
+    } else {
+        print_text(&book, pairs.iter());
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(error) => {
+            if !error.is_empty() {
+                eprintln!("error: {error}\n");
+            }
+            eprintln!("{USAGE}");
+            return ExitCode::from(2);
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Snapshots from a validator's JSON-RPC API.
+//!
+//! A minimal blocking HTTP/1.1 client over `std::net`, enough for a local test
+//! validator (`http://127.0.0.1:8899`); TLS endpoints are not supported.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+use serde_json::{json, Value};
+
+use crate::account::{accounts_from_value, UiAccount};
+use crate::token::TOKEN_PROGRAM_IDS;
+use crate::{AccountSnapshot, Error, Escrow, Pubkey, Result};
+
+/// `getMultipleAccounts` accepts at most this many keys per call.
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
+
+pub struct RpcClient {
+    /// `host:port`
+    address: String,
+    path: String,
+}
+
+impl RpcClient {
+    /// A client for an `http://host[:port][/path]` endpoint.
+    pub fn new(url: &str) -> Result<Self> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| {
+            Error::Format(format!("unsupported RPC URL {url:?}, expected http://"))
+        })?;
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, "/"),
+        };
+        let address = match authority.contains(':') {
+            true => authority.to_string(),
+            false => format!("{authority}:80"),
+        };
+
+        Ok(Self {
+            address,
+            path: path.to_string(),
+        })
+    }
+
+    fn post(&self, body: &[u8]) -> Result<Vec<u8>> {
+        let mut stream = TcpStream::connect(&self.address)?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.address,
+            body.len()
+        )?;
+        stream.write_all(body)?;
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        let malformed = || Error::Format("malformed HTTP response".into());
+        let split = response
+            .windows(4)
+            .position(|w| w == b"\r\n\r\n")
+            .ok_or_else(malformed)?;
+        let head = std::str::from_utf8(&response[..split])
+            .map_err(|_| malformed())?
+            .to_ascii_lowercase();
+        let body = &response[split + 4..];
+
+        let status = head.lines().next().unwrap_or_default();
+        if status.split_whitespace().nth(1) != Some("200") {
+            return Err(Error::Rpc(format!("HTTP {status}")));
+        }
+        match head
+            .lines()
+            .any(|line| line.starts_with("transfer-encoding:") && line.contains("chunked"))
+        {
+            true => dechunk(body).ok_or_else(malformed),
+            false => Ok(body.to_vec()),
+        }
+    }
+
+    /// Call `method` and return its `result`.
+    pub fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value =
+            serde_json::from_slice(&self.post(&serde_json::to_vec(&request)?)?)?;
+        if let Some(error) = response.get("error") {
+            let mut message = error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("unknown")
+                .to_string();
+            // Failed preflight simulations carry the program logs.
+            for log in error["data"]["logs"].as_array().into_iter().flatten() {
+                message.push_str("\n  ");
+                message.push_str(log.as_str().unwrap_or_default());
+            }
+            return Err(Error::Rpc(message));
+        }
+        response
+            .get_mut("result")
+            .map(Value::take)
+            .ok_or_else(|| Error::Format(format!("{method}: response has no result")))
+    }
+
+    /// All accounts owned by `program_id`.
+    pub fn get_program_accounts(&self, program_id: &Pubkey) -> Result<Vec<AccountSnapshot>> {
+        let result = self.call(
+            "getProgramAccounts",
+            json!([program_id.to_string(), { "encoding": "base64", "commitment": "confirmed" }]),
+        )?;
+        accounts_from_value(result)
+    }
+
+    /// The accounts at `keys` that exist.
+    pub fn get_multiple_accounts(&self, keys: &[Pubkey]) -> Result<Vec<AccountSnapshot>> {
+        let mut accounts = Vec::new();
+        for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+            let keys: Vec<String> = chunk.iter().map(Pubkey::to_string).collect();
+            let mut result = self.call(
+                "getMultipleAccounts",
+                json!([keys, { "encoding": "base64", "commitment": "confirmed" }]),
+            )?;
+            let values: Vec<Option<UiAccount>> = serde_json::from_value(result["value"].take())?;
+            for (pubkey, account) in chunk.iter().zip(values) {
+                if let Some(account) = account {
+                    accounts.push(account.into_snapshot(*pubkey)?);
+                }
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// Everything an [`OrderBook`](crate::OrderBook) needs: the accounts of
+    /// `program_ids` and the vaults of the escrows among them.
+    pub fn snapshot(&self, program_ids: &[Pubkey]) -> Result<Vec<AccountSnapshot>> {
+        let mut accounts = Vec::new();
+        for program_id in program_ids {
+            accounts.extend(self.get_program_accounts(program_id)?);
+        }
+
+        let vaults: Vec<Pubkey> = accounts
+            .iter()
+            .filter_map(Escrow::decode)
+            .flat_map(|escrow| {
+                TOKEN_PROGRAM_IDS.map(|token_program| escrow.vault_address(&token_program))
+            })
+            .collect();
+        accounts.extend(self.get_multiple_accounts(&vaults)?);
+        Ok(accounts)
+    }
+}
+
+/// Decode a `Transfer-Encoding: chunked` body.
+fn dechunk(mut body: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::new();
+    loop {
+        let line_end = body.windows(2).position(|w| w == b"\r\n")?;
+        let size = std::str::from_utf8(&body[..line_end]).ok()?;
+        let size = usize::from_str_radix(size.split(';').next()?.trim(), 16).ok()?;
+        body = &body[line_end + 2..];
+        if size == 0 {
+            return Some(out);
+        }
+        out.extend_from_slice(body.get(..size)?);
+        body = body.get(size + 2..)?;
+    }
+}
@@ -0,0 +1,51 @@
+//! SPL token accounts, as far as the vault join needs them.
+
+use crate::{AccountSnapshot, Pubkey};
+
+pub const TOKEN_PROGRAM_ID: Pubkey =
+    solana_pubkey::pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+pub const TOKEN_2022_PROGRAM_ID: Pubkey =
+    solana_pubkey::pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Token programs a vault can live under.
+pub const TOKEN_PROGRAM_IDS: [Pubkey; 2] = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID];
+
+/// Length of a token account without Token-2022 extensions.
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+const STATE_OFFSET: usize = 108;
+
+/// Token-2022 account type byte, present when the account has extensions (mints
+/// with extensions are padded to the same length).
+const ACCOUNT_TYPE_OFFSET: usize = TOKEN_ACCOUNT_LEN;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// The mint, owner and balance of an initialized token account.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenAccount {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+impl TokenAccount {
+    /// Decode `account` if it is an initialized (or frozen) token account.
+    pub fn decode(account: &AccountSnapshot) -> Option<Self> {
+        let data = &account.data;
+        if !TOKEN_PROGRAM_IDS.contains(&account.owner)
+            || data.len() < TOKEN_ACCOUNT_LEN
+            || data[STATE_OFFSET] == 0
+        {
+            return None;
+        }
+        if data.len() > TOKEN_ACCOUNT_LEN && data[ACCOUNT_TYPE_OFFSET] != ACCOUNT_TYPE_ACCOUNT {
+            return None;
+        }
+
+        Some(TokenAccount {
+            mint: Pubkey::new_from_array(data[0..32].try_into().unwrap()),
+            owner: Pubkey::new_from_array(data[32..64].try_into().unwrap()),
+            amount: u64::from_le_bytes(data[64..72].try_into().unwrap()),
+        })
+    }
+}
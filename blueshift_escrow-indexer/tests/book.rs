@@ -0,0 +1,169 @@
+//! Decoding and the order book, against `fixtures/snapshot.json`: under program
+//! 2222...2222, mint A = [0xa1; 32], mint B = [0xb2; 32], makers [0x11..0x13; 32]:
+//! - Anchor v2, seed 1: 1000 A for 500 B
+//! - Pinocchio v2, seed 2: 1000 A for 400 B, rent to maker 3
+//! - Pinocchio v1, seed 3: 1000 B for 2000 A
+//! - Anchor v1, seed 4: 300 A (Token-2022 vault) for 900 B
+//! - Anchor v2, seed 5: 100 B, vault not in the snapshot
+//! - a Pinocchio attested escrow and a v2 layout off its PDA, both skipped
+//! - the vaults, and an unrelated wallet
+
+use blueshift_escrow_indexer::account::{parse_accounts, read_accounts};
+use blueshift_escrow_indexer::token::TOKEN_2022_PROGRAM_ID;
+use blueshift_escrow_indexer::{
+    AccountSnapshot, Escrow, Layout, OrderBook, Pubkey, ESCROW_PROGRAM_ID, PRICE_SCALE,
+};
+
+const MINT_A: Pubkey = Pubkey::new_from_array([0xa1; 32]);
+const MINT_B: Pubkey = Pubkey::new_from_array([0xb2; 32]);
+
+fn maker(i: u8) -> Pubkey {
+    Pubkey::new_from_array([0x10 + i; 32])
+}
+
+fn snapshot() -> Vec<AccountSnapshot> {
+    read_accounts(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/snapshot.json"
+    ))
+    .unwrap()
+}
+
+fn book() -> OrderBook {
+    OrderBook::from_accounts(&snapshot(), &[ESCROW_PROGRAM_ID])
+}
+
+#[test]
+fn decodes_both_layouts_and_versions() {
+    let escrows: Vec<Escrow> = snapshot().iter().filter_map(Escrow::decode).collect();
+    let summary: Vec<(Layout, u8, u64)> = escrows
+        .iter()
+        .map(|e| (e.layout, e.version, e.seed))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            (Layout::Anchor, 2, 1),
+            (Layout::Pinocchio, 2, 2),
+            (Layout::Pinocchio, 1, 3),
+            (Layout::Anchor, 1, 4),
+            (Layout::Anchor, 2, 5),
+        ]
+    );
+
+    let pinocchio_v2 = &escrows[1];
+    assert_eq!(pinocchio_v2.maker, maker(2));
+    assert_eq!(pinocchio_v2.mint_a, MINT_A);
+    assert_eq!(pinocchio_v2.mint_b, MINT_B);
+    assert_eq!(pinocchio_v2.receive, 400);
+    assert_eq!(pinocchio_v2.rent_recipient, maker(3));
+    assert_eq!(pinocchio_v2.program_id, ESCROW_PROGRAM_ID);
+    assert_eq!(pinocchio_v2.pda(), Some(pinocchio_v2.address));
+
+    // v1 accounts default the rent recipient to the maker, as migrating would
+    let anchor_v1 = &escrows[3];
+    assert_eq!(anchor_v1.rent_recipient, maker(1));
+}
+
+#[test]
+fn rejects_truncated_and_foreign_data() {
+    let accounts = snapshot();
+    let mut escrow = accounts[0].clone();
+    assert!(Escrow::decode(&escrow).is_some());
+
+    escrow.data.pop();
+    assert_eq!(Escrow::decode(&escrow), None);
+
+    let mut wrong_discriminator = accounts[0].clone();
+    wrong_discriminator.data[0] = 2;
+    assert_eq!(Escrow::decode(&wrong_discriminator), None);
+
+    let mut wrong_version = accounts[2].clone();
+    wrong_version.data[113] = 3;
+    assert_eq!(Escrow::decode(&wrong_version), None);
+
+    // Unpadded, a Pinocchio v2 escrow would be as long as an attested escrow.
+    let mut unpadded = accounts[2].clone();
+    unpadded.data.truncate(146);
+    assert_eq!(Escrow::decode(&unpadded), None);
+}
+
+#[test]
+fn order_book_groups_by_pair_cheapest_first() {
+    let book = book();
+    assert_eq!(book.len(), 5);
+    assert_eq!(
+        book.pairs().copied().collect::<Vec<_>>(),
+        [(MINT_A, MINT_B), (MINT_B, MINT_A)]
+    );
+
+    let asks = book.pair(&MINT_A, &MINT_B);
+    let seeds: Vec<u64> = asks.iter().map(|order| order.escrow.seed).collect();
+    assert_eq!(seeds, [2, 1, 4, 5]);
+    let prices: Vec<Option<u64>> = asks.iter().map(|order| order.price()).collect();
+    assert_eq!(
+        prices,
+        [
+            Some(PRICE_SCALE * 2 / 5),
+            Some(PRICE_SCALE / 2),
+            Some(PRICE_SCALE * 3),
+            None
+        ]
+    );
+
+    let bids = book.pair(&MINT_B, &MINT_A);
+    assert_eq!(bids.len(), 1);
+    assert_eq!(bids[0].escrow.maker, maker(3));
+    assert_eq!(bids[0].vault_amount, Some(1000));
+    assert_eq!(bids[0].price(), Some(PRICE_SCALE * 2));
+
+    assert!(book.pair(&MINT_A, &MINT_A).is_empty());
+}
+
+#[test]
+fn joins_vaults_under_either_token_program() {
+    let book = book();
+    let asks = book.pair(&MINT_A, &MINT_B);
+
+    let token_2022 = &asks[2];
+    assert_eq!(
+        token_2022.vault,
+        token_2022.escrow.vault_address(&TOKEN_2022_PROGRAM_ID)
+    );
+    assert_eq!(token_2022.vault_amount, Some(300));
+
+    let missing = &asks[3];
+    assert_eq!(missing.vault_amount, None);
+    assert_eq!(book.get(&missing.escrow.address), Some(missing));
+}
+
+#[test]
+fn ignores_other_programs() {
+    let other = Pubkey::new_from_array([9; 32]);
+    assert!(OrderBook::from_accounts(&snapshot(), &[other]).is_empty());
+}
+
+#[test]
+fn parses_single_accounts_and_rpc_responses() {
+    let accounts = snapshot();
+    let json: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/snapshot.json"
+        ))
+        .unwrap(),
+    )
+    .unwrap();
+
+    let single = parse_accounts(&json[0].to_string()).unwrap();
+    assert_eq!(single, accounts[..1]);
+
+    let response = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": json });
+    assert_eq!(parse_accounts(&response.to_string()).unwrap(), accounts);
+
+    let with_context = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": { "context": { "slot": 1 }, "value": json } });
+    assert_eq!(parse_accounts(&with_context.to_string()).unwrap(), accounts);
+
+    let base58 = json[0].to_string().replace("\"base64\"", "\"base58\"");
+    assert!(parse_accounts(&base58).is_err());
+}
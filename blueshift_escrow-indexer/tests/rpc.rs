@@ -0,0 +1,126 @@
+//! The RPC client against a fake JSON-RPC server on loopback that serves
+//! `fixtures/snapshot.json`.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use blueshift_escrow_indexer::rpc::RpcClient;
+use blueshift_escrow_indexer::{OrderBook, Pubkey, ESCROW_PROGRAM_ID};
+use serde_json::{json, Value};
+
+fn fixture() -> Vec<Value> {
+    let json = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/snapshot.json"
+    ))
+    .unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+fn respond(request: &Value, accounts: &[Value]) -> Value {
+    let params = &request["params"];
+    match request["method"].as_str().unwrap() {
+        "getProgramAccounts" => {
+            let owned: Vec<&Value> = accounts
+                .iter()
+                .filter(|account| account["account"]["owner"] == params[0])
+                .collect();
+            json!({ "jsonrpc": "2.0", "id": 1, "result": owned })
+        }
+        "getMultipleAccounts" => {
+            let found: Vec<Value> = params[0]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|key| {
+                    accounts
+                        .iter()
+                        .find(|account| account["pubkey"] == *key)
+                        .map_or(Value::Null, |account| account["account"].clone())
+                })
+                .collect();
+            json!({ "jsonrpc": "2.0", "id": 1, "result": { "context": { "slot": 1 }, "value": found } })
+        }
+        _ => {
+            json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "Method not found" } })
+        }
+    }
+}
+
+/// Serve `requests` requests, sending large bodies chunked as a validator does.
+fn serve(requests: usize) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let accounts = fixture();
+
+    thread::spawn(move || {
+        for stream in listener.incoming().take(requests) {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = respond(&serde_json::from_slice(&body).unwrap(), &accounts).to_string();
+            if response.len() > 1024 {
+                write!(stream, "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nTransfer-Encoding: chunked\r\n\r\n").unwrap();
+                for chunk in response.as_bytes().chunks(1000) {
+                    write!(stream, "{:x}\r\n", chunk.len()).unwrap();
+                    stream.write_all(chunk).unwrap();
+                    write!(stream, "\r\n").unwrap();
+                }
+                write!(stream, "0\r\n\r\n").unwrap();
+            } else {
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{response}",
+                    response.len()
+                )
+                .unwrap();
+            }
+        }
+    });
+    url
+}
+
+#[test]
+fn snapshot_fetches_escrows_and_their_vaults() {
+    // getProgramAccounts, then one getMultipleAccounts for the vaults
+    let client = RpcClient::new(&serve(2)).unwrap();
+    let accounts = client.snapshot(&[ESCROW_PROGRAM_ID]).unwrap();
+
+    let book = OrderBook::from_accounts(&accounts, &[ESCROW_PROGRAM_ID]);
+    let mint_a = Pubkey::new_from_array([0xa1; 32]);
+    let mint_b = Pubkey::new_from_array([0xb2; 32]);
+    let amounts: Vec<Option<u64>> = book
+        .pair(&mint_a, &mint_b)
+        .iter()
+        .map(|order| order.vault_amount)
+        .collect();
+    assert_eq!(amounts, [Some(1000), Some(1000), Some(300), None]);
+    assert_eq!(book.pair(&mint_b, &mint_a).len(), 1);
+}
+
+#[test]
+fn surfaces_rpc_errors() {
+    let client = RpcClient::new(&serve(1)).unwrap();
+    let error = client.call("getSlot", json!([])).unwrap_err();
+    assert_eq!(error.to_string(), "RPC error: Method not found");
+}
+
+#[test]
+fn rejects_non_http_urls() {
+    assert!(RpcClient::new("https://api.mainnet-beta.solana.com").is_err());
+    assert!(RpcClient::new("http://127.0.0.1:8899").is_ok());
+}
@@ -0,0 +1,426 @@
+//! CPI helpers for programs that compose with the escrow. Enable with the `cpi`
+//! feature, which also drops this crate's entrypoint.
+//!
+//! ```ignore
+//! blueshift_pinocchio_escrow::cpi::Take { taker, maker, escrow, .. }.invoke()?;
+//! ```
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::get_return_data,
+    instruction::{AccountMeta, Instruction, Signer},
+    program::invoke_signed_with_bounds,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::instructions::{EscrowKind, EscrowQuote};
+
+/// Create an escrow and deposit token A into its vault.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Maker.
+///   1. `[WRITE]` Escrow PDA ([b"escrow", maker, seed]).
+///   2. `[]` Mint A.
+///   3. `[]` Mint B.
+///   4. `[WRITE]` Maker's token A account.
+///   5. `[WRITE]` Vault (escrow's associated token account for mint A).
+///   6. `[]` Token program.
+///   7. `[]` Associated token program.
+///   8. `[]` System program.
+///   9. `[WRITE, SIGNER]` Payer, if not the maker (optional).
+pub struct Make<'a> {
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+    pub mint_a: &'a AccountInfo,
+    pub mint_b: &'a AccountInfo,
+    pub maker_ata_a: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    /// Funds the escrow and vault accounts instead of the maker.
+    pub payer: Option<&'a AccountInfo>,
+    /// Escrow seed.
+    pub seed: u64,
+    /// Amount of token B the maker wants.
+    pub receive: u64,
+    /// Amount of token A to deposit.
+    pub amount: u64,
+    /// Receives the escrow's and vault's rent on close; the payer if `None`.
+    pub rent_recipient: Option<Pubkey>,
+}
+
+impl Make<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            invoke_signed_with_bounds::<10>(instruction, accounts, signers)
+        })
+    }
+
+    /// Build the escrow instruction and pass it to `f` with the account infos to
+    /// invoke it with.
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let payer = self.payer.unwrap_or(self.maker);
+        let account_metas: [AccountMeta; 10] = [
+            AccountMeta::writable_signer(self.maker.key()),
+            AccountMeta::writable(self.escrow.key()),
+            AccountMeta::readonly(self.mint_a.key()),
+            AccountMeta::readonly(self.mint_b.key()),
+            AccountMeta::writable(self.maker_ata_a.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::readonly(self.token_program.key()),
+            AccountMeta::readonly(self.associated_token_program.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::writable_signer(payer.key()),
+        ];
+        let accounts_len = if self.payer.is_some() { 10 } else { 9 };
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..9]: seed (8 bytes, u64)
+        // -  [9..17]: receive (8 bytes, u64)
+        // -  [17..25]: amount (8 bytes, u64)
+        // -  [25..57]: rent_recipient (32 bytes, Pubkey, omitted for the payer)
+        let mut instruction_data = [0u8; 57];
+        instruction_data[1..9].copy_from_slice(&self.seed.to_le_bytes());
+        instruction_data[9..17].copy_from_slice(&self.receive.to_le_bytes());
+        instruction_data[17..25].copy_from_slice(&self.amount.to_le_bytes());
+        let data_len = match &self.rent_recipient {
+            Some(rent_recipient) => {
+                instruction_data[25..57].copy_from_slice(rent_recipient);
+                57
+            }
+            None => 25,
+        };
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas[..accounts_len],
+            data: &instruction_data[..data_len],
+        };
+
+        f(
+            &instruction,
+            &[
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.mint_b,
+                self.maker_ata_a,
+                self.vault,
+                self.token_program,
+                self.associated_token_program,
+                self.system_program,
+                payer,
+            ][..accounts_len],
+        )
+    }
+}
+
+/// Take an escrow: pay its `receive` in token B, receive the vault's token A.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Taker.
+///   1. `[WRITE]` Maker.
+///   2. `[WRITE]` Escrow PDA.
+///   3. `[]` Mint A.
+///   4. `[]` Mint B.
+///   5. `[WRITE]` Vault.
+///   6. `[WRITE]` Taker's token A account.
+///   7. `[WRITE]` Taker's token B account.
+///   8. `[WRITE]` Maker's token B account.
+///   9. `[]` System program.
+///  10. `[]` Token program.
+///  11. `[]` Associated token program.
+///  12. `[WRITE]` Rent recipient recorded in the escrow.
+pub struct Take<'a> {
+    pub taker: &'a AccountInfo,
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+    pub mint_a: &'a AccountInfo,
+    pub mint_b: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub taker_ata_a: &'a AccountInfo,
+    pub taker_ata_b: &'a AccountInfo,
+    pub maker_ata_b: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+    pub rent_recipient: &'a AccountInfo,
+    /// Fail unless the escrow's `receive` equals this.
+    pub expected_receive: Option<u64>,
+    /// Fail unless the vault holds at least this much token A.
+    pub min_amount_a: Option<u64>,
+}
+
+impl Take<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            invoke_signed_with_bounds::<13>(instruction, accounts, signers)
+        })
+    }
+
+    /// Build the escrow instruction and pass it to `f` with the account infos to
+    /// invoke it with.
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let account_metas: [AccountMeta; 13] = [
+            AccountMeta::writable_signer(self.taker.key()),
+            AccountMeta::writable(self.maker.key()),
+            AccountMeta::writable(self.escrow.key()),
+            AccountMeta::readonly(self.mint_a.key()),
+            AccountMeta::readonly(self.mint_b.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::writable(self.taker_ata_a.key()),
+            AccountMeta::writable(self.taker_ata_b.key()),
+            AccountMeta::writable(self.maker_ata_b.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::readonly(self.token_program.key()),
+            AccountMeta::readonly(self.associated_token_program.key()),
+            AccountMeta::writable(self.rent_recipient.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..9]: expected_receive (8 bytes, u64, 0 = unchecked)
+        // -  [9..17]: min_amount_a (8 bytes, u64, 0 = unchecked)
+        let mut instruction_data = [0u8; 17];
+        instruction_data[0] = 1;
+        instruction_data[1..9].copy_from_slice(&self.expected_receive.unwrap_or(0).to_le_bytes());
+        instruction_data[9..17].copy_from_slice(&self.min_amount_a.unwrap_or(0).to_le_bytes());
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas,
+            data: &instruction_data,
+        };
+
+        f(
+            &instruction,
+            &[
+                self.taker,
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.mint_b,
+                self.vault,
+                self.taker_ata_a,
+                self.taker_ata_b,
+                self.maker_ata_b,
+                self.system_program,
+                self.token_program,
+                self.associated_token_program,
+                self.rent_recipient,
+            ],
+        )
+    }
+}
+
+/// Refund an escrow: return the vault's token A to the maker.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Maker.
+///   1. `[WRITE]` Escrow PDA.
+///   2. `[]` Mint A.
+///   3. `[WRITE]` Vault.
+///   4. `[WRITE]` Maker's token A account.
+///   5. `[]` System program.
+///   6. `[]` Token program.
+///   7. `[WRITE]` Rent recipient recorded in the escrow.
+pub struct Refund<'a> {
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+    pub mint_a: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub maker_ata_a: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub rent_recipient: &'a AccountInfo,
+}
+
+impl Refund<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            invoke_signed_with_bounds::<8>(instruction, accounts, signers)
+        })
+    }
+
+    /// Build the escrow instruction and pass it to `f` with the account infos to
+    /// invoke it with.
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let account_metas: [AccountMeta; 8] = [
+            AccountMeta::writable_signer(self.maker.key()),
+            AccountMeta::writable(self.escrow.key()),
+            AccountMeta::readonly(self.mint_a.key()),
+            AccountMeta::writable(self.vault.key()),
+            AccountMeta::writable(self.maker_ata_a.key()),
+            AccountMeta::readonly(self.system_program.key()),
+            AccountMeta::readonly(self.token_program.key()),
+            AccountMeta::writable(self.rent_recipient.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas,
+            data: &[2],
+        };
+
+        f(
+            &instruction,
+            &[
+                self.maker,
+                self.escrow,
+                self.mint_a,
+                self.vault,
+                self.maker_ata_a,
+                self.system_program,
+                self.token_program,
+                self.rent_recipient,
+            ],
+        )
+    }
+}
+
+/// Sweep a stray token account owned by the maker's escrow PDA back to the maker.
+///
+/// ### Accounts:
+///   0. `[WRITE, SIGNER]` Maker.
+///   1. `[]` Escrow PDA of `kind` ([prefix, maker, seed]), possibly closed.
+///   2. `[]` Mint of the swept account.
+///   3. `[WRITE]` Token account owned by the escrow PDA.
+///   4. `[WRITE]` Maker's token account for the mint.
+///   5. `[]` Token program owning the token account: SPL Token or Token-2022.
+pub struct Sweep<'a> {
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub token_account: &'a AccountInfo,
+    pub maker_token_account: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    /// Escrow seed.
+    pub seed: u64,
+    /// Which of the maker's escrow PDAs owns the token account.
+    pub kind: EscrowKind,
+}
+
+impl Sweep<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            invoke_signed_with_bounds::<6>(instruction, accounts, signers)
+        })
+    }
+
+    /// Build the escrow instruction and pass it to `f` with the account infos to
+    /// invoke it with.
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let account_metas: [AccountMeta; 6] = [
+            AccountMeta::writable_signer(self.maker.key()),
+            AccountMeta::readonly(self.escrow.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::writable(self.token_account.key()),
+            AccountMeta::writable(self.maker_token_account.key()),
+            AccountMeta::readonly(self.token_program.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..9]: seed (8 bytes, u64)
+        // -  [9]: escrow kind (1 byte, u8)
+        let mut instruction_data = [11u8; 10];
+        instruction_data[1..9].copy_from_slice(&self.seed.to_le_bytes());
+        instruction_data[9] = self.kind as u8;
+
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas,
+            data: &instruction_data,
+        };
+
+        f(
+            &instruction,
+            &[
+                self.maker,
+                self.escrow,
+                self.mint,
+                self.token_account,
+                self.maker_token_account,
+                self.token_program,
+            ],
+        )
+    }
+}
+
+/// Quote an escrow: its terms and vault balance, set as return data.
+///
+/// ### Accounts:
+///   0. `[]` Escrow PDA.
+///   1. `[]` Vault.
+pub struct Quote<'a> {
+    pub escrow: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+}
+
+impl Quote<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        self.with_instruction(|instruction, accounts| {
+            invoke_signed_with_bounds::<2>(instruction, accounts, signers)
+        })
+    }
+
+    /// Build the escrow instruction and pass it to `f` with the account infos to
+    /// invoke it with.
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        let account_metas: [AccountMeta; 2] = [
+            AccountMeta::readonly(self.escrow.key()),
+            AccountMeta::readonly(self.vault.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        let instruction = Instruction {
+            program_id: &crate::ID,
+            accounts: &account_metas,
+            data: &[10],
+        };
+
+        f(&instruction, &[self.escrow, self.vault])
+    }
+
+    /// Invoke and decode the quote from the escrow program's return data.
+    pub fn get(&self) -> Result<EscrowQuote, ProgramError> {
+        self.invoke()?;
+
+        match get_return_data() {
+            Some(data) if data.program_id() == &crate::ID => EscrowQuote::from_bytes(&data),
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! CancelOrder instruction: maker burns a signed order's nonce by creating its receipt.
+
+use core::mem::size_of;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+use crate::instructions::helpers::create_order_receipt;
+
+/// CancelOrder instruction data: nonce (u64) of the order to cancel.
+pub struct CancelOrderInstructionData {
+    pub nonce: u64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for CancelOrderInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(Self {
+            nonce: u64::from_le_bytes(data.try_into().unwrap()),
+        })
+    }
+}
+
+/// CancelOrder accounts: maker, order_receipt, system_program.
+pub struct CancelOrderAccounts<'a> {
+    pub maker: &'a AccountInfo,
+    pub order_receipt: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for CancelOrderAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [maker, order_receipt, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !maker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if system_program.key() != &pinocchio_system::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        Ok(Self { maker, order_receipt })
+    }
+}
+
+pub struct CancelOrder<'a> {
+    pub accounts: CancelOrderAccounts<'a>,
+    pub data: CancelOrderInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for CancelOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: CancelOrderAccounts::try_from(accounts)?,
+            data: CancelOrderInstructionData::try_from(data)?,
+        })
+    }
+}
+
+impl<'a> CancelOrder<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        create_order_receipt(
+            self.accounts.maker,
+            self.accounts.order_receipt,
+            self.accounts.maker.key(),
+            self.data.nonce,
+        )
+    }
+}
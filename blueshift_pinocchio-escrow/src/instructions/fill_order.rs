@@ -0,0 +1,220 @@
+//! FillOrder instruction: settle a maker's off-chain signed order without a Make.
+//! The maker has approved their delegate PDA on maker_ata_a; the next instruction
+//! in the transaction must be an ed25519 verify of the maker signing the order.
+
+use core::{mem::size_of, ops::Deref};
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{
+        clock::Clock,
+        instructions::{Instructions, IntrospectedInstruction},
+        Sysvar,
+    },
+    ProgramResult,
+};
+use pinocchio_token::instructions::TransferChecked;
+
+use crate::instructions::helpers::{check_token_account, create_order_receipt, find_delegate_address};
+use crate::instructions::precompile::ed25519_signer_and_message;
+
+/// Signed order message: program id, maker, mint_a, mint_b, amount (token A offered),
+/// receive (token B wanted), nonce (u64), expiry (i64 unix timestamp); integers little-endian.
+pub struct SignedOrder {
+    pub program_id: Pubkey,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub amount: u64,
+    pub receive: u64,
+    pub nonce: u64,
+    pub expiry: i64,
+}
+
+impl SignedOrder {
+    pub const LEN: usize = size_of::<Pubkey>() * 4 + size_of::<u64>() * 4;
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for SignedOrder {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != SignedOrder::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let amount = u64::from_le_bytes(data[128..136].try_into().unwrap());
+        let receive = u64::from_le_bytes(data[136..144].try_into().unwrap());
+        if amount == 0 || receive == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(Self {
+            program_id: data[0..32].try_into().unwrap(),
+            maker: data[32..64].try_into().unwrap(),
+            mint_a: data[64..96].try_into().unwrap(),
+            mint_b: data[96..128].try_into().unwrap(),
+            amount,
+            receive,
+            nonce: u64::from_le_bytes(data[144..152].try_into().unwrap()),
+            expiry: i64::from_le_bytes(data[152..160].try_into().unwrap()),
+        })
+    }
+}
+
+/// FillOrder accounts: taker, maker, delegate, order_receipt, mint_a, mint_b, maker_ata_a, taker_ata_a, taker_ata_b, maker_ata_b, instructions, system_program, token_program.
+pub struct FillOrderAccounts<'a> {
+    pub taker: &'a AccountInfo,
+    pub maker: &'a AccountInfo,
+    pub delegate: &'a AccountInfo,
+    pub order_receipt: &'a AccountInfo,
+    pub mint_a: &'a AccountInfo,
+    pub mint_b: &'a AccountInfo,
+    pub maker_ata_a: &'a AccountInfo,
+    pub taker_ata_a: &'a AccountInfo,
+    pub taker_ata_b: &'a AccountInfo,
+    pub maker_ata_b: &'a AccountInfo,
+    pub instructions: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for FillOrderAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [
+            taker, maker, delegate, order_receipt, mint_a, mint_b,
+            maker_ata_a, taker_ata_a, taker_ata_b, maker_ata_b,
+            instructions, system_program, token_program,
+        ] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !taker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if token_program.key() != &pinocchio_token::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if system_program.key() != &pinocchio_system::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        check_token_account(maker_ata_a, mint_a.key(), maker.key())?;
+        check_token_account(maker_ata_b, mint_b.key(), maker.key())?;
+
+        Ok(Self {
+            taker,
+            maker,
+            delegate,
+            order_receipt,
+            mint_a,
+            mint_b,
+            maker_ata_a,
+            taker_ata_a,
+            taker_ata_b,
+            maker_ata_b,
+            instructions,
+            system_program,
+            token_program,
+        })
+    }
+}
+
+/// The order `maker` signed in the ed25519 verify instruction following the
+/// current one.
+pub fn signed_order<T: Deref<Target = [u8]>>(
+    instructions: &Instructions<T>,
+    maker: &Pubkey,
+) -> Result<SignedOrder, ProgramError> {
+    let ix: IntrospectedInstruction = instructions.get_instruction_relative(1)?;
+    let (signer, message) = ed25519_signer_and_message(&ix)?;
+    let order = SignedOrder::try_from(message)?;
+
+    if order.program_id != crate::ID {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    if signer != maker || &order.maker != maker {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(order)
+}
+
+pub struct FillOrder<'a> {
+    pub accounts: FillOrderAccounts<'a>,
+    pub order: SignedOrder,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for FillOrder<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = FillOrderAccounts::try_from(accounts)?;
+
+        let instructions: Instructions<Ref<[u8]>> = Instructions::try_from(accounts.instructions)?;
+        let order = signed_order(&instructions, accounts.maker.key())?;
+        if &order.mint_a != accounts.mint_a.key() || &order.mint_b != accounts.mint_b.key() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self { accounts, order })
+    }
+}
+
+impl<'a> FillOrder<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let maker_key = self.accounts.maker.key();
+
+        // Creating the receipt fails if the order was already filled or cancelled.
+        create_order_receipt(
+            self.accounts.taker,
+            self.accounts.order_receipt,
+            maker_key,
+            self.order.nonce,
+        )?;
+        if Clock::get()?.unix_timestamp > self.order.expiry {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (delegate_key, delegate_bump) = find_delegate_address(maker_key, &crate::ID);
+        if self.accounts.delegate.key() != &delegate_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let delegate_binding = [delegate_bump];
+        let delegate_seeds = [
+            Seed::from(b"delegate"),
+            Seed::from(maker_key.as_ref()),
+            Seed::from(&delegate_binding),
+        ];
+
+        let mint_b_data = self.accounts.mint_b.try_borrow_data()?;
+        let decimals_b = if mint_b_data.len() > 44 { mint_b_data[44] } else { return Err(ProgramError::InvalidAccountData) };
+        drop(mint_b_data);
+
+        TransferChecked {
+            from: self.accounts.taker_ata_b,
+            mint: self.accounts.mint_b,
+            to: self.accounts.maker_ata_b,
+            authority: self.accounts.taker,
+            amount: self.order.receive,
+            decimals: decimals_b,
+        }
+        .invoke()?;
+
+        let mint_a_data = self.accounts.mint_a.try_borrow_data()?;
+        let decimals_a = if mint_a_data.len() > 44 { mint_a_data[44] } else { return Err(ProgramError::InvalidAccountData) };
+        drop(mint_a_data);
+
+        TransferChecked {
+            from: self.accounts.maker_ata_a,
+            mint: self.accounts.mint_a,
+            to: self.accounts.taker_ata_a,
+            authority: self.accounts.delegate,
+            amount: self.order.amount,
+            decimals: decimals_a,
+        }
+        .invoke_signed(&[Signer::from(&delegate_seeds)])?;
+
+        Ok(())
+    }
+}
@@ -1,6 +1,20 @@
-//! PDA helpers for escrow.
+//! PDA and token account helpers for escrow.
 
-use pinocchio::pubkey::{find_program_address, Pubkey};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Seed, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::{find_program_address, Pubkey},
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::{Allocate, Assign, CreateAccount, Transfer};
+
+// SPL Token Account mint at offset 0, owner at offset 32, amount at offset 64.
+const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+const TOKEN_ACCOUNT_OWNER_OFFSET: usize = 32;
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
 
 /// Derive escrow PDA and bump. Seeds: [b"escrow", maker, seed_le_bytes].
 pub fn find_escrow_address(maker: &Pubkey, seed: u64, program_id: &Pubkey) -> (Pubkey, u8) {
@@ -9,3 +23,241 @@ pub fn find_escrow_address(maker: &Pubkey, seed: u64, program_id: &Pubkey) -> (P
         program_id,
     )
 }
+
+/// Derive attested escrow PDA and bump. Seeds: [b"attested", maker, seed_le_bytes].
+pub fn find_attested_escrow_address(maker: &Pubkey, seed: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[b"attested", maker.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derive limit escrow PDA and bump. Seeds: [b"limit", maker, seed_le_bytes].
+pub fn find_limit_escrow_address(maker: &Pubkey, seed: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[b"limit", maker.as_ref(), &seed.to_le_bytes()],
+        program_id,
+    )
+}
+
+// TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+/// Token-2022 program ID.
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xee, 0x75, 0x8f, 0xde, 0x18, 0x42, 0x5d, 0xbc, 0xe4, 0x6c, 0xcd, 0xda,
+    0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0x0d, 0x27, 0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc,
+];
+
+/// Derive a maker's order delegate PDA and bump. Seeds: [b"delegate", maker].
+/// Makers approve this PDA on their token A account to make signed orders fillable.
+pub fn find_delegate_address(maker: &Pubkey, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"delegate", maker.as_ref()], program_id)
+}
+
+/// Derive a signed order's receipt PDA and bump. Seeds: [b"order", maker, nonce_le_bytes].
+/// The receipt exists once the order is filled or cancelled.
+pub fn find_order_receipt_address(maker: &Pubkey, nonce: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[b"order", maker.as_ref(), &nonce.to_le_bytes()],
+        program_id,
+    )
+}
+
+/// Derive the associated token account of `wallet` for `mint` under the token program.
+pub fn find_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(
+        &[wallet.as_ref(), pinocchio_token::ID.as_ref(), mint.as_ref()],
+        &pinocchio_associated_token_account::ID,
+    )
+}
+
+/// Check that `token_account` is a token account for `mint` owned by `owner`.
+pub fn check_token_account(token_account: &AccountInfo, mint: &Pubkey, owner: &Pubkey) -> ProgramResult {
+    let data = token_account.try_borrow_data()?;
+    if data.len() < TOKEN_ACCOUNT_AMOUNT_OFFSET + 8 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if &data[TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32] != mint.as_ref()
+        || &data[TOKEN_ACCOUNT_OWNER_OFFSET..TOKEN_ACCOUNT_OWNER_OFFSET + 32] != owner.as_ref()
+    {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    Ok(())
+}
+
+/// Read the token amount held by `token_account`.
+pub fn token_account_amount(token_account: &AccountInfo) -> Result<u64, ProgramError> {
+    let data = token_account.try_borrow_data()?;
+    if data.len() < TOKEN_ACCOUNT_AMOUNT_OFFSET + 8 {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(u64::from_le_bytes(
+        data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap(),
+    ))
+}
+
+/// Close a program-owned `account`, moving all of its lamports to `destination`.
+pub fn close_program_account(account: &AccountInfo, destination: &AccountInfo) -> ProgramResult {
+    let lamports = destination
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    *destination.try_borrow_mut_lamports()? = lamports;
+    *account.try_borrow_mut_lamports()? = 0;
+    account.close()
+}
+
+/// Create a rent-exempt account of `space` bytes owned by this program at the PDA
+/// `account`, funded by `payer`. Anyone can send lamports to a PDA before it is
+/// created, which makes `CreateAccount` fail, so an address that already holds
+/// lamports is topped up, allocated and assigned instead.
+pub fn create_program_account(
+    payer: &AccountInfo,
+    account: &AccountInfo,
+    space: usize,
+    signers: &[Signer],
+) -> ProgramResult {
+    let lamports = Rent::get()?.minimum_balance(space);
+    if account.lamports() == 0 {
+        return CreateAccount {
+            from: payer,
+            to: account,
+            lamports,
+            space: space as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(signers);
+    }
+
+    let shortfall = lamports.saturating_sub(account.lamports());
+    if shortfall > 0 {
+        Transfer {
+            from: payer,
+            to: account,
+            lamports: shortfall,
+        }
+        .invoke()?;
+    }
+    Allocate {
+        account,
+        space: space as u64,
+    }
+    .invoke_signed(signers)?;
+    Assign {
+        account,
+        owner: &crate::ID,
+    }
+    .invoke_signed(signers)
+}
+
+/// Create the receipt of `maker`'s signed order `nonce`, paid for by `payer`. Fails
+/// if the receipt already exists, i.e. the order was already filled or cancelled.
+pub fn create_order_receipt(
+    payer: &AccountInfo,
+    receipt: &AccountInfo,
+    maker: &Pubkey,
+    nonce: u64,
+) -> ProgramResult {
+    if receipt.is_owned_by(&crate::ID) {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    let (receipt_key, bump) = find_order_receipt_address(maker, nonce, &crate::ID);
+    if receipt.key() != &receipt_key {
+        return Err(ProgramError::InvalidSeeds);
+    }
+
+    let nonce_bytes = nonce.to_le_bytes();
+    let binding = [bump];
+    let seeds = [
+        Seed::from(b"order"),
+        Seed::from(maker.as_ref()),
+        Seed::from(nonce_bytes.as_ref()),
+        Seed::from(&binding),
+    ];
+    create_program_account(payer, receipt, 0, &[Signer::from(&seeds)])
+}
+
+/// TransferChecked CPI to either token program; instruction 12 is the same in
+/// SPL Token and Token-2022.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Source token account
+///   1. `[]` Mint
+///   2. `[WRITE]` Destination token account
+///   3. `[SIGNER]` Source account's owner
+pub struct TransferChecked<'a> {
+    pub from: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub to: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TransferChecked<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas: [AccountMeta; 4] = [
+            AccountMeta::writable(self.from.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::writable(self.to.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        // Instruction data layout:
+        // -  [0]: instruction discriminator (1 byte, u8)
+        // -  [1..9]: amount (8 bytes, u64)
+        // -  [9]: decimals (1 byte, u8)
+        let mut instruction_data = [0; 10];
+        instruction_data[0] = 12;
+        instruction_data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: &instruction_data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.from, self.mint, self.to, self.authority],
+            signers,
+        )
+    }
+}
+
+/// CloseAccount CPI to either token program; instruction 9 is the same in SPL
+/// Token and Token-2022.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Token account to close
+///   1. `[WRITE]` Destination of its lamports
+///   2. `[SIGNER]` Token account's owner
+pub struct CloseAccount<'a> {
+    pub account: &'a AccountInfo,
+    pub destination: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl CloseAccount<'_> {
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas: [AccountMeta; 3] = [
+            AccountMeta::writable(self.account.key()),
+            AccountMeta::writable(self.destination.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: &[9],
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.account, self.destination, self.authority],
+            signers,
+        )
+    }
+}
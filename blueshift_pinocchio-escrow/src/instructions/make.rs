@@ -5,6 +5,7 @@ use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
+    pubkey::Pubkey,
     sysvars::{rent::Rent, Sysvar},
     ProgramResult,
 };
@@ -15,11 +16,13 @@ use pinocchio_token::instructions::TransferChecked;
 use crate::instructions::helpers::find_escrow_address;
 use crate::state::Escrow;
 
-/// Make instruction data: seed (u64), receive (u64, amount of token B wanted), amount (u64, token A to deposit).
+/// Make instruction data: seed (u64), receive (u64, amount of token B wanted), amount (u64, token A to deposit),
+/// optionally followed by rent_recipient (Pubkey, receives the escrow's and vault's rent on close; defaults to the payer).
 pub struct MakeInstructionData {
     pub seed: u64,
     pub receive: u64,
     pub amount: u64,
+    pub rent_recipient: Option<Pubkey>,
 }
 
 impl MakeInstructionData {
@@ -30,20 +33,25 @@ impl<'a> core::convert::TryFrom<&'a [u8]> for MakeInstructionData {
     type Error = ProgramError;
 
     fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
-        if data.len() < MakeInstructionData::LEN {
-            return Err(ProgramError::InvalidInstructionData);
-        }
+        let rent_recipient = match data.len() {
+            MakeInstructionData::LEN => None,
+            len if len == MakeInstructionData::LEN + size_of::<Pubkey>() => {
+                Some(data[MakeInstructionData::LEN..].try_into().unwrap())
+            }
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
         let seed = u64::from_le_bytes(data[0..8].try_into().unwrap());
         let receive = u64::from_le_bytes(data[8..16].try_into().unwrap());
         let amount = u64::from_le_bytes(data[16..24].try_into().unwrap());
         if receive == 0 || amount == 0 {
             return Err(ProgramError::InvalidInstructionData);
         }
-        Ok(Self { seed, receive, amount })
+        Ok(Self { seed, receive, amount, rent_recipient })
     }
 }
 
-/// Make accounts: maker, escrow, mint_a, mint_b, maker_ata_a, vault, token_program, associated_token_program, system_program.
+/// Make accounts: maker, escrow, mint_a, mint_b, maker_ata_a, vault, token_program, associated_token_program, system_program,
+/// optionally followed by payer. The payer funds the escrow and vault accounts; without one the maker does.
 pub struct MakeAccounts<'a> {
     pub maker: &'a AccountInfo,
     pub escrow: &'a AccountInfo,
@@ -54,18 +62,25 @@ pub struct MakeAccounts<'a> {
     pub token_program: &'a AccountInfo,
     pub associated_token_program: &'a AccountInfo,
     pub system_program: &'a AccountInfo,
+    /// Funds account creation; the maker unless a separate payer was passed.
+    pub payer: &'a AccountInfo,
 }
 
 impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for MakeAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [maker, escrow, mint_a, mint_b, maker_ata_a, vault, token_program, associated_token_program, system_program] =
+        let [maker, escrow, mint_a, mint_b, maker_ata_a, vault, token_program, associated_token_program, system_program, payer @ ..] =
             accounts else {
                 return Err(ProgramError::NotEnoughAccountKeys);
             };
+        let payer = match payer {
+            [] => maker,
+            [payer] => payer,
+            _ => return Err(ProgramError::NotEnoughAccountKeys),
+        };
 
-        if !maker.is_signer() {
+        if !maker.is_signer() || !payer.is_signer() {
             return Err(ProgramError::InvalidAccountOwner);
         }
         if token_program.key() != &pinocchio_token::ID {
@@ -88,6 +103,7 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for MakeAccounts<'a> {
             token_program,
             associated_token_program,
             system_program,
+            payer,
         })
     }
 }
@@ -113,6 +129,41 @@ impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for Make<'a> {
     }
 }
 
+impl<'a> MakeAccounts<'a> {
+    /// Create the escrow's token A vault (ATA owned by `escrow`, funded by the payer)
+    /// and move `amount` token A into it from the maker.
+    pub fn create_vault_and_deposit(&self, amount: u64) -> ProgramResult {
+        Create {
+            funding_account: self.payer,
+            account: self.vault,
+            wallet: self.escrow,
+            mint: self.mint_a,
+            system_program: self.system_program,
+            token_program: self.token_program,
+        }
+        .invoke()?;
+
+        // SPL Mint decimals at offset 44
+        const MINT_DECIMALS_OFFSET: usize = 44;
+        let mint_data = self.mint_a.try_borrow_data()?;
+        if mint_data.len() <= MINT_DECIMALS_OFFSET {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let decimals = mint_data[MINT_DECIMALS_OFFSET];
+        TransferChecked {
+            from: self.maker_ata_a,
+            mint: self.mint_a,
+            to: self.vault,
+            authority: self.maker,
+            amount,
+            decimals,
+        }
+        .invoke()?;
+
+        Ok(())
+    }
+}
+
 impl<'a> Make<'a> {
     pub fn process(&mut self) -> ProgramResult {
         let rent = Rent::get()?;
@@ -130,7 +181,7 @@ impl<'a> Make<'a> {
         let signers = [Signer::from(&seeds)];
 
         CreateAccount {
-            from: self.accounts.maker,
+            from: self.accounts.payer,
             to: self.accounts.escrow,
             lamports,
             space: Escrow::LEN as u64,
@@ -138,18 +189,8 @@ impl<'a> Make<'a> {
         }
         .invoke_signed(&signers)?;
 
-        Create {
-            funding_account: self.accounts.maker,
-            account: self.accounts.vault,
-            wallet: self.accounts.escrow,
-            mint: self.accounts.mint_a,
-            system_program: self.accounts.system_program,
-            token_program: self.accounts.token_program,
-        }
-        .invoke()?;
-
         let mut escrow_data = self.accounts.escrow.try_borrow_mut_data()?;
-        let escrow = Escrow::load_mut(&mut *escrow_data)?;
+        let escrow = Escrow::load_mut(&mut escrow_data)?;
         escrow.set_inner(
             self.data.seed,
             *self.accounts.maker.key(),
@@ -157,25 +198,10 @@ impl<'a> Make<'a> {
             *self.accounts.mint_b.key(),
             self.data.receive,
             [bump],
+            self.data.rent_recipient.unwrap_or(*self.accounts.payer.key()),
         );
+        drop(escrow_data);
 
-        // SPL Mint decimals at offset 44
-        const MINT_DECIMALS_OFFSET: usize = 44;
-        let mint_data = self.accounts.mint_a.try_borrow_data()?;
-        if mint_data.len() <= MINT_DECIMALS_OFFSET {
-            return Err(ProgramError::InvalidAccountData);
-        }
-        let decimals = mint_data[MINT_DECIMALS_OFFSET];
-        TransferChecked {
-            from: self.accounts.maker_ata_a,
-            mint: self.accounts.mint_a,
-            to: self.accounts.vault,
-            authority: self.accounts.maker,
-            amount: self.data.amount,
-            decimals,
-        }
-        .invoke()?;
-
-        Ok(())
+        self.accounts.create_vault_and_deposit(self.data.amount)
     }
 }
@@ -0,0 +1,110 @@
+//! MakeAttested instruction: like Make, but records a secp256r1 attester whose
+//! signature is required for the escrow to be taken.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::instructions::helpers::find_attested_escrow_address;
+use crate::instructions::make::{MakeAccounts, MakeInstructionData};
+use crate::state::{AttestedEscrow, Secp256r1Pubkey};
+
+/// MakeAttested instruction data: Make data (seed, receive, amount) followed by the attester's compressed secp256r1 key (33 bytes).
+pub struct MakeAttestedInstructionData {
+    pub make: MakeInstructionData,
+    pub attester: Secp256r1Pubkey,
+}
+
+impl MakeAttestedInstructionData {
+    pub const LEN: usize = MakeInstructionData::LEN + core::mem::size_of::<Secp256r1Pubkey>();
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for MakeAttestedInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != MakeAttestedInstructionData::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let make = MakeInstructionData::try_from(&data[..MakeInstructionData::LEN])?;
+        let attester = data[MakeInstructionData::LEN..MakeAttestedInstructionData::LEN]
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+        Ok(Self { make, attester })
+    }
+}
+
+/// MakeAttested accounts are the same as Make; `escrow` is the [b"attested", maker, seed] PDA.
+/// Attested escrows refund their rent to the maker, so there is no separate payer.
+pub struct MakeAttested<'a> {
+    pub accounts: MakeAccounts<'a>,
+    pub data: MakeAttestedInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for MakeAttested<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MakeAccounts::try_from(accounts)?;
+        let data = MakeAttestedInstructionData::try_from(data)?;
+        if accounts.payer.key() != accounts.maker.key() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let (escrow_key, _bump) =
+            find_attested_escrow_address(accounts.maker.key(), data.make.seed, &crate::ID);
+        if accounts.escrow.key() != &escrow_key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> MakeAttested<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let rent = Rent::get()?;
+        let lamports = rent.minimum_balance(AttestedEscrow::LEN);
+
+        let (_, bump) =
+            find_attested_escrow_address(self.accounts.maker.key(), self.data.make.seed, &crate::ID);
+        let bump_binding = [bump];
+        let seed_bytes = self.data.make.seed.to_le_bytes();
+        let seeds = [
+            Seed::from(b"attested"),
+            Seed::from(self.accounts.maker.key().as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(bump_binding.as_ref()),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        CreateAccount {
+            from: self.accounts.maker,
+            to: self.accounts.escrow,
+            lamports,
+            space: AttestedEscrow::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+
+        let mut escrow_data = self.accounts.escrow.try_borrow_mut_data()?;
+        let escrow = AttestedEscrow::load_mut(&mut escrow_data)?;
+        escrow.set_inner(
+            self.data.make.seed,
+            *self.accounts.maker.key(),
+            *self.accounts.mint_a.key(),
+            *self.accounts.mint_b.key(),
+            self.data.make.receive,
+            [bump],
+            self.data.attester,
+        );
+        drop(escrow_data);
+
+        self.accounts.create_vault_and_deposit(self.data.make.amount)
+    }
+}
@@ -0,0 +1,124 @@
+//! MakeLimit instruction: like Make, but instead of a fixed `receive` the maker
+//! sets a limit price and a price feed that `TakeLimit` settles at.
+
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::instructions::helpers::find_limit_escrow_address;
+use crate::instructions::make::MakeAccounts;
+use crate::state::LimitEscrow;
+
+/// MakeLimit instruction data: seed (u64), limit_price (u64, minimum token B per token A, scaled by `QUOTE_PRICE_SCALE`),
+/// amount (u64, token A to deposit), max_price_age (u64, slots), price_feed (Pubkey, a `PriceFeed` account for mint A / mint B).
+pub struct MakeLimitInstructionData {
+    pub seed: u64,
+    pub limit_price: u64,
+    pub amount: u64,
+    pub max_price_age: u64,
+    pub price_feed: Pubkey,
+}
+
+impl MakeLimitInstructionData {
+    pub const LEN: usize = size_of::<u64>() * 4 + size_of::<Pubkey>();
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for MakeLimitInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != MakeLimitInstructionData::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let limit_price = u64::from_le_bytes(data[8..16].try_into().unwrap());
+        let amount = u64::from_le_bytes(data[16..24].try_into().unwrap());
+        if limit_price == 0 || amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(Self {
+            seed: u64::from_le_bytes(data[0..8].try_into().unwrap()),
+            limit_price,
+            amount,
+            max_price_age: u64::from_le_bytes(data[24..32].try_into().unwrap()),
+            price_feed: data[32..64].try_into().unwrap(),
+        })
+    }
+}
+
+/// MakeLimit accounts are the same as Make; `escrow` is the [b"limit", maker, seed] PDA.
+/// Limit escrows refund their rent to the maker, so there is no separate payer.
+pub struct MakeLimit<'a> {
+    pub accounts: MakeAccounts<'a>,
+    pub data: MakeLimitInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for MakeLimit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MakeAccounts::try_from(accounts)?;
+        let data = MakeLimitInstructionData::try_from(data)?;
+        if accounts.payer.key() != accounts.maker.key() {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let (escrow_key, _bump) =
+            find_limit_escrow_address(accounts.maker.key(), data.seed, &crate::ID);
+        if accounts.escrow.key() != &escrow_key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { accounts, data })
+    }
+}
+
+impl<'a> MakeLimit<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let rent = Rent::get()?;
+        let lamports = rent.minimum_balance(LimitEscrow::LEN);
+
+        let (_, bump) =
+            find_limit_escrow_address(self.accounts.maker.key(), self.data.seed, &crate::ID);
+        let bump_binding = [bump];
+        let seed_bytes = self.data.seed.to_le_bytes();
+        let seeds = [
+            Seed::from(b"limit"),
+            Seed::from(self.accounts.maker.key().as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(bump_binding.as_ref()),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        CreateAccount {
+            from: self.accounts.maker,
+            to: self.accounts.escrow,
+            lamports,
+            space: LimitEscrow::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+
+        let mut escrow_data = self.accounts.escrow.try_borrow_mut_data()?;
+        let escrow = LimitEscrow::load_mut(&mut escrow_data)?;
+        escrow.set_inner(
+            self.data.seed,
+            *self.accounts.maker.key(),
+            *self.accounts.mint_a.key(),
+            *self.accounts.mint_b.key(),
+            self.data.limit_price,
+            self.data.max_price_age,
+            self.data.price_feed,
+            [bump],
+        );
+        drop(escrow_data);
+
+        self.accounts.create_vault_and_deposit(self.data.amount)
+    }
+}
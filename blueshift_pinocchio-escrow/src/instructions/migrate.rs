@@ -0,0 +1,88 @@
+//! Migrate instruction: maker upgrades a v1 escrow account to the current layout in
+//! place, topping up its rent for the larger size.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::create_program_address,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::state::{Escrow, EscrowV1};
+
+/// Migrate accounts: maker, escrow, system_program.
+pub struct MigrateAccounts<'a> {
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for MigrateAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [maker, escrow, system_program] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !maker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if system_program.key() != &pinocchio_system::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if !escrow.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_data = escrow.try_borrow_data()?;
+        let escrow_state = EscrowV1::load(&escrow_data)?;
+        if escrow_state.maker != *maker.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_key = create_program_address(
+            &[b"escrow", maker.key().as_ref(), &escrow_state.seed.to_le_bytes(), &escrow_state.bump],
+            &crate::ID,
+        )?;
+        if escrow.key() != &escrow_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self { maker, escrow })
+    }
+}
+
+pub struct Migrate<'a> {
+    pub accounts: MigrateAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Migrate<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: MigrateAccounts::try_from(accounts)?,
+        })
+    }
+}
+
+impl<'a> Migrate<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let lamports = Rent::get()?.minimum_balance(Escrow::LEN);
+        let shortfall = lamports.saturating_sub(self.accounts.escrow.lamports());
+        if shortfall > 0 {
+            Transfer {
+                from: self.accounts.maker,
+                to: self.accounts.escrow,
+                lamports: shortfall,
+            }
+            .invoke()?;
+        }
+
+        self.accounts.escrow.resize(Escrow::LEN)?;
+        let mut escrow_data = self.accounts.escrow.try_borrow_mut_data()?;
+        Escrow::upgrade_from_v1(&mut escrow_data)?;
+
+        Ok(())
+    }
+}
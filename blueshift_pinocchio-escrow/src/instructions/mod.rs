@@ -1,8 +1,33 @@
+pub mod cancel_order;
+pub mod fill_order;
 pub mod helpers;
 pub mod make;
+pub mod make_attested;
+pub mod make_limit;
+pub mod migrate;
+pub mod precompile;
+pub mod quote;
 pub mod refund;
+pub mod refund_attested;
+pub mod refund_limit;
+pub mod sweep;
 pub mod take;
+pub mod take_attested;
+pub mod take_limit;
+pub mod take_many;
 
+pub use cancel_order::*;
+pub use fill_order::*;
 pub use make::*;
+pub use make_attested::*;
+pub use make_limit::*;
+pub use migrate::*;
+pub use quote::*;
 pub use refund::*;
+pub use refund_attested::*;
+pub use refund_limit::*;
+pub use sweep::*;
 pub use take::*;
+pub use take_attested::*;
+pub use take_limit::*;
+pub use take_many::*;
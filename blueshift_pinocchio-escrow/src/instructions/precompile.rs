@@ -0,0 +1,95 @@
+//! Signature precompile introspection: read back the key and message a
+//! signature-verify instruction in the same transaction has checked.
+
+use pinocchio::{
+    program_error::ProgramError, pubkey::Pubkey, sysvars::instructions::IntrospectedInstruction,
+};
+
+use pinocchio_secp256r1_instruction::Secp256r1Instruction;
+
+// Secp256r1SigVerify1111111111111111111111111
+pub const SECP256R1_PROGRAM_ID: Pubkey = [
+    0x06, 0x92, 0x0d, 0xec, 0x2f, 0xea, 0x71, 0xb5, 0xb7, 0x23, 0x81, 0x4d, 0x74, 0x2d, 0xa9, 0x03,
+    0x1c, 0x83, 0xe7, 0x5f, 0xdb, 0x79, 0x5d, 0x56, 0x8e, 0x75, 0x47, 0x80, 0x20, 0x00, 0x00, 0x00,
+];
+
+/// Precompile data: num_signatures (u8), padding (u8), then one 14-byte offsets
+/// entry per signature (seven little-endian u16s).
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_LEN: usize = 14;
+
+/// `*_instruction_index` value meaning "this same instruction".
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Check that precompile `data` carries exactly one signature whose public key
+/// and message both live in its own data, and return its offsets entry as
+/// (signature offset, signature ix, public key offset, public key ix, message
+/// offset, message size, message ix).
+fn single_signature_offsets(data: &[u8]) -> Result<[u16; 7], ProgramError> {
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN || data[0] != 1 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    let offsets = &data[SIGNATURE_OFFSETS_START..SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_LEN];
+    let fields: [u16; 7] =
+        core::array::from_fn(|i| u16::from_le_bytes([offsets[i * 2], offsets[i * 2 + 1]]));
+
+    // Data referenced from another instruction would not be what this one verified.
+    if fields[1] != CURRENT_INSTRUCTION
+        || fields[3] != CURRENT_INSTRUCTION
+        || fields[6] != CURRENT_INSTRUCTION
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+
+    Ok(fields)
+}
+
+/// Parse a precompile instruction carrying exactly one signature whose public key
+/// and message both live in its own data. Returns (public key, message).
+fn single_signature(data: &[u8], pubkey_len: usize) -> Result<(&[u8], &[u8]), ProgramError> {
+    let [_, _, public_key_offset, _, message_offset, message_size, _] =
+        single_signature_offsets(data)?;
+
+    let public_key = data
+        .get(public_key_offset as usize..public_key_offset as usize + pubkey_len)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let message = data
+        .get(message_offset as usize..message_offset as usize + message_size as usize)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    Ok((public_key, message))
+}
+
+/// A single-signature secp256r1 verify instruction, read with
+/// `pinocchio-secp256r1-instruction` as the secp256r1 vault does.
+pub fn secp256r1_instruction<'a>(
+    ix: &'a IntrospectedInstruction<'a>,
+) -> Result<Secp256r1Instruction<'a>, ProgramError> {
+    if ix.get_program_id() != &SECP256R1_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let data = ix.get_instruction_data();
+    single_signature_offsets(data)?;
+
+    Secp256r1Instruction::try_from(data).map_err(|_| ProgramError::InvalidInstructionData)
+}
+
+// Ed25519SigVerify111111111111111111111111111
+pub const ED25519_PROGRAM_ID: Pubkey = [
+    0x03, 0x7d, 0x46, 0xd6, 0x7c, 0x93, 0xfb, 0xbe, 0x12, 0xf9, 0x42, 0x8f, 0x83, 0x8d, 0x40, 0xff,
+    0x05, 0x70, 0x74, 0x49, 0x27, 0xf4, 0x8a, 0x64, 0xfc, 0xca, 0x70, 0x44, 0x80, 0x00, 0x00, 0x00,
+];
+
+/// Signer key and message of a single-signature ed25519 verify instruction.
+pub fn ed25519_signer_and_message<'a>(
+    ix: &'a IntrospectedInstruction<'a>,
+) -> Result<(&'a Pubkey, &'a [u8]), ProgramError> {
+    if ix.get_program_id() != &ED25519_PROGRAM_ID {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+    let (public_key, message) =
+        single_signature(ix.get_instruction_data(), core::mem::size_of::<Pubkey>())?;
+
+    Ok((public_key.try_into().map_err(|_| ProgramError::InvalidInstructionData)?, message))
+}
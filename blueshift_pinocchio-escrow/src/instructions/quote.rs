@@ -0,0 +1,145 @@
+//! Quote instruction: read-only; returns the escrow's terms and vault balance as
+//! return data, for `simulateTransaction` and for programs pricing escrows by CPI.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    cpi::set_return_data,
+    program_error::ProgramError,
+    pubkey::{create_program_address, Pubkey},
+    ProgramResult,
+};
+
+use crate::instructions::helpers::{find_associated_token_address, token_account_amount};
+use crate::state::Escrow;
+
+/// Fixed-point scale of `EscrowQuote::price`.
+pub const QUOTE_PRICE_SCALE: u64 = 1_000_000_000;
+
+/// Return data of `Quote`. Little-endian and unpadded, the same bytes the Anchor
+/// escrow's `quote` returns.
+pub struct EscrowQuote {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    /// Token A on offer.
+    pub vault_amount: u64,
+    /// Token B the taker pays.
+    pub receive: u64,
+    /// `receive` per unit of token A in base units, scaled by `QUOTE_PRICE_SCALE`
+    /// (saturating); 0 when the vault is empty.
+    pub price: u64,
+}
+
+impl EscrowQuote {
+    pub const LEN: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8;
+
+    pub fn new(escrow: &Escrow, vault_amount: u64) -> Self {
+        let price = match vault_amount {
+            0 => 0,
+            _ => (escrow.receive as u128 * QUOTE_PRICE_SCALE as u128 / vault_amount as u128)
+                .min(u64::MAX as u128) as u64,
+        };
+
+        Self {
+            seed: escrow.seed,
+            maker: escrow.maker,
+            mint_a: escrow.mint_a,
+            mint_b: escrow.mint_b,
+            vault_amount,
+            receive: escrow.receive,
+            price,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mut bytes = [0u8; Self::LEN];
+        bytes[0..8].copy_from_slice(&self.seed.to_le_bytes());
+        bytes[8..40].copy_from_slice(&self.maker);
+        bytes[40..72].copy_from_slice(&self.mint_a);
+        bytes[72..104].copy_from_slice(&self.mint_b);
+        bytes[104..112].copy_from_slice(&self.vault_amount.to_le_bytes());
+        bytes[112..120].copy_from_slice(&self.receive.to_le_bytes());
+        bytes[120..128].copy_from_slice(&self.price.to_le_bytes());
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ProgramError> {
+        if bytes.len() != Self::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        Ok(Self {
+            seed: u64::from_le_bytes(bytes[0..8].try_into().unwrap()),
+            maker: bytes[8..40].try_into().unwrap(),
+            mint_a: bytes[40..72].try_into().unwrap(),
+            mint_b: bytes[72..104].try_into().unwrap(),
+            vault_amount: u64::from_le_bytes(bytes[104..112].try_into().unwrap()),
+            receive: u64::from_le_bytes(bytes[112..120].try_into().unwrap()),
+            price: u64::from_le_bytes(bytes[120..128].try_into().unwrap()),
+        })
+    }
+}
+
+/// Quote accounts: escrow (at its PDA), vault (the escrow's associated token account for mint A).
+pub struct QuoteAccounts<'a> {
+    pub escrow: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for QuoteAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [escrow, vault] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !escrow.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_data = escrow.try_borrow_data()?;
+        let escrow_state = Escrow::load(&escrow_data)?;
+        if !vault.is_owned_by(&pinocchio_token::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_key = create_program_address(
+            &[b"escrow", escrow_state.maker.as_ref(), &escrow_state.seed.to_le_bytes(), &escrow_state.bump],
+            &crate::ID,
+        )?;
+        if escrow.key() != &escrow_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        let (vault_key, _) = find_associated_token_address(escrow.key(), &escrow_state.mint_a);
+        if vault.key() != &vault_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        Ok(Self { escrow, vault })
+    }
+}
+
+pub struct Quote<'a> {
+    pub accounts: QuoteAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Quote<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: QuoteAccounts::try_from(accounts)?,
+        })
+    }
+}
+
+impl<'a> Quote<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let vault_amount = token_account_amount(self.accounts.vault)?;
+        let escrow_data = self.accounts.escrow.try_borrow_data()?;
+        let quote = EscrowQuote::new(Escrow::load(&escrow_data)?, vault_amount);
+
+        set_return_data(&quote.to_bytes());
+        Ok(())
+    }
+}
@@ -4,16 +4,27 @@ use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
+    pubkey::Pubkey,
     ProgramResult,
 };
-use pinocchio_system::instructions::Transfer;
 use pinocchio_token::instructions::{CloseAccount, TransferChecked};
 
-use crate::state::Escrow;
+use crate::instructions::helpers::close_program_account;
+use crate::state::{Escrow, EscrowV1};
 
 const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
 
-/// Refund accounts: maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program.
+/// The v1 fields of a current escrow, or of a v1 one not yet migrated, and who
+/// receives its rent: the maker for v1, which has no rent recipient.
+fn load_escrow(data: &[u8]) -> Result<(&EscrowV1, Pubkey), ProgramError> {
+    match Escrow::load(data) {
+        Ok(escrow) => Ok((EscrowV1::load(&data[..EscrowV1::LEN])?, escrow.rent_recipient)),
+        Err(_) => EscrowV1::load(data).map(|escrow| (escrow, escrow.maker)),
+    }
+}
+
+/// Refund accounts: maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, rent_recipient.
+/// The escrow may still be v1; see [`EscrowV1`].
 pub struct RefundAccounts<'a> {
     pub maker: &'a AccountInfo,
     pub escrow: &'a AccountInfo,
@@ -22,13 +33,15 @@ pub struct RefundAccounts<'a> {
     pub maker_ata_a: &'a AccountInfo,
     pub system_program: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
+    /// Receives the vault's and escrow's rent on close.
+    pub rent_recipient: &'a AccountInfo,
 }
 
 impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for RefundAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program] =
+        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program, rent_recipient] =
             accounts else {
                 return Err(ProgramError::NotEnoughAccountKeys);
             };
@@ -40,10 +53,13 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for RefundAccounts<'a> {
             return Err(ProgramError::InvalidAccountOwner);
         }
         let escrow_data = escrow.try_borrow_data()?;
-        let escrow_state = Escrow::load(&*escrow_data)?;
+        let (escrow_state, escrow_rent_recipient) = load_escrow(&escrow_data)?;
         if escrow_state.maker != *maker.key() || escrow_state.mint_a != *mint_a.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
+        if escrow_rent_recipient != *rent_recipient.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
 
         Ok(Self {
             maker,
@@ -53,6 +69,7 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for RefundAccounts<'a> {
             maker_ata_a,
             system_program,
             token_program,
+            rent_recipient,
         })
     }
 }
@@ -71,59 +88,60 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Refund<'a> {
     }
 }
 
-impl<'a> Refund<'a> {
-    pub fn process(&mut self) -> ProgramResult {
-        let escrow_data = self.accounts.escrow.try_borrow_data()?;
-        let escrow = Escrow::load(&*escrow_data)?;
-        let seed = escrow.seed;
-        let bump = escrow.bump[0];
-        drop(escrow_data);
-
-        let seed_bytes = seed.to_le_bytes();
-        let binding = [bump];
-        let seeds = [
-            Seed::from(b"escrow"),
-            Seed::from(self.accounts.maker.key().as_ref()),
-            Seed::from(seed_bytes.as_ref()),
-            Seed::from(&binding),
-        ];
-        let signers = [Signer::from(&seeds)];
-
-        let vault_data = self.accounts.vault.try_borrow_data()?;
+impl<'a> RefundAccounts<'a> {
+    /// Return the whole vault to the maker and close vault and escrow into
+    /// `rent_recipient`. `signers` must sign for the escrow PDA.
+    pub fn refund_and_close(&self, signers: &[Signer]) -> ProgramResult {
+        let vault_data = self.vault.try_borrow_data()?;
         if vault_data.len() < TOKEN_ACCOUNT_AMOUNT_OFFSET + 8 {
             return Err(ProgramError::InvalidAccountData);
         }
         let vault_amount = u64::from_le_bytes(vault_data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap());
         drop(vault_data);
 
-        let mint_a_data = self.accounts.mint_a.try_borrow_data()?;
+        let mint_a_data = self.mint_a.try_borrow_data()?;
         let decimals_a = if mint_a_data.len() > 44 { mint_a_data[44] } else { return Err(ProgramError::InvalidAccountData) };
         drop(mint_a_data);
 
         TransferChecked {
-            from: self.accounts.vault,
-            mint: self.accounts.mint_a,
-            to: self.accounts.maker_ata_a,
-            authority: self.accounts.escrow,
+            from: self.vault,
+            mint: self.mint_a,
+            to: self.maker_ata_a,
+            authority: self.escrow,
             amount: vault_amount,
             decimals: decimals_a,
         }
-        .invoke_signed(&signers)?;
+        .invoke_signed(signers)?;
 
         CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
+            account: self.vault,
+            destination: self.rent_recipient,
+            authority: self.escrow,
         }
-        .invoke_signed(&signers)?;
+        .invoke_signed(signers)?;
 
-        Transfer {
-            from: self.accounts.escrow,
-            to: self.accounts.maker,
-            lamports: self.accounts.escrow.lamports(),
-        }
-        .invoke_signed(&signers)?;
+        close_program_account(self.escrow, self.rent_recipient)
+    }
+}
+
+impl<'a> Refund<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let escrow_data = self.accounts.escrow.try_borrow_data()?;
+        let (escrow, _) = load_escrow(&escrow_data)?;
+        let seed = escrow.seed;
+        let bump = escrow.bump[0];
+        drop(escrow_data);
+
+        let seed_bytes = seed.to_le_bytes();
+        let binding = [bump];
+        let seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(self.accounts.maker.key().as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(&binding),
+        ];
+        let signers = [Signer::from(&seeds)];
 
-        Ok(())
+        self.accounts.refund_and_close(&signers)
     }
 }
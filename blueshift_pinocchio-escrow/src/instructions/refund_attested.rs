@@ -0,0 +1,74 @@
+//! RefundAttested instruction: Refund for an attested escrow (no attestation needed).
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::instructions::refund::RefundAccounts;
+use crate::state::AttestedEscrow;
+
+pub struct RefundAttested<'a> {
+    pub accounts: RefundAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for RefundAttested<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint_a, vault, maker_ata_a, system_program, token_program] =
+            accounts else {
+                return Err(ProgramError::NotEnoughAccountKeys);
+            };
+
+        if !maker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if !escrow.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_data = escrow.try_borrow_data()?;
+        let escrow_state = AttestedEscrow::load(&escrow_data)?;
+        if escrow_state.maker != *maker.key() || escrow_state.mint_a != *mint_a.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            accounts: RefundAccounts {
+                maker,
+                escrow,
+                mint_a,
+                vault,
+                maker_ata_a,
+                system_program,
+                token_program,
+                // Attested escrows record no rent recipient; their rent goes to the maker.
+                rent_recipient: maker,
+            },
+        })
+    }
+}
+
+impl<'a> RefundAttested<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let escrow_data = self.accounts.escrow.try_borrow_data()?;
+        let escrow = AttestedEscrow::load(&escrow_data)?;
+        let seed = escrow.seed;
+        let bump = escrow.bump[0];
+        drop(escrow_data);
+
+        let seed_bytes = seed.to_le_bytes();
+        let binding = [bump];
+        let seeds = [
+            Seed::from(b"attested"),
+            Seed::from(self.accounts.maker.key().as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(&binding),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        self.accounts.refund_and_close(&signers)
+    }
+}
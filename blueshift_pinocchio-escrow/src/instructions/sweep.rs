@@ -0,0 +1,219 @@
+//! Sweep instruction: maker recovers a stray token account owned by one of their
+//! escrow PDAs, e.g. tokens sent to a vault address after the escrow was taken.
+
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+use crate::instructions::helpers::{
+    check_token_account, find_attested_escrow_address, find_escrow_address,
+    find_limit_escrow_address, token_account_amount, CloseAccount, TransferChecked,
+    TOKEN_2022_PROGRAM_ID,
+};
+use crate::state::{AttestedEscrow, Escrow, EscrowV1, LimitEscrow};
+
+/// Which of the maker's escrow PDAs owns the swept token account; the
+/// discriminant is its byte in Sweep's instruction data.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EscrowKind {
+    /// [b"escrow", maker, seed], from Make.
+    Escrow = 0,
+    /// [b"attested", maker, seed], from MakeAttested.
+    Attested = 1,
+    /// [b"limit", maker, seed], from MakeLimit.
+    Limit = 2,
+}
+
+impl EscrowKind {
+    /// Seed prefix of this kind's PDA.
+    pub fn prefix(self) -> &'static [u8] {
+        match self {
+            Self::Escrow => b"escrow",
+            Self::Attested => b"attested",
+            Self::Limit => b"limit",
+        }
+    }
+
+    /// Derive the PDA and bump of `maker`'s escrow of this kind for `seed`.
+    pub fn find_address(self, maker: &Pubkey, seed: u64, program_id: &Pubkey) -> (Pubkey, u8) {
+        match self {
+            Self::Escrow => find_escrow_address(maker, seed, program_id),
+            Self::Attested => find_attested_escrow_address(maker, seed, program_id),
+            Self::Limit => find_limit_escrow_address(maker, seed, program_id),
+        }
+    }
+}
+
+/// Sweep instruction data: seed (u64) of the escrow PDA owning the token account,
+/// then optionally its kind (u8: 0 escrow, 1 attested, 2 limit; escrow if omitted).
+pub struct SweepInstructionData {
+    pub seed: u64,
+    pub kind: EscrowKind,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for SweepInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() < size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (seed, kind) = data.split_at(size_of::<u64>());
+        let kind = match kind {
+            [] | [0] => EscrowKind::Escrow,
+            [1] => EscrowKind::Attested,
+            [2] => EscrowKind::Limit,
+            _ => return Err(ProgramError::InvalidInstructionData),
+        };
+        Ok(Self {
+            seed: u64::from_le_bytes(seed.try_into().unwrap()),
+            kind,
+        })
+    }
+}
+
+/// Sweep accounts: maker, escrow, mint, token_account, maker_token_account, token_program.
+/// `escrow` is the maker's PDA of the given kind and seed and may already be closed;
+/// `token_program` is SPL Token or Token-2022, whichever owns `token_account`.
+pub struct SweepAccounts<'a> {
+    pub maker: &'a AccountInfo,
+    pub escrow: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub token_account: &'a AccountInfo,
+    pub maker_token_account: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for SweepAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [maker, escrow, mint, token_account, maker_token_account, token_program] = accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !maker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if token_program.key() != &pinocchio_token::ID
+            && token_program.key() != &TOKEN_2022_PROGRAM_ID
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if !token_account.is_owned_by(token_program.key()) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        check_token_account(token_account, mint.key(), escrow.key())?;
+        check_token_account(maker_token_account, mint.key(), maker.key())?;
+
+        Ok(Self {
+            maker,
+            escrow,
+            mint,
+            token_account,
+            maker_token_account,
+            token_program,
+        })
+    }
+}
+
+pub struct Sweep<'a> {
+    pub accounts: SweepAccounts<'a>,
+    pub data: SweepInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for Sweep<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: SweepAccounts::try_from(accounts)?,
+            data: SweepInstructionData::try_from(data)?,
+        })
+    }
+}
+
+impl<'a> SweepAccounts<'a> {
+    /// While the escrow is open its token A belongs to Take or Refund, not Sweep.
+    fn check_not_open_escrow_mint(&self) -> ProgramResult {
+        if !self.escrow.is_owned_by(&crate::ID) {
+            return Ok(());
+        }
+        let escrow_data = self.escrow.try_borrow_data()?;
+        // Layout lengths are distinct, so at most one of these loads.
+        let mint_a = if let Ok(escrow) = Escrow::load(&escrow_data) {
+            escrow.mint_a
+        } else if let Ok(escrow) = EscrowV1::load(&escrow_data) {
+            escrow.mint_a
+        } else if let Ok(escrow) = AttestedEscrow::load(&escrow_data) {
+            escrow.mint_a
+        } else if let Ok(escrow) = LimitEscrow::load(&escrow_data) {
+            escrow.mint_a
+        } else {
+            return Ok(());
+        };
+        if &mint_a == self.mint.key() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(())
+    }
+}
+
+impl<'a> Sweep<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let maker_key = self.accounts.maker.key();
+        let kind = self.data.kind;
+        let (escrow_key, bump) = kind.find_address(maker_key, self.data.seed, &crate::ID);
+        if self.accounts.escrow.key() != &escrow_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+        self.accounts.check_not_open_escrow_mint()?;
+
+        let seed_bytes = self.data.seed.to_le_bytes();
+        let binding = [bump];
+        let seeds = [
+            Seed::from(kind.prefix()),
+            Seed::from(maker_key.as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(&binding),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        let amount = token_account_amount(self.accounts.token_account)?;
+        if amount > 0 {
+            let mint_data = self.accounts.mint.try_borrow_data()?;
+            let decimals = if mint_data.len() > 44 {
+                mint_data[44]
+            } else {
+                return Err(ProgramError::InvalidAccountData);
+            };
+            drop(mint_data);
+
+            TransferChecked {
+                from: self.accounts.token_account,
+                mint: self.accounts.mint,
+                to: self.accounts.maker_token_account,
+                authority: self.accounts.escrow,
+                token_program: self.accounts.token_program,
+                amount,
+                decimals,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        CloseAccount {
+            account: self.accounts.token_account,
+            destination: self.accounts.maker,
+            authority: self.accounts.escrow,
+            token_program: self.accounts.token_program,
+        }
+        .invoke_signed(&signers)
+    }
+}
@@ -1,20 +1,54 @@
 //! Take instruction: taker sends token B to maker, receives token A from vault; escrow and vault closed.
 
+use core::mem::size_of;
 use pinocchio::{
     account_info::AccountInfo,
     instruction::{Seed, Signer},
     program_error::ProgramError,
     ProgramResult,
 };
-use pinocchio_system::instructions::Transfer;
 use pinocchio_token::instructions::{CloseAccount, TransferChecked};
 
+use crate::instructions::helpers::{check_token_account, close_program_account, token_account_amount};
 use crate::state::Escrow;
 
 // SPL Token Account amount at offset 64.
 const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
 
-/// Take accounts: taker, maker, escrow, mint_a, mint_b, vault, taker_ata_a, taker_ata_b, maker_ata_b, system_program, token_program, associated_token_program.
+/// Take instruction data: the terms the taker signed for. Either empty (take at
+/// whatever the escrow and vault hold) or expected_receive (u64) followed by
+/// min_amount_a (u64), where 0 skips that check.
+/// - expected_receive: the escrow's `receive` must equal it.
+/// - min_amount_a: the vault must hold at least this much token A.
+pub struct TakeInstructionData {
+    pub expected_receive: Option<u64>,
+    pub min_amount_a: Option<u64>,
+}
+
+impl TakeInstructionData {
+    pub const LEN: usize = size_of::<u64>() * 2;
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for TakeInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data.len() {
+            0 => Ok(Self { expected_receive: None, min_amount_a: None }),
+            TakeInstructionData::LEN => {
+                let expected_receive = u64::from_le_bytes(data[0..8].try_into().unwrap());
+                let min_amount_a = u64::from_le_bytes(data[8..16].try_into().unwrap());
+                Ok(Self {
+                    expected_receive: (expected_receive != 0).then_some(expected_receive),
+                    min_amount_a: (min_amount_a != 0).then_some(min_amount_a),
+                })
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// Take accounts: taker, maker, escrow, mint_a, mint_b, vault, taker_ata_a, taker_ata_b, maker_ata_b, system_program, token_program, associated_token_program, rent_recipient.
 pub struct TakeAccounts<'a> {
     pub taker: &'a AccountInfo,
     pub maker: &'a AccountInfo,
@@ -28,6 +62,8 @@ pub struct TakeAccounts<'a> {
     pub system_program: &'a AccountInfo,
     pub token_program: &'a AccountInfo,
     pub associated_token_program: &'a AccountInfo,
+    /// Receives the vault's and escrow's rent on close.
+    pub rent_recipient: &'a AccountInfo,
 }
 
 impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
@@ -38,6 +74,7 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
             taker, maker, escrow, mint_a, mint_b, vault,
             taker_ata_a, taker_ata_b, maker_ata_b,
             system_program, token_program, associated_token_program,
+            rent_recipient,
         ] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
@@ -49,13 +86,16 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
             return Err(ProgramError::InvalidAccountOwner);
         }
         let escrow_data = escrow.try_borrow_data()?;
-        let escrow_state = Escrow::load(&*escrow_data)?;
+        let escrow_state = Escrow::load(&escrow_data)?;
         if escrow_state.maker != *maker.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
         if escrow_state.mint_a != *mint_a.key() || escrow_state.mint_b != *mint_b.key() {
             return Err(ProgramError::InvalidAccountOwner);
         }
+        if escrow_state.rent_recipient != *rent_recipient.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
 
         Ok(Self {
             taker,
@@ -70,94 +110,108 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeAccounts<'a> {
             system_program,
             token_program,
             associated_token_program,
+            rent_recipient,
         })
     }
 }
 
 pub struct Take<'a> {
     pub accounts: TakeAccounts<'a>,
+    pub data: TakeInstructionData,
 }
 
-impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Take<'a> {
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for Take<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
         Ok(Self {
             accounts: TakeAccounts::try_from(accounts)?,
+            data: TakeInstructionData::try_from(data)?,
         })
     }
 }
 
-impl<'a> Take<'a> {
-    pub fn process(&mut self) -> ProgramResult {
-        let escrow_data = self.accounts.escrow.try_borrow_data()?;
-        let escrow = Escrow::load(&*escrow_data)?;
-        let seed = escrow.seed;
-        let bump = escrow.bump[0];
-        let receive = escrow.receive;
-        drop(escrow_data);
+impl<'a> TakeAccounts<'a> {
+    /// Pay `receive` token B to the maker, release the vault to the taker and close
+    /// vault and escrow into `rent_recipient`. `signers` must sign for the escrow PDA.
+    pub fn settle(&self, receive: u64, signers: &[Signer]) -> ProgramResult {
+        check_token_account(self.maker_ata_b, self.mint_b.key(), self.maker.key())?;
 
-        let maker_key = self.accounts.maker.key();
-        let seed_bytes = seed.to_le_bytes();
-        let binding = [bump];
-        let seeds = [
-            Seed::from(b"escrow"),
-            Seed::from(maker_key.as_ref()),
-            Seed::from(seed_bytes.as_ref()),
-            Seed::from(&binding),
-        ];
-        let signers = [Signer::from(&seeds)];
-
-        let mint_b_data = self.accounts.mint_b.try_borrow_data()?;
+        let mint_b_data = self.mint_b.try_borrow_data()?;
         let decimals_b = if mint_b_data.len() > 44 { mint_b_data[44] } else { return Err(ProgramError::InvalidAccountData) };
         drop(mint_b_data);
 
         TransferChecked {
-            from: self.accounts.taker_ata_b,
-            mint: self.accounts.mint_b,
-            to: self.accounts.maker_ata_b,
-            authority: self.accounts.taker,
+            from: self.taker_ata_b,
+            mint: self.mint_b,
+            to: self.maker_ata_b,
+            authority: self.taker,
             amount: receive,
             decimals: decimals_b,
         }
         .invoke()?;
 
-        let vault_data = self.accounts.vault.try_borrow_data()?;
+        let vault_data = self.vault.try_borrow_data()?;
         if vault_data.len() < TOKEN_ACCOUNT_AMOUNT_OFFSET + 8 {
             return Err(ProgramError::InvalidAccountData);
         }
         let vault_amount = u64::from_le_bytes(vault_data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8].try_into().unwrap());
         drop(vault_data);
 
-        let mint_a_data = self.accounts.mint_a.try_borrow_data()?;
+        let mint_a_data = self.mint_a.try_borrow_data()?;
         let decimals_a = if mint_a_data.len() > 44 { mint_a_data[44] } else { return Err(ProgramError::InvalidAccountData) };
         drop(mint_a_data);
 
         TransferChecked {
-            from: self.accounts.vault,
-            mint: self.accounts.mint_a,
-            to: self.accounts.taker_ata_a,
-            authority: self.accounts.escrow,
+            from: self.vault,
+            mint: self.mint_a,
+            to: self.taker_ata_a,
+            authority: self.escrow,
             amount: vault_amount,
             decimals: decimals_a,
         }
-        .invoke_signed(&signers)?;
+        .invoke_signed(signers)?;
 
         CloseAccount {
-            account: self.accounts.vault,
-            destination: self.accounts.maker,
-            authority: self.accounts.escrow,
+            account: self.vault,
+            destination: self.rent_recipient,
+            authority: self.escrow,
         }
-        .invoke_signed(&signers)?;
+        .invoke_signed(signers)?;
+
+        close_program_account(self.escrow, self.rent_recipient)
+    }
+}
+
+impl<'a> Take<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let escrow_data = self.accounts.escrow.try_borrow_data()?;
+        let escrow = Escrow::load(&escrow_data)?;
+        let seed = escrow.seed;
+        let bump = escrow.bump[0];
+        let receive = escrow.receive;
+        drop(escrow_data);
 
-        let escrow_lamports = self.accounts.escrow.lamports();
-        Transfer {
-            from: self.accounts.escrow,
-            to: self.accounts.maker,
-            lamports: escrow_lamports,
+        if self.data.expected_receive.is_some_and(|expected| expected != receive) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if let Some(min_amount_a) = self.data.min_amount_a {
+            if token_account_amount(self.accounts.vault)? < min_amount_a {
+                return Err(ProgramError::InsufficientFunds);
+            }
         }
-        .invoke_signed(&signers)?;
 
-        Ok(())
+        let maker_key = self.accounts.maker.key();
+        let seed_bytes = seed.to_le_bytes();
+        let binding = [bump];
+        let seeds = [
+            Seed::from(b"escrow"),
+            Seed::from(maker_key.as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(&binding),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        self.accounts.settle(receive, &signers)
     }
 }
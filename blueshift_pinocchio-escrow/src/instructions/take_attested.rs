@@ -0,0 +1,164 @@
+//! TakeAttested instruction: Take on an attested escrow. The next instruction in
+//! the transaction must be a secp256r1 verify of the escrow's attester signing
+//! (escrow address || taker || expiry), before that expiry.
+
+use core::{mem::size_of, ops::Deref};
+use pinocchio::{
+    account_info::{AccountInfo, Ref},
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+    sysvars::{
+        clock::Clock,
+        instructions::{Instructions, IntrospectedInstruction},
+        Sysvar,
+    },
+    ProgramResult,
+};
+
+use crate::instructions::precompile::secp256r1_instruction;
+use crate::instructions::take::TakeAccounts;
+use crate::state::{AttestedEscrow, Secp256r1Pubkey};
+
+/// TakeAttested accounts: the Take accounts followed by the instructions sysvar.
+pub struct TakeAttestedAccounts<'a> {
+    pub take: TakeAccounts<'a>,
+    pub instructions: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeAttestedAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [
+            taker, maker, escrow, mint_a, mint_b, vault,
+            taker_ata_a, taker_ata_b, maker_ata_b,
+            system_program, token_program, associated_token_program,
+            instructions,
+        ] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !taker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if !escrow.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_data = escrow.try_borrow_data()?;
+        let escrow_state = AttestedEscrow::load(&escrow_data)?;
+        if escrow_state.maker != *maker.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if escrow_state.mint_a != *mint_a.key() || escrow_state.mint_b != *mint_b.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            take: TakeAccounts {
+                taker,
+                maker,
+                escrow,
+                mint_a,
+                mint_b,
+                vault,
+                taker_ata_a,
+                taker_ata_b,
+                maker_ata_b,
+                system_program,
+                token_program,
+                associated_token_program,
+                // Attested escrows record no rent recipient; their rent goes to the maker.
+                rent_recipient: maker,
+            },
+            instructions,
+        })
+    }
+}
+
+pub struct TakeAttested<'a> {
+    pub accounts: TakeAttestedAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeAttested<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: TakeAttestedAccounts::try_from(accounts)?,
+        })
+    }
+}
+
+/// Check that the instruction after the current one is a secp256r1 verify of
+/// `attester` signing exactly (escrow || taker || expiry), and return the expiry
+/// (i64 unix timestamp, little-endian). The expiry keeps an attestation from
+/// outliving the escrow it was given for: a re-made escrow at the same seed has
+/// the same address.
+pub fn verify_attestation<T: Deref<Target = [u8]>>(
+    instructions: &Instructions<T>,
+    attester: &Secp256r1Pubkey,
+    escrow: &Pubkey,
+    taker: &Pubkey,
+) -> Result<i64, ProgramError> {
+    let ix: IntrospectedInstruction = instructions.get_instruction_relative(1)?;
+    let verify = secp256r1_instruction(&ix)?;
+    let signer = verify
+        .get_signer(0)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    if signer != attester {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let message = verify
+        .get_message_data(0)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    if message.len() != PUBKEY_BYTES * 2 + size_of::<i64>()
+        || &message[..PUBKEY_BYTES] != escrow.as_ref()
+        || &message[PUBKEY_BYTES..PUBKEY_BYTES * 2] != taker.as_ref()
+    {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(i64::from_le_bytes(
+        message[PUBKEY_BYTES * 2..].try_into().unwrap(),
+    ))
+}
+
+impl<'a> TakeAttested<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let take = &self.accounts.take;
+
+        let escrow_data = take.escrow.try_borrow_data()?;
+        let escrow = AttestedEscrow::load(&escrow_data)?;
+        let seed = escrow.seed;
+        let bump = escrow.bump[0];
+        let receive = escrow.receive;
+        let attester = escrow.attester;
+        drop(escrow_data);
+
+        let instructions: Instructions<Ref<[u8]>> =
+            Instructions::try_from(self.accounts.instructions)?;
+        let expiry = verify_attestation(
+            &instructions,
+            &attester,
+            take.escrow.key(),
+            take.taker.key(),
+        )?;
+        if Clock::get()?.unix_timestamp > expiry {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let maker_key = take.maker.key();
+        let seed_bytes = seed.to_le_bytes();
+        let binding = [bump];
+        let seeds = [
+            Seed::from(b"attested"),
+            Seed::from(maker_key.as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(&binding),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        take.settle(receive, &signers)
+    }
+}
@@ -0,0 +1,152 @@
+//! TakeLimit instruction: Take on a limit escrow. The taker pays for the whole
+//! vault at the price feed's current price; see `LimitEscrow::amount_owed`.
+
+use core::mem::size_of;
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::instructions::helpers::token_account_amount;
+use crate::instructions::take::TakeAccounts;
+use crate::state::{LimitEscrow, PriceFeed};
+
+/// TakeLimit instruction data: either empty or max_receive (u64), the most token B
+/// the taker will pay, where 0 skips the check. Guards the taker against the feed
+/// moving between signing and execution.
+pub struct TakeLimitInstructionData {
+    pub max_receive: Option<u64>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for TakeLimitInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data.len() {
+            0 => Ok(Self { max_receive: None }),
+            len if len == size_of::<u64>() => {
+                let max_receive = u64::from_le_bytes(data.try_into().unwrap());
+                Ok(Self {
+                    max_receive: (max_receive != 0).then_some(max_receive),
+                })
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// TakeLimit accounts: the Take accounts with the escrow's price feed in place of the rent recipient.
+pub struct TakeLimitAccounts<'a> {
+    pub take: TakeAccounts<'a>,
+    pub price_feed: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeLimitAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [
+            taker, maker, escrow, mint_a, mint_b, vault,
+            taker_ata_a, taker_ata_b, maker_ata_b,
+            system_program, token_program, associated_token_program,
+            price_feed,
+        ] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !taker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if !escrow.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_data = escrow.try_borrow_data()?;
+        let escrow_state = LimitEscrow::load(&escrow_data)?;
+        if escrow_state.maker != *maker.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if escrow_state.mint_a != *mint_a.key() || escrow_state.mint_b != *mint_b.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if escrow_state.price_feed != *price_feed.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            take: TakeAccounts {
+                taker,
+                maker,
+                escrow,
+                mint_a,
+                mint_b,
+                vault,
+                taker_ata_a,
+                taker_ata_b,
+                maker_ata_b,
+                system_program,
+                token_program,
+                associated_token_program,
+                // Limit escrows record no rent recipient; their rent goes to the maker.
+                rent_recipient: maker,
+            },
+            price_feed,
+        })
+    }
+}
+
+pub struct TakeLimit<'a> {
+    pub accounts: TakeLimitAccounts<'a>,
+    pub data: TakeLimitInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for TakeLimit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: TakeLimitAccounts::try_from(accounts)?,
+            data: TakeLimitInstructionData::try_from(data)?,
+        })
+    }
+}
+
+impl<'a> TakeLimit<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let take = &self.accounts.take;
+        let vault_amount = token_account_amount(take.vault)?;
+        let slot = Clock::get()?.slot;
+
+        let escrow_data = take.escrow.try_borrow_data()?;
+        let escrow = LimitEscrow::load(&escrow_data)?;
+        let feed_data = self.accounts.price_feed.try_borrow_data()?;
+        let receive = escrow.amount_owed(PriceFeed::load(&feed_data)?, vault_amount, slot)?;
+        let seed = escrow.seed;
+        let bump = escrow.bump[0];
+        drop(feed_data);
+        drop(escrow_data);
+
+        if self
+            .data
+            .max_receive
+            .is_some_and(|max_receive| receive > max_receive)
+        {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let maker_key = take.maker.key();
+        let seed_bytes = seed.to_le_bytes();
+        let binding = [bump];
+        let seeds = [
+            Seed::from(b"limit"),
+            Seed::from(maker_key.as_ref()),
+            Seed::from(seed_bytes.as_ref()),
+            Seed::from(&binding),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        take.settle(receive, &signers)
+    }
+}
@@ -0,0 +1,224 @@
+//! TakeMany instruction: take several escrows for the same mint pair in one go.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::create_program_address,
+    ProgramResult,
+};
+
+use crate::instructions::helpers::{check_token_account, token_account_amount};
+use crate::instructions::take::TakeAccounts;
+use crate::state::Escrow;
+
+/// Accounts per escrow after the fixed header: maker, escrow, vault, maker_ata_b, rent_recipient.
+pub const TAKE_MANY_GROUP_LEN: usize = 5;
+
+/// Most escrows one TakeMany can settle (one bit each in the fillable set).
+pub const TAKE_MANY_MAX_ESCROWS: usize = u64::BITS as usize;
+
+/// TakeMany instruction data: skip_unfillable (u8). 0 fails the whole instruction
+/// on the first unfillable escrow; 1 skips it and settles the rest.
+pub struct TakeManyInstructionData {
+    pub skip_unfillable: bool,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for TakeManyInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data {
+            [0] => Ok(Self { skip_unfillable: false }),
+            [1] => Ok(Self { skip_unfillable: true }),
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+/// TakeMany accounts: taker, mint_a, mint_b, taker_ata_a, taker_ata_b, system_program, token_program, associated_token_program, then one (maker, escrow, vault, maker_ata_b, rent_recipient) group per escrow.
+pub struct TakeManyAccounts<'a> {
+    pub taker: &'a AccountInfo,
+    pub mint_a: &'a AccountInfo,
+    pub mint_b: &'a AccountInfo,
+    pub taker_ata_a: &'a AccountInfo,
+    pub taker_ata_b: &'a AccountInfo,
+    pub system_program: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub associated_token_program: &'a AccountInfo,
+    pub groups: &'a [AccountInfo],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TakeManyAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [
+            taker, mint_a, mint_b, taker_ata_a, taker_ata_b,
+            system_program, token_program, associated_token_program,
+            groups @ ..,
+        ] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !taker.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if token_program.key() != &pinocchio_token::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        if groups.is_empty() || groups.len() % TAKE_MANY_GROUP_LEN != 0 {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        }
+        if groups.len() / TAKE_MANY_GROUP_LEN > TAKE_MANY_MAX_ESCROWS {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        Ok(Self {
+            taker,
+            mint_a,
+            mint_b,
+            taker_ata_a,
+            taker_ata_b,
+            system_program,
+            token_program,
+            associated_token_program,
+            groups,
+        })
+    }
+}
+
+pub struct TakeMany<'a> {
+    pub accounts: TakeManyAccounts<'a>,
+    pub data: TakeManyInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for TakeMany<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        Ok(Self {
+            accounts: TakeManyAccounts::try_from(accounts)?,
+            data: TakeManyInstructionData::try_from(data)?,
+        })
+    }
+}
+
+impl<'a> TakeMany<'a> {
+    /// The Take accounts of escrow group `index`.
+    fn take_accounts(&self, index: usize) -> TakeAccounts<'a> {
+        let group = &self.accounts.groups[index * TAKE_MANY_GROUP_LEN..][..TAKE_MANY_GROUP_LEN];
+        TakeAccounts {
+            taker: self.accounts.taker,
+            maker: &group[0],
+            escrow: &group[1],
+            mint_a: self.accounts.mint_a,
+            mint_b: self.accounts.mint_b,
+            vault: &group[2],
+            taker_ata_a: self.accounts.taker_ata_a,
+            taker_ata_b: self.accounts.taker_ata_b,
+            maker_ata_b: &group[3],
+            system_program: self.accounts.system_program,
+            token_program: self.accounts.token_program,
+            associated_token_program: self.accounts.associated_token_program,
+            rent_recipient: &group[4],
+        }
+    }
+
+    /// Check everything but the escrow's address that a settlement CPI could fail
+    /// on, so skipped escrows never abort the batch. Returns the token B owed.
+    fn check_fillable(&self, group: &TakeAccounts, taker_balance_b: u64) -> Result<u64, ProgramError> {
+        if !group.escrow.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let escrow_data = group.escrow.try_borrow_data()?;
+        let escrow = Escrow::load(&escrow_data)?;
+        if escrow.maker != *group.maker.key()
+            || escrow.mint_a != *group.mint_a.key()
+            || escrow.mint_b != *group.mint_b.key()
+            || escrow.rent_recipient != *group.rent_recipient.key()
+        {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let receive = escrow.receive;
+        drop(escrow_data);
+
+        check_token_account(group.vault, group.mint_a.key(), group.escrow.key())?;
+        check_token_account(group.maker_ata_b, group.mint_b.key(), group.maker.key())?;
+        if receive > taker_balance_b {
+            return Err(ProgramError::InsufficientFunds);
+        }
+
+        Ok(receive)
+    }
+
+    /// Pick the escrows to settle before settling any: each must be fillable, appear
+    /// once in the batch and be paid for out of what the escrows before it leave of
+    /// the taker's token B balance. Returns one bit per group.
+    fn fillable_groups(&self) -> Result<u64, ProgramError> {
+        let mut taker_balance_b = token_account_amount(self.accounts.taker_ata_b)?;
+        let mut fillable = 0u64;
+
+        for index in 0..self.accounts.groups.len() / TAKE_MANY_GROUP_LEN {
+            let take = self.take_accounts(index);
+            let duplicate = (0..index).any(|earlier| self.take_accounts(earlier).escrow.key() == take.escrow.key());
+            let checked = if duplicate {
+                Err(ProgramError::InvalidArgument)
+            } else {
+                self.check_fillable(&take, taker_balance_b)
+            };
+
+            match checked {
+                Ok(receive) => {
+                    taker_balance_b -= receive;
+                    fillable |= 1 << index;
+                }
+                Err(_) if self.data.skip_unfillable => continue,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(fillable)
+    }
+
+    pub fn process(&mut self) -> ProgramResult {
+        check_token_account(self.accounts.taker_ata_a, self.accounts.mint_a.key(), self.accounts.taker.key())?;
+        check_token_account(self.accounts.taker_ata_b, self.accounts.mint_b.key(), self.accounts.taker.key())?;
+        let fillable = self.fillable_groups()?;
+
+        for index in 0..self.accounts.groups.len() / TAKE_MANY_GROUP_LEN {
+            if fillable & (1 << index) == 0 {
+                continue;
+            }
+            let take = self.take_accounts(index);
+
+            let escrow_data = take.escrow.try_borrow_data()?;
+            let escrow = Escrow::load(&escrow_data)?;
+            let (seed, bump, receive) = (escrow.seed, escrow.bump[0], escrow.receive);
+            drop(escrow_data);
+
+            let seed_bytes = seed.to_le_bytes();
+            let escrow_key = create_program_address(
+                &[b"escrow", take.maker.key().as_ref(), &seed_bytes, &[bump]],
+                &crate::ID,
+            )?;
+            if take.escrow.key() != &escrow_key {
+                if self.data.skip_unfillable {
+                    continue;
+                }
+                return Err(ProgramError::InvalidSeeds);
+            }
+
+            let binding = [bump];
+            let seeds = [
+                Seed::from(b"escrow"),
+                Seed::from(take.maker.key().as_ref()),
+                Seed::from(seed_bytes.as_ref()),
+                Seed::from(&binding),
+            ];
+            take.settle(receive, &[Signer::from(&seeds)])?;
+        }
+
+        Ok(())
+    }
+}
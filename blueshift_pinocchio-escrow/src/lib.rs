@@ -2,15 +2,23 @@
 
 use pinocchio::{
     account_info::AccountInfo,
-    entrypoint,
     program_error::ProgramError,
     pubkey::Pubkey,
     ProgramResult,
 };
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+pinocchio::entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
 pinocchio::nostd_panic_handler!();
 
+// Without the entrypoint the host-side cdylib still needs a panic handler; the
+// program embedding this crate provides its own on-chain.
+#[cfg(all(feature = "no-entrypoint", not(target_os = "solana")))]
+extern crate std;
+
+#[cfg(feature = "cpi")]
+pub mod cpi;
 pub mod errors;
 pub mod instructions;
 pub mod state;
@@ -26,15 +34,27 @@ pub const ID: Pubkey = [
     0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
 ];
 
-fn process_instruction(
+pub fn process_instruction(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
     match instruction_data.split_first() {
         Some((d, data)) if *d == 0 => Make::try_from((data, accounts))?.process(),
-        Some((d, _)) if *d == 1 => Take::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 1 => Take::try_from((data, accounts))?.process(),
         Some((d, _)) if *d == 2 => Refund::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 3 => MakeAttested::try_from((data, accounts))?.process(),
+        Some((d, _)) if *d == 4 => TakeAttested::try_from(accounts)?.process(),
+        Some((d, _)) if *d == 5 => RefundAttested::try_from(accounts)?.process(),
+        Some((d, _)) if *d == 6 => FillOrder::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 7 => CancelOrder::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 8 => TakeMany::try_from((data, accounts))?.process(),
+        Some((d, _)) if *d == 9 => Migrate::try_from(accounts)?.process(),
+        Some((d, _)) if *d == 10 => Quote::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 11 => Sweep::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 12 => MakeLimit::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 13 => TakeLimit::try_from((data, accounts))?.process(),
+        Some((d, _)) if *d == 14 => RefundLimit::try_from(accounts)?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -1,7 +1,20 @@
 use core::mem::size_of;
 use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
 
-/// Escrow account state: seed, maker, mints, receive amount (token B), bump.
+use crate::instructions::QUOTE_PRICE_SCALE;
+
+/// Current `Escrow` layout version. v1 accounts predate the version byte and are
+/// recognised by their length; see [`EscrowV1`].
+pub const ESCROW_VERSION: u8 = 2;
+
+/// Escrow account state (v2): seed, maker, mints, receive amount (token B), bump,
+/// layout version and the account that receives the escrow's rent on close.
+///
+/// The v1 fields are a prefix of this layout, so `Migrate` only has to resize the
+/// account and fill in the fields after `bump`. Layouts are told apart by length,
+/// so v2 is padded to one no other escrow layout has: at 146 bytes it would alias
+/// `AttestedEscrow`, whose compressed attester key starts with 0x02 or 0x03 where
+/// `version` is.
 #[repr(C)]
 pub struct Escrow {
     pub seed: u64,
@@ -10,16 +23,21 @@ pub struct Escrow {
     pub mint_b: Pubkey,
     pub receive: u64,
     pub bump: [u8; 1],
+    pub version: u8,
+    /// Receives the vault's and escrow's rent on close; the maker for escrows
+    /// migrated from v1.
+    pub rent_recipient: Pubkey,
+    pub padding: [u8; 6],
 }
 
 impl Escrow {
-    pub const LEN: usize = size_of::<u64>()
+    pub const LEN: usize = EscrowV1::LEN
+        + size_of::<u8>()
         + size_of::<Pubkey>()
-        + size_of::<Pubkey>()
-        + size_of::<Pubkey>()
-        + size_of::<u64>()
-        + size_of::<[u8; 1]>();
+        + size_of::<[u8; 6]>();
 
+    /// Length-checked mutable view. The version is not checked so `Make` can
+    /// initialise a freshly created account through `set_inner`.
     #[inline(always)]
     pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
         if bytes.len() != Escrow::LEN {
@@ -33,7 +51,24 @@ impl Escrow {
         if bytes.len() != Escrow::LEN {
             return Err(ProgramError::InvalidAccountData);
         }
-        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+        let escrow = unsafe { &*(bytes.as_ptr() as *const Self) };
+        if escrow.version != ESCROW_VERSION {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(escrow)
+    }
+
+    /// Fill the v2 fields of an account resized from the v1 layout: the version
+    /// and a `rent_recipient` defaulting to the maker.
+    #[inline(always)]
+    pub fn upgrade_from_v1(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        let escrow = Escrow::load_mut(bytes)?;
+        if escrow.version != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+        escrow.version = ESCROW_VERSION;
+        escrow.rent_recipient = escrow.maker;
+        Ok(escrow)
     }
 
     #[inline(always)]
@@ -67,6 +102,103 @@ impl Escrow {
     }
 
     #[inline(always)]
+    pub fn set_rent_recipient(&mut self, rent_recipient: Pubkey) {
+        self.rent_recipient = rent_recipient;
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        receive: u64,
+        bump: [u8; 1],
+        rent_recipient: Pubkey,
+    ) {
+        self.seed = seed;
+        self.maker = maker;
+        self.mint_a = mint_a;
+        self.mint_b = mint_b;
+        self.receive = receive;
+        self.bump = bump;
+        self.version = ESCROW_VERSION;
+        self.rent_recipient = rent_recipient;
+        self.padding = [0; 6];
+    }
+}
+
+/// Escrow account state as written before layout versioning. Read-only: existing
+/// accounts are upgraded in place by `Migrate`, and until then can only be
+/// refunded, with the rent going to the maker.
+#[repr(C)]
+pub struct EscrowV1 {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: [u8; 1],
+}
+
+impl EscrowV1 {
+    pub const LEN: usize = size_of::<u64>()
+        + size_of::<Pubkey>()
+        + size_of::<Pubkey>()
+        + size_of::<Pubkey>()
+        + size_of::<u64>()
+        + size_of::<[u8; 1]>();
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != EscrowV1::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+}
+
+/// Compressed secp256r1 (P-256) public key.
+pub type Secp256r1Pubkey = [u8; 33];
+
+/// Attested escrow account state: the v1 `Escrow` fields followed by the secp256r1
+/// attester key whose signature over (escrow, taker) is required to take.
+///
+/// Its length is its own: `Escrow` is padded so the two never alias.
+#[repr(C)]
+pub struct AttestedEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub receive: u64,
+    pub bump: [u8; 1],
+    pub attester: Secp256r1Pubkey,
+}
+
+impl AttestedEscrow {
+    pub const LEN: usize = EscrowV1::LEN + size_of::<Secp256r1Pubkey>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != AttestedEscrow::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != AttestedEscrow::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
     pub fn set_inner(
         &mut self,
         seed: u64,
@@ -75,6 +207,7 @@ impl Escrow {
         mint_b: Pubkey,
         receive: u64,
         bump: [u8; 1],
+        attester: Secp256r1Pubkey,
     ) {
         self.seed = seed;
         self.maker = maker;
@@ -82,5 +215,142 @@ impl Escrow {
         self.mint_b = mint_b;
         self.receive = receive;
         self.bump = bump;
+        self.attester = attester;
+    }
+}
+
+/// Limit escrow account state: a maker selling token A at the price in `price_feed`,
+/// as long as it is at least `limit_price` and no older than `max_price_age` slots.
+/// Prices are token B base units per token A base unit, scaled by
+/// `QUOTE_PRICE_SCALE` like `EscrowQuote::price`.
+#[repr(C)]
+pub struct LimitEscrow {
+    pub seed: u64,
+    pub maker: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub limit_price: u64,
+    pub max_price_age: u64,
+    pub price_feed: Pubkey,
+    pub bump: [u8; 1],
+}
+
+impl LimitEscrow {
+    pub const LEN: usize = size_of::<u64>()
+        + size_of::<Pubkey>() * 3
+        + size_of::<u64>() * 2
+        + size_of::<Pubkey>()
+        + size_of::<[u8; 1]>();
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != LimitEscrow::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != LimitEscrow::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    #[inline(always)]
+    #[allow(clippy::too_many_arguments)]
+    pub fn set_inner(
+        &mut self,
+        seed: u64,
+        maker: Pubkey,
+        mint_a: Pubkey,
+        mint_b: Pubkey,
+        limit_price: u64,
+        max_price_age: u64,
+        price_feed: Pubkey,
+        bump: [u8; 1],
+    ) {
+        self.seed = seed;
+        self.maker = maker;
+        self.mint_a = mint_a;
+        self.mint_b = mint_b;
+        self.limit_price = limit_price;
+        self.max_price_age = max_price_age;
+        self.price_feed = price_feed;
+        self.bump = bump;
+    }
+
+    /// Token B owed for `amount` token A at `feed`'s price, rounded up in the maker's
+    /// favour. `slot` is the current slot.
+    ///
+    /// Fails with `InvalidAccountData` if the feed prices another pair or was last
+    /// published more than `max_price_age` slots ago, and with `InvalidArgument` if
+    /// its price is below `limit_price`.
+    pub fn amount_owed(
+        &self,
+        feed: &PriceFeed,
+        amount: u64,
+        slot: u64,
+    ) -> Result<u64, ProgramError> {
+        if feed.mint_a != self.mint_a || feed.mint_b != self.mint_b {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if slot.saturating_sub(feed.slot) > self.max_price_age {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if feed.price < self.limit_price {
+            return Err(ProgramError::InvalidArgument);
+        }
+
+        let owed = (amount as u128 * feed.price as u128).div_ceil(QUOTE_PRICE_SCALE as u128);
+        u64::try_from(owed).map_err(|_| ProgramError::ArithmeticOverflow)
+    }
+}
+
+/// Price feed read by `TakeLimit`. Owned and written by any program the maker
+/// trusts: an oracle adapter on mainnet, a mock program in tests. Little-endian:
+///
+/// | offset | field    | type     | meaning                                          |
+/// |--------|----------|----------|--------------------------------------------------|
+/// | 0      | `mint_a` | `Pubkey` | token being priced                               |
+/// | 32     | `mint_b` | `Pubkey` | token the price is quoted in                     |
+/// | 64     | `price`  | `u64`    | token B base units per token A base unit, scaled by `QUOTE_PRICE_SCALE` |
+/// | 72     | `slot`   | `u64`    | slot the price was published in                  |
+///
+/// The account may be longer; bytes past `PriceFeed::LEN` are ignored.
+#[repr(C)]
+pub struct PriceFeed {
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub price: u64,
+    pub slot: u64,
+}
+
+impl PriceFeed {
+    pub const LEN: usize = size_of::<Pubkey>() * 2 + size_of::<u64>() * 2;
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() < PriceFeed::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() < PriceFeed::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    #[inline(always)]
+    pub fn set_inner(&mut self, mint_a: Pubkey, mint_b: Pubkey, price: u64, slot: u64) {
+        self.mint_a = mint_a;
+        self.mint_b = mint_b;
+        self.price = price;
+        self.slot = slot;
     }
 }
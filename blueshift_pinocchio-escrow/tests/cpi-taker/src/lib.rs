@@ -0,0 +1,185 @@
+//! Test program that calls the escrow through `blueshift_pinocchio_escrow::cpi`,
+//! the way a router composes with it, e.g. filling an escrow as one leg of a
+//! multi-hop swap.
+//!
+//! The first byte of instruction data picks the helper: 0 Make, 1 Take, 2 Refund,
+//! 3 Sweep, 4 Quote. The rest is that escrow instruction's own data. Accounts are
+//! the helper's accounts in the order `cpi` documents them, followed by the
+//! escrow program.
+
+#![no_std]
+
+use blueshift_pinocchio_escrow::{
+    cpi::{Make, Quote, Refund, Sweep, Take},
+    MakeInstructionData, SweepInstructionData, TakeInstructionData,
+};
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::Instruction,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    ProgramResult,
+};
+
+pinocchio::entrypoint!(process_instruction);
+pinocchio::nostd_panic_handler!();
+
+// 33333333333333333333333333333333333333333333
+pub const ID: Pubkey = [
+    0x1e, 0x3c, 0xd6, 0x28, 0x43, 0x80, 0x94, 0x0e,
+    0x08, 0x62, 0x4c, 0xb8, 0x33, 0x8b, 0x77, 0xdc,
+    0x33, 0x25, 0x75, 0xd1, 0x5f, 0xa3, 0x9a, 0x0f,
+    0x1d, 0xf1, 0x5e, 0xe0, 0x8f, 0xb8, 0x23, 0xee,
+];
+
+/// One escrow call, built from this program's accounts and instruction data.
+pub enum EscrowCall<'a> {
+    Make(Make<'a>),
+    Take(Take<'a>),
+    Refund(Refund<'a>),
+    Sweep(Sweep<'a>),
+    Quote(Quote<'a>),
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for EscrowCall<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let Some((escrow_program, accounts)) = accounts.split_last() else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        if escrow_program.key() != &blueshift_pinocchio_escrow::ID {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        match data.split_first() {
+            Some((0, data)) => {
+                let (accounts, payer) = match accounts {
+                    [accounts @ .., payer] if accounts.len() == 9 => (accounts, Some(payer)),
+                    accounts => (accounts, None),
+                };
+                let [
+                    maker, escrow, mint_a, mint_b, maker_ata_a, vault,
+                    token_program, associated_token_program, system_program,
+                ] = accounts else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                let data = MakeInstructionData::try_from(data)?;
+                Ok(Self::Make(Make {
+                    maker,
+                    escrow,
+                    mint_a,
+                    mint_b,
+                    maker_ata_a,
+                    vault,
+                    token_program,
+                    associated_token_program,
+                    system_program,
+                    payer,
+                    seed: data.seed,
+                    receive: data.receive,
+                    amount: data.amount,
+                    rent_recipient: data.rent_recipient,
+                }))
+            }
+            Some((1, data)) => {
+                let [
+                    taker, maker, escrow, mint_a, mint_b, vault,
+                    taker_ata_a, taker_ata_b, maker_ata_b,
+                    system_program, token_program, associated_token_program,
+                    rent_recipient,
+                ] = accounts else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                let limits = TakeInstructionData::try_from(data)?;
+                Ok(Self::Take(Take {
+                    taker,
+                    maker,
+                    escrow,
+                    mint_a,
+                    mint_b,
+                    vault,
+                    taker_ata_a,
+                    taker_ata_b,
+                    maker_ata_b,
+                    system_program,
+                    token_program,
+                    associated_token_program,
+                    rent_recipient,
+                    expected_receive: limits.expected_receive,
+                    min_amount_a: limits.min_amount_a,
+                }))
+            }
+            Some((2, [])) => {
+                let [
+                    maker, escrow, mint_a, vault, maker_ata_a,
+                    system_program, token_program, rent_recipient,
+                ] = accounts else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                Ok(Self::Refund(Refund {
+                    maker,
+                    escrow,
+                    mint_a,
+                    vault,
+                    maker_ata_a,
+                    system_program,
+                    token_program,
+                    rent_recipient,
+                }))
+            }
+            Some((3, data)) => {
+                let [maker, escrow, mint, token_account, maker_token_account, token_program] =
+                    accounts
+                else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                let data = SweepInstructionData::try_from(data)?;
+                Ok(Self::Sweep(Sweep {
+                    maker,
+                    escrow,
+                    mint,
+                    token_account,
+                    maker_token_account,
+                    token_program,
+                    seed: data.seed,
+                    kind: data.kind,
+                }))
+            }
+            Some((4, [])) => {
+                let [escrow, vault] = accounts else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+                Ok(Self::Quote(Quote { escrow, vault }))
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+impl EscrowCall<'_> {
+    /// Build the escrow instruction and pass it to `f` with its account infos.
+    pub fn with_instruction<R>(&self, f: impl FnOnce(&Instruction, &[&AccountInfo]) -> R) -> R {
+        match self {
+            Self::Make(make) => make.with_instruction(f),
+            Self::Take(take) => take.with_instruction(f),
+            Self::Refund(refund) => refund.with_instruction(f),
+            Self::Sweep(sweep) => sweep.with_instruction(f),
+            Self::Quote(quote) => quote.with_instruction(f),
+        }
+    }
+}
+
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    match EscrowCall::try_from((instruction_data, accounts))? {
+        EscrowCall::Make(make) => make.invoke(),
+        EscrowCall::Take(take) => take.invoke(),
+        EscrowCall::Refund(refund) => refund.invoke(),
+        EscrowCall::Sweep(sweep) => sweep.invoke(),
+        EscrowCall::Quote(quote) => quote.get().map(|_| ()),
+    }
+}
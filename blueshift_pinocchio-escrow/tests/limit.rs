@@ -0,0 +1,149 @@
+//! Limit escrows: pricing against a `PriceFeed`, staleness and the feed layout.
+
+use blueshift_pinocchio_escrow::{
+    LimitEscrow, MakeLimitInstructionData, PriceFeed, TakeLimitInstructionData, QUOTE_PRICE_SCALE,
+};
+use pinocchio::program_error::ProgramError;
+
+const MAKER: [u8; 32] = [7; 32];
+const MINT_A: [u8; 32] = [8; 32];
+const MINT_B: [u8; 32] = [9; 32];
+const FEED: [u8; 32] = [10; 32];
+
+/// Account data buffers, 8-byte aligned like the runtime's.
+#[repr(C, align(8))]
+struct EscrowData([u8; LimitEscrow::LEN]);
+#[repr(C, align(8))]
+struct FeedData([u8; PriceFeed::LEN]);
+
+/// Sells token A for at least 2 token B each, at prices up to 100 slots old.
+fn limit_escrow() -> EscrowData {
+    let mut data = EscrowData([0; LimitEscrow::LEN]);
+    LimitEscrow::load_mut(&mut data.0).unwrap().set_inner(
+        42,
+        MAKER,
+        MINT_A,
+        MINT_B,
+        2 * QUOTE_PRICE_SCALE,
+        100,
+        FEED,
+        [254],
+    );
+    data
+}
+
+/// A feed as the documented layout says an oracle writes it.
+fn feed(mint_a: [u8; 32], price: u64, slot: u64) -> FeedData {
+    let mut data = FeedData([0; PriceFeed::LEN]);
+    data.0[0..32].copy_from_slice(&mint_a);
+    data.0[32..64].copy_from_slice(&MINT_B);
+    data.0[64..72].copy_from_slice(&price.to_le_bytes());
+    data.0[72..80].copy_from_slice(&slot.to_le_bytes());
+    data
+}
+
+#[test]
+fn owed_follows_feed_price_at_or_above_limit() {
+    let escrow_data = limit_escrow();
+    let escrow = LimitEscrow::load(&escrow_data.0).unwrap();
+
+    let at_limit = feed(MINT_A, 2 * QUOTE_PRICE_SCALE, 1_000);
+    let feed_state = PriceFeed::load(&at_limit.0).unwrap();
+    assert_eq!(feed_state.price, 2 * QUOTE_PRICE_SCALE);
+    assert_eq!(feed_state.slot, 1_000);
+    assert_eq!(escrow.amount_owed(feed_state, 500, 1_000), Ok(1_000));
+
+    let above = feed(MINT_A, 3 * QUOTE_PRICE_SCALE, 1_000);
+    let above = PriceFeed::load(&above.0).unwrap();
+    assert_eq!(escrow.amount_owed(above, 500, 1_050), Ok(1_500));
+
+    let below = feed(MINT_A, 2 * QUOTE_PRICE_SCALE - 1, 1_000);
+    let below = PriceFeed::load(&below.0).unwrap();
+    assert_eq!(
+        escrow.amount_owed(below, 500, 1_000),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn owed_rounds_up_and_reports_overflow() {
+    let escrow_data = limit_escrow();
+    let escrow = LimitEscrow::load(&escrow_data.0).unwrap();
+
+    let data = feed(MINT_A, 2 * QUOTE_PRICE_SCALE + 1, 0);
+    let feed_state = PriceFeed::load(&data.0).unwrap();
+    assert_eq!(escrow.amount_owed(feed_state, 3, 0), Ok(7));
+    assert_eq!(
+        escrow.amount_owed(feed_state, u64::MAX, 0),
+        Err(ProgramError::ArithmeticOverflow)
+    );
+}
+
+#[test]
+fn stale_or_mismatched_feed_is_rejected() {
+    let escrow_data = limit_escrow();
+    let escrow = LimitEscrow::load(&escrow_data.0).unwrap();
+
+    let data = feed(MINT_A, 2 * QUOTE_PRICE_SCALE, 1_000);
+    let feed_state = PriceFeed::load(&data.0).unwrap();
+    assert!(escrow.amount_owed(feed_state, 1, 1_100).is_ok());
+    assert_eq!(
+        escrow.amount_owed(feed_state, 1, 1_101),
+        Err(ProgramError::InvalidAccountData)
+    );
+
+    let other_pair = feed(MINT_B, 2 * QUOTE_PRICE_SCALE, 1_000);
+    assert_eq!(
+        escrow.amount_owed(PriceFeed::load(&other_pair.0).unwrap(), 1, 1_000),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn feed_may_be_longer_but_not_shorter() {
+    let data = feed(MINT_A, 1, 1);
+    assert!(matches!(
+        PriceFeed::load(&data.0[..PriceFeed::LEN - 1]),
+        Err(ProgramError::InvalidAccountData)
+    ));
+
+    #[repr(C, align(8))]
+    struct Longer([u8; PriceFeed::LEN + 16]);
+    let mut longer = Longer([0xff; PriceFeed::LEN + 16]);
+    PriceFeed::load_mut(&mut longer.0)
+        .unwrap()
+        .set_inner(MINT_A, MINT_B, 5, 6);
+    assert_eq!(longer.0[..PriceFeed::LEN], feed(MINT_A, 5, 6).0);
+    assert_eq!(longer.0[PriceFeed::LEN..], [0xff; 16]);
+}
+
+#[test]
+fn instruction_data_lengths() {
+    let mut make = [0u8; MakeLimitInstructionData::LEN];
+    make[8..16].copy_from_slice(&QUOTE_PRICE_SCALE.to_le_bytes());
+    make[16..24].copy_from_slice(&1_000u64.to_le_bytes());
+    make[24..32].copy_from_slice(&100u64.to_le_bytes());
+    make[32..64].copy_from_slice(&FEED);
+    let parsed = MakeLimitInstructionData::try_from(&make[..]).unwrap();
+    assert_eq!(parsed.max_price_age, 100);
+    assert_eq!(parsed.price_feed, FEED);
+    assert!(MakeLimitInstructionData::try_from(&make[..63]).is_err());
+
+    // A zero limit would let any feed price through.
+    make[8..16].fill(0);
+    assert!(MakeLimitInstructionData::try_from(&make[..]).is_err());
+
+    assert_eq!(
+        TakeLimitInstructionData::try_from(&[][..])
+            .unwrap()
+            .max_receive,
+        None
+    );
+    assert_eq!(
+        TakeLimitInstructionData::try_from(&9u64.to_le_bytes()[..])
+            .unwrap()
+            .max_receive,
+        Some(9)
+    );
+    assert!(TakeLimitInstructionData::try_from(&[0; 4][..]).is_err());
+}
@@ -0,0 +1,131 @@
+//! v1 -> v2 escrow layout migration, on raw account data.
+
+use blueshift_pinocchio_escrow::{AttestedEscrow, Escrow, EscrowV1, LimitEscrow, ESCROW_VERSION};
+use pinocchio::program_error::ProgramError;
+
+const MAKER: [u8; 32] = [7; 32];
+const MINT_A: [u8; 32] = [8; 32];
+const MINT_B: [u8; 32] = [9; 32];
+
+/// Account data buffer, 8-byte aligned like the runtime's.
+#[repr(C, align(8))]
+struct AccountData([u8; 160]);
+
+/// A v1 escrow as written by `Make` before layout versioning, with room to grow.
+fn v1_account() -> AccountData {
+    let mut data = AccountData([0; 160]);
+    let bytes = &mut data.0;
+    bytes[0..8].copy_from_slice(&42u64.to_le_bytes());
+    bytes[8..40].copy_from_slice(&MAKER);
+    bytes[40..72].copy_from_slice(&MINT_A);
+    bytes[72..104].copy_from_slice(&MINT_B);
+    bytes[104..112].copy_from_slice(&1_000u64.to_le_bytes());
+    bytes[112] = 254;
+    data
+}
+
+#[test]
+fn v1_reader_decodes_legacy_layout() {
+    let data = v1_account();
+    let escrow = EscrowV1::load(&data.0[..EscrowV1::LEN]).unwrap();
+    assert_eq!(escrow.seed, 42);
+    assert_eq!(escrow.maker, MAKER);
+    assert_eq!(escrow.mint_a, MINT_A);
+    assert_eq!(escrow.mint_b, MINT_B);
+    assert_eq!(escrow.receive, 1_000);
+    assert_eq!(escrow.bump, [254]);
+}
+
+#[test]
+fn current_reader_rejects_v1_account() {
+    let data = v1_account();
+    assert!(matches!(
+        Escrow::load(&data.0[..EscrowV1::LEN]),
+        Err(ProgramError::InvalidAccountData)
+    ));
+}
+
+#[test]
+fn current_reader_rejects_unversioned_data() {
+    let data = v1_account();
+    assert!(matches!(
+        Escrow::load(&data.0[..Escrow::LEN]),
+        Err(ProgramError::InvalidAccountData)
+    ));
+}
+
+#[test]
+fn upgrade_keeps_v1_fields_and_sets_the_version() {
+    let mut data = v1_account();
+    // `resize` zero-extends the account to the v2 length.
+    Escrow::upgrade_from_v1(&mut data.0[..Escrow::LEN]).unwrap();
+
+    let escrow = Escrow::load(&data.0[..Escrow::LEN]).unwrap();
+    assert_eq!(escrow.seed, 42);
+    assert_eq!(escrow.maker, MAKER);
+    assert_eq!(escrow.mint_a, MINT_A);
+    assert_eq!(escrow.mint_b, MINT_B);
+    assert_eq!(escrow.receive, 1_000);
+    assert_eq!(escrow.bump, [254]);
+    assert_eq!(escrow.version, ESCROW_VERSION);
+}
+
+#[test]
+fn upgrade_sends_rent_to_the_maker() {
+    let mut data = v1_account();
+    // v1 escrows closed into the maker; migrating must not change who is refunded.
+    data.0[113..146].fill(0xff);
+    data.0[113] = 0;
+    let escrow = Escrow::upgrade_from_v1(&mut data.0[..Escrow::LEN]).unwrap();
+    assert_eq!(escrow.rent_recipient, MAKER);
+}
+
+#[test]
+fn upgrade_rejects_already_migrated_account() {
+    let mut data = v1_account();
+    Escrow::upgrade_from_v1(&mut data.0[..Escrow::LEN]).unwrap();
+    assert!(matches!(
+        Escrow::upgrade_from_v1(&mut data.0[..Escrow::LEN]),
+        Err(ProgramError::AccountAlreadyInitialized)
+    ));
+}
+
+#[test]
+fn upgrade_rejects_wrong_length() {
+    let mut data = v1_account();
+    assert!(matches!(
+        Escrow::upgrade_from_v1(&mut data.0[..EscrowV1::LEN]),
+        Err(ProgramError::InvalidAccountData)
+    ));
+}
+
+#[test]
+fn layouts_have_distinct_lengths() {
+    let lens = [
+        EscrowV1::LEN,
+        Escrow::LEN,
+        AttestedEscrow::LEN,
+        LimitEscrow::LEN,
+    ];
+    for (i, len) in lens.iter().enumerate() {
+        assert!(
+            !lens[i + 1..].contains(len),
+            "two escrow layouts are {len} bytes"
+        );
+    }
+}
+
+#[test]
+fn current_reader_rejects_attested_escrow() {
+    let mut data = v1_account();
+    // A compressed attester key starts with 0x02 or 0x03, right where `version` is.
+    for prefix in [2, 3] {
+        AttestedEscrow::load_mut(&mut data.0[..AttestedEscrow::LEN])
+            .unwrap()
+            .attester = [prefix; 33];
+        assert!(matches!(
+            Escrow::load(&data.0[..AttestedEscrow::LEN]),
+            Err(ProgramError::InvalidAccountData)
+        ));
+    }
+}
@@ -0,0 +1,57 @@
+//! Test program standing in for an oracle: writes `PriceFeed` accounts that the
+//! escrow's limit orders settle at.
+//!
+//! Accounts: the price feed, created with this program as owner and at least
+//! `PriceFeed::LEN` bytes. The instruction data is the `PriceFeed` layout itself
+//! (mint_a, mint_b, price, slot); a slot of 0 publishes at the current slot, any
+//! other value lets tests backdate the price to make it stale.
+
+#![no_std]
+
+use blueshift_pinocchio_escrow::PriceFeed;
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+pinocchio::entrypoint!(process_instruction);
+pinocchio::nostd_panic_handler!();
+
+// 44444444444444444444444444444444444444444444
+pub const ID: Pubkey = [
+    0x2d, 0x5b, 0x41, 0x3c, 0x65, 0x40, 0xde, 0x15,
+    0x0c, 0x93, 0x73, 0x14, 0x4d, 0x51, 0x33, 0xca,
+    0x4c, 0xb8, 0x30, 0xba, 0x0f, 0x75, 0x67, 0x16,
+    0xac, 0xea, 0x0e, 0x50, 0xd7, 0x94, 0x35, 0xe5,
+];
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let [price_feed] = accounts else {
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    if !price_feed.is_owned_by(program_id) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    if instruction_data.len() != PriceFeed::LEN {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let mint_a: Pubkey = instruction_data[0..32].try_into().unwrap();
+    let mint_b: Pubkey = instruction_data[32..64].try_into().unwrap();
+    let price = u64::from_le_bytes(instruction_data[64..72].try_into().unwrap());
+    let slot = match u64::from_le_bytes(instruction_data[72..80].try_into().unwrap()) {
+        0 => Clock::get()?.slot,
+        slot => slot,
+    };
+
+    let mut data = price_feed.try_borrow_mut_data()?;
+    PriceFeed::load_mut(&mut data)?.set_inner(mint_a, mint_b, price, slot);
+    Ok(())
+}
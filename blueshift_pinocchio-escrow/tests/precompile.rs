@@ -0,0 +1,274 @@
+//! Signature precompile introspection against hand-built instructions sysvar data.
+
+use blueshift_pinocchio_escrow::{
+    precompile::{ED25519_PROGRAM_ID, SECP256R1_PROGRAM_ID},
+    signed_order, verify_attestation, MakeAttestedInstructionData, Secp256r1Pubkey, SignedOrder,
+};
+use pinocchio::{program_error::ProgramError, sysvars::instructions::Instructions};
+
+const ESCROW: [u8; 32] = [1; 32];
+const TAKER: [u8; 32] = [2; 32];
+const OTHER_PROGRAM: [u8; 32] = [3; 32];
+const ATTESTER: Secp256r1Pubkey = [4; 33];
+const MAKER: [u8; 32] = [5; 32];
+const MINT_A: [u8; 32] = [6; 32];
+const MINT_B: [u8; 32] = [7; 32];
+const EXPIRY: i64 = 1_700_000_000;
+
+/// Offsets-entry value meaning "this same instruction".
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+/// Single-signature precompile data with the signature, public key and message all
+/// stored after the offsets; the three instruction indices are given.
+fn precompile_data(pubkey: &[u8], message: &[u8], indices: [u16; 3]) -> Vec<u8> {
+    let signature_offset = 16u16;
+    let public_key_offset = signature_offset + 64;
+    let message_offset = public_key_offset + pubkey.len() as u16;
+
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        indices[0],
+        public_key_offset,
+        indices[1],
+        message_offset,
+        message.len() as u16,
+        indices[2],
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(&[0; 64]);
+    data.extend_from_slice(pubkey);
+    data.extend_from_slice(message);
+    data
+}
+
+/// Instructions sysvar data for `instructions` (program id, data) with no account
+/// metas, currently executing the first one.
+fn sysvar(instructions: &[(&[u8; 32], &[u8])]) -> Vec<u8> {
+    let header = 2 + 2 * instructions.len();
+    let mut offsets = Vec::new();
+    let mut body = Vec::new();
+    for (program_id, data) in instructions {
+        offsets.extend_from_slice(&((header + body.len()) as u16).to_le_bytes());
+        body.extend_from_slice(&0u16.to_le_bytes());
+        body.extend_from_slice(*program_id);
+        body.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        body.extend_from_slice(data);
+    }
+
+    let mut sysvar = (instructions.len() as u16).to_le_bytes().to_vec();
+    sysvar.extend_from_slice(&offsets);
+    sysvar.extend_from_slice(&body);
+    sysvar.extend_from_slice(&0u16.to_le_bytes());
+    sysvar
+}
+
+fn attestation_message(escrow: &[u8; 32], taker: &[u8; 32]) -> Vec<u8> {
+    [escrow.as_slice(), taker.as_slice(), &EXPIRY.to_le_bytes()].concat()
+}
+
+fn check_attestation(sysvar: &[u8]) -> Result<i64, ProgramError> {
+    let instructions = unsafe { Instructions::new_unchecked(sysvar) };
+    verify_attestation(&instructions, &ATTESTER, &ESCROW, &TAKER)
+}
+
+#[test]
+fn attestation_accepts_attester_signing_escrow_taker_and_expiry() {
+    let verify = precompile_data(
+        &ATTESTER,
+        &attestation_message(&ESCROW, &TAKER),
+        [CURRENT_INSTRUCTION; 3],
+    );
+    let data = sysvar(&[
+        (&blueshift_pinocchio_escrow::ID, &[]),
+        (&SECP256R1_PROGRAM_ID, &verify),
+    ]);
+    assert_eq!(check_attestation(&data), Ok(EXPIRY));
+}
+
+#[test]
+fn attestation_requires_a_following_verify_instruction() {
+    let data = sysvar(&[(&blueshift_pinocchio_escrow::ID, &[])]);
+    assert_eq!(
+        check_attestation(&data),
+        Err(ProgramError::InvalidInstructionData)
+    );
+
+    let verify = precompile_data(
+        &ATTESTER,
+        &attestation_message(&ESCROW, &TAKER),
+        [CURRENT_INSTRUCTION; 3],
+    );
+    let data = sysvar(&[
+        (&blueshift_pinocchio_escrow::ID, &[]),
+        (&OTHER_PROGRAM, &verify),
+    ]);
+    assert_eq!(
+        check_attestation(&data),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
+
+#[test]
+fn attestation_rejects_the_wrong_message() {
+    for message in [
+        attestation_message(&TAKER, &ESCROW),
+        attestation_message(&ESCROW, &OTHER_PROGRAM),
+        ESCROW.to_vec(),
+        // An attestation without an expiry, as signed before expiries were added.
+        [ESCROW, TAKER].concat(),
+        [attestation_message(&ESCROW, &TAKER), vec![0]].concat(),
+    ] {
+        let verify = precompile_data(&ATTESTER, &message, [CURRENT_INSTRUCTION; 3]);
+        let data = sysvar(&[
+            (&blueshift_pinocchio_escrow::ID, &[]),
+            (&SECP256R1_PROGRAM_ID, &verify),
+        ]);
+        assert_eq!(
+            check_attestation(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
+
+#[test]
+fn attestation_rejects_the_wrong_attester() {
+    let mut other = ATTESTER;
+    other[32] ^= 1;
+    let verify = precompile_data(
+        &other,
+        &attestation_message(&ESCROW, &TAKER),
+        [CURRENT_INSTRUCTION; 3],
+    );
+    let data = sysvar(&[
+        (&blueshift_pinocchio_escrow::ID, &[]),
+        (&SECP256R1_PROGRAM_ID, &verify),
+    ]);
+    assert_eq!(
+        check_attestation(&data),
+        Err(ProgramError::MissingRequiredSignature)
+    );
+}
+
+#[test]
+fn attestation_rejects_data_from_other_instructions() {
+    for field in 0..3 {
+        let mut indices = [CURRENT_INSTRUCTION; 3];
+        indices[field] = 0;
+        let verify = precompile_data(&ATTESTER, &attestation_message(&ESCROW, &TAKER), indices);
+        let data = sysvar(&[
+            (&blueshift_pinocchio_escrow::ID, &[]),
+            (&SECP256R1_PROGRAM_ID, &verify),
+        ]);
+        assert_eq!(
+            check_attestation(&data),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
+
+#[test]
+fn make_attested_data_carries_the_attester() {
+    let mut data = Vec::new();
+    data.extend_from_slice(&7u64.to_le_bytes());
+    data.extend_from_slice(&500u64.to_le_bytes());
+    data.extend_from_slice(&1_000u64.to_le_bytes());
+    data.extend_from_slice(&ATTESTER);
+
+    let parsed = MakeAttestedInstructionData::try_from(data.as_slice()).unwrap();
+    assert_eq!(parsed.make.seed, 7);
+    assert_eq!(parsed.make.receive, 500);
+    assert_eq!(parsed.make.amount, 1_000);
+    assert_eq!(parsed.attester, ATTESTER);
+
+    assert!(MakeAttestedInstructionData::try_from(&data[..data.len() - 1]).is_err());
+
+    data.push(0);
+    assert_eq!(
+        MakeAttestedInstructionData::try_from(data.as_slice()).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
+
+/// Signed order message from `maker`, addressed to `program_id`.
+fn order_message(program_id: &[u8; 32], maker: &[u8; 32], amount: u64) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(program_id);
+    message.extend_from_slice(maker);
+    message.extend_from_slice(&MINT_A);
+    message.extend_from_slice(&MINT_B);
+    message.extend_from_slice(&amount.to_le_bytes());
+    message.extend_from_slice(&250u64.to_le_bytes());
+    message.extend_from_slice(&9u64.to_le_bytes());
+    message.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+    message
+}
+
+fn check_order(
+    verify_program: &[u8; 32],
+    signer: &[u8; 32],
+    message: &[u8],
+) -> Result<SignedOrder, ProgramError> {
+    let verify = precompile_data(signer, message, [CURRENT_INSTRUCTION; 3]);
+    let data = sysvar(&[
+        (&blueshift_pinocchio_escrow::ID, &[]),
+        (verify_program, &verify),
+    ]);
+    let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+    signed_order(&instructions, &MAKER)
+}
+
+#[test]
+fn signed_order_reads_the_makers_order() {
+    let message = order_message(&blueshift_pinocchio_escrow::ID, &MAKER, 1_000);
+    let order = check_order(&ED25519_PROGRAM_ID, &MAKER, &message).unwrap();
+    assert_eq!(order.maker, MAKER);
+    assert_eq!(order.mint_a, MINT_A);
+    assert_eq!(order.mint_b, MINT_B);
+    assert_eq!(order.amount, 1_000);
+    assert_eq!(order.receive, 250);
+    assert_eq!(order.nonce, 9);
+    assert_eq!(order.expiry, 1_700_000_000);
+}
+
+#[test]
+fn signed_order_requires_the_makers_ed25519_signature() {
+    let message = order_message(&blueshift_pinocchio_escrow::ID, &MAKER, 1_000);
+    assert_eq!(
+        check_order(&SECP256R1_PROGRAM_ID, &MAKER, &message).err(),
+        Some(ProgramError::IncorrectProgramId)
+    );
+    assert_eq!(
+        check_order(&ED25519_PROGRAM_ID, &TAKER, &message).err(),
+        Some(ProgramError::MissingRequiredSignature)
+    );
+
+    // Signed by the maker, but naming someone else as the maker.
+    let message = order_message(&blueshift_pinocchio_escrow::ID, &TAKER, 1_000);
+    assert_eq!(
+        check_order(&ED25519_PROGRAM_ID, &MAKER, &message).err(),
+        Some(ProgramError::MissingRequiredSignature)
+    );
+}
+
+#[test]
+fn signed_order_rejects_malformed_orders() {
+    let other_program = order_message(&OTHER_PROGRAM, &MAKER, 1_000);
+    let empty = order_message(&blueshift_pinocchio_escrow::ID, &MAKER, 0);
+    let mut truncated = order_message(&blueshift_pinocchio_escrow::ID, &MAKER, 1_000);
+    truncated.pop();
+    for message in [other_program, empty, truncated] {
+        assert_eq!(
+            check_order(&ED25519_PROGRAM_ID, &MAKER, &message).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+
+    let data = sysvar(&[(&blueshift_pinocchio_escrow::ID, &[])]);
+    let instructions = unsafe { Instructions::new_unchecked(data.as_slice()) };
+    assert_eq!(
+        signed_order(&instructions, &MAKER).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
@@ -0,0 +1,63 @@
+//! Quote return data: price and byte layout.
+
+use blueshift_pinocchio_escrow::{Escrow, EscrowQuote, ESCROW_VERSION, QUOTE_PRICE_SCALE};
+use pinocchio::program_error::ProgramError;
+
+const MAKER: [u8; 32] = [7; 32];
+const MINT_A: [u8; 32] = [8; 32];
+const MINT_B: [u8; 32] = [9; 32];
+
+/// Account data buffer, 8-byte aligned like the runtime's.
+#[repr(C, align(8))]
+struct AccountData([u8; Escrow::LEN]);
+
+fn escrow_account(receive: u64) -> AccountData {
+    let mut data = AccountData([0; Escrow::LEN]);
+    Escrow::load_mut(&mut data.0)
+        .unwrap()
+        .set_inner(42, MAKER, MINT_A, MINT_B, receive, [254], MAKER);
+    data
+}
+
+#[test]
+fn quote_reports_escrow_terms() {
+    let data = escrow_account(500);
+    let escrow = Escrow::load(&data.0).unwrap();
+    assert_eq!(escrow.version, ESCROW_VERSION);
+
+    let quote = EscrowQuote::new(escrow, 1_000);
+    assert_eq!(quote.seed, 42);
+    assert_eq!(quote.maker, MAKER);
+    assert_eq!(quote.mint_a, MINT_A);
+    assert_eq!(quote.mint_b, MINT_B);
+    assert_eq!(quote.vault_amount, 1_000);
+    assert_eq!(quote.receive, 500);
+    assert_eq!(quote.price, QUOTE_PRICE_SCALE / 2);
+}
+
+#[test]
+fn price_is_zero_for_empty_vault_and_saturates() {
+    let data = escrow_account(u64::MAX);
+    let escrow = Escrow::load(&data.0).unwrap();
+    assert_eq!(EscrowQuote::new(escrow, 0).price, 0);
+    assert_eq!(EscrowQuote::new(escrow, 1).price, u64::MAX);
+}
+
+#[test]
+fn return_data_round_trips() {
+    let data = escrow_account(500);
+    let bytes = EscrowQuote::new(Escrow::load(&data.0).unwrap(), 1_000).to_bytes();
+    assert_eq!(bytes.len(), EscrowQuote::LEN);
+    assert_eq!(&bytes[0..8], &42u64.to_le_bytes());
+    assert_eq!(&bytes[8..40], &MAKER);
+
+    let quote = EscrowQuote::from_bytes(&bytes).unwrap();
+    assert_eq!(quote.vault_amount, 1_000);
+    assert_eq!(quote.receive, 500);
+    assert_eq!(quote.price, QUOTE_PRICE_SCALE / 2);
+
+    assert_eq!(
+        EscrowQuote::from_bytes(&bytes[1..]).err(),
+        Some(ProgramError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,136 @@
+//! Accounts for scenario tests: wallets, program accounts, mints and token
+//! accounts laid out as the system and token programs expect, and PDAs derived
+//! the way the programs derive them on-chain.
+
+use crate::programs::Program;
+use crate::strategy::ASSOCIATED_TOKEN_PROGRAM_ID;
+use crate::{Account, AccountInput, Invocation, Pubkey, SYSTEM_PROGRAM_ID, TOKEN_ACCOUNT_LEN};
+
+/// Rent-exempt balance of an SPL mint.
+pub const MINT_LAMPORTS: u64 = rent_exempt(82);
+/// Rent-exempt balance of an SPL token account.
+pub const TOKEN_ACCOUNT_LAMPORTS: u64 = rent_exempt(TOKEN_ACCOUNT_LEN);
+/// Decimals of every fixture mint.
+pub const DECIMALS: u8 = 6;
+
+/// Rent-exempt minimum of an account of `len` bytes under the SVM's default rent.
+pub const fn rent_exempt(len: usize) -> u64 {
+    (len as u64 + 128) * 6_960
+}
+
+/// Account data, 8-byte aligned like the runtime's, for the programs' zero-copy
+/// `load`/`load_mut`.
+#[repr(C, align(8))]
+pub struct AccountData<const LEN: usize>(pub [u8; LEN]);
+
+impl<const LEN: usize> AccountData<LEN> {
+    pub const fn zeroed() -> Self {
+        Self([0; LEN])
+    }
+
+    /// A copy of `account`'s data, which must be `LEN` bytes long.
+    pub fn of(account: &Account) -> Self {
+        let mut data = Self::zeroed();
+        data.0.copy_from_slice(&account.data);
+        data
+    }
+}
+
+/// A writable system-owned wallet.
+pub fn wallet(key: Pubkey, lamports: u64) -> Account {
+    Account {
+        key,
+        owner: SYSTEM_PROGRAM_ID,
+        lamports,
+        data: Vec::new(),
+        is_signer: false,
+        is_writable: true,
+        executable: false,
+    }
+}
+
+/// A writable system-owned wallet that signs.
+pub fn signer(key: Pubkey, lamports: u64) -> Account {
+    Account {
+        is_signer: true,
+        ..wallet(key, lamports)
+    }
+}
+
+/// The program at `key`; the harness passes the SVM's own account for it.
+pub fn program(key: Pubkey) -> Account {
+    Account {
+        is_writable: false,
+        executable: true,
+        ..wallet(key, 1)
+    }
+}
+
+/// An initialized mint of `token_program` with `DECIMALS` and no authorities.
+pub fn mint(key: Pubkey, token_program: Pubkey) -> Account {
+    let mut data = vec![0; 82];
+    data[36..44].copy_from_slice(&u64::MAX.to_le_bytes());
+    data[44] = DECIMALS;
+    data[45] = 1;
+    Account {
+        owner: token_program,
+        data,
+        ..wallet(key, MINT_LAMPORTS)
+    }
+}
+
+/// An initialized token account of `token_program` for `mint`, owned by `owner`
+/// and holding `amount`.
+pub fn token_account(
+    key: Pubkey,
+    token_program: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) -> Account {
+    let mut data = vec![0; TOKEN_ACCOUNT_LEN];
+    data[0..32].copy_from_slice(&mint);
+    data[32..64].copy_from_slice(&owner);
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    data[108] = 1;
+    Account {
+        owner: token_program,
+        data,
+        ..wallet(key, TOKEN_ACCOUNT_LAMPORTS)
+    }
+}
+
+/// The token amount `account` holds.
+pub fn token_amount(account: &Account) -> u64 {
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+/// The PDA of `seeds` under `program_id` and its bump.
+pub fn pda(seeds: &[&[u8]], program_id: &Pubkey) -> (Pubkey, u8) {
+    let (address, bump) = solana_pubkey::Pubkey::find_program_address(seeds, &(*program_id).into());
+    (address.to_bytes(), bump)
+}
+
+/// `wallet`'s associated token account for `mint` under `token_program`.
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Pubkey {
+    pda(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// An invocation of `program` with discriminator `discriminator` followed by
+/// `data`, passing each of `accounts` once.
+pub fn invocation(
+    program: Program,
+    discriminator: u8,
+    data: &[u8],
+    accounts: Vec<Account>,
+) -> Invocation {
+    Invocation {
+        program,
+        accounts: accounts.into_iter().map(AccountInput::Account).collect(),
+        data: [&[discriminator], data].concat(),
+    }
+}
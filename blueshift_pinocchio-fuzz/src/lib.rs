@@ -0,0 +1,336 @@
+//! Property-based fuzzing harness for the Pinocchio programs in this repository
+//! (escrow, vault and secp256r1 vault).
+//!
+//! Invocations run under mollusk-svm against the SBF builds of the programs, with
+//! the real system, SPL Token, Token-2022 and associated token programs loaded, so
+//! CPIs, PDA syscalls and the `Clock` and `Rent` sysvars behave as on-chain. The
+//! escrow and both vaults share a program ID, so each [`Program`] runs in an SVM
+//! of its own. Build the programs first with `cargo build-sbf` in each program's
+//! directory and in `blueshift_pinocchio-escrow/tests/cpi-taker`; the harness
+//! reads each `.so` from the crate's `target/deploy`, or from `SBF_OUT_DIR` when
+//! set.
+//!
+//! After a successful instruction the harness checks conservation on the accounts
+//! as the SVM left them, after every CPI: lamports and the tokens of each mint may
+//! not be created out of thin air. A program that aborts (a panic, or an abort in
+//! a CPI it made) fails the run; an instruction the runtime itself rejects, such
+//! as a CPI escalating privileges, is an [`Outcome::Rejected`] and changes nothing.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use programs::Program;
+
+use mollusk_svm::program::{
+    create_program_account_loader_v3, keyed_account_for_system_program, loader_keys,
+};
+use mollusk_svm::result::ProgramResult;
+use mollusk_svm::Mollusk;
+use mollusk_svm_programs_token::{associated_token, token, token2022};
+use solana_instruction::error::InstructionError;
+use solana_instruction::{AccountMeta, Instruction};
+
+pub mod fixtures;
+pub mod programs;
+pub mod strategy;
+
+pub type Pubkey = [u8; 32];
+
+pub const SYSTEM_PROGRAM_ID: Pubkey = [0; 32];
+// TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+pub const TOKEN_PROGRAM_ID: Pubkey = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xd7, 0x65, 0xa1, 0x93, 0xd9, 0xcb, 0xe1, 0x46, 0xce, 0xeb, 0x79, 0xac,
+    0x1c, 0xb4, 0x85, 0xed, 0x5f, 0x5b, 0x37, 0x91, 0x3a, 0x8c, 0xf5, 0x85, 0x7e, 0xff, 0x00, 0xa9,
+];
+// TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xee, 0x75, 0x8f, 0xde, 0x18, 0x42, 0x5d, 0xbc, 0xe4, 0x6c, 0xcd, 0xda,
+    0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0x0d, 0x27, 0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc,
+];
+
+/// SPL token account length and the offsets this harness reads. Token-2022
+/// accounts with extensions are longer, with their account type at
+/// `TOKEN_ACCOUNT_LEN`.
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+const TOKEN_ACCOUNT_MINT_OFFSET: usize = 0;
+const TOKEN_ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+const TOKEN_2022_ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+
+/// Unix timestamp of the clock sysvar in [`run`].
+pub const NOW: i64 = 1_700_000_000;
+
+/// An account as the runtime sees it before and after an instruction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Account {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+}
+
+/// One entry of an instruction's account list.
+#[derive(Clone, Debug)]
+pub enum AccountInput {
+    Account(Account),
+    /// Repeats the account at this earlier position.
+    Duplicate(u8),
+}
+
+/// A program invocation: the instruction's accounts and data.
+#[derive(Clone, Debug)]
+pub struct Invocation {
+    pub program: Program,
+    pub accounts: Vec<AccountInput>,
+    pub data: Vec<u8>,
+}
+
+impl Invocation {
+    /// The distinct accounts, in order of first appearance.
+    pub fn unique_accounts(&self) -> Vec<Account> {
+        self.accounts
+            .iter()
+            .filter_map(|input| match input {
+                AccountInput::Account(account) => Some(account.clone()),
+                AccountInput::Duplicate(_) => None,
+            })
+            .collect()
+    }
+
+    /// The account at position `index`, following duplicates.
+    pub fn account_at(&self, index: usize) -> &Account {
+        match &self.accounts[index] {
+            AccountInput::Account(account) => account,
+            AccountInput::Duplicate(original) => self.account_at(*original as usize),
+        }
+    }
+}
+
+/// Result of running one invocation.
+#[derive(Debug)]
+pub enum Outcome {
+    /// The program returned `Ok`: the accounts as the SVM left them, in order of
+    /// first appearance, and the return data.
+    Success {
+        accounts: Vec<Account>,
+        return_data: Vec<u8>,
+    },
+    /// The program returned an error code. The runtime discards all changes.
+    Error(u64),
+    /// The runtime rejected the instruction, e.g. for a CPI escalating privileges
+    /// or breaking an account rule. The runtime discards all changes.
+    Rejected(String),
+}
+
+thread_local! {
+    static SVMS: RefCell<BTreeMap<Program, Mollusk>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+/// An SVM with `program`, the programs it calls, and the system, token and
+/// associated token programs.
+fn svm(program: Program) -> Mollusk {
+    let sbf_programs = program.sbf_programs();
+    for (i, loaded) in sbf_programs.iter().enumerate() {
+        // A second program at the same ID would replace the first.
+        assert!(
+            sbf_programs[..i].iter().all(|other| other.id != loaded.id),
+            "{} shares its program ID with another program in the {program:?} SVM",
+            loaded.name
+        );
+    }
+
+    let mut svm = Mollusk::default();
+    token::add_program(&mut svm);
+    token2022::add_program(&mut svm);
+    associated_token::add_program(&mut svm);
+    for loaded in sbf_programs {
+        svm.add_program_with_elf_and_loader(
+            &loaded.id.into(),
+            &loaded.elf(),
+            &loader_keys::LOADER_V3,
+        );
+    }
+    svm
+}
+
+/// The account the runtime holds at `key` if it is one of the programs loaded
+/// into `program`'s SVM. Programs are accounts the bank owns, so whatever an
+/// invocation passes at one of these keys is replaced by it.
+fn program_account(program: Program, key: &Pubkey) -> Option<solana_account::Account> {
+    let account = match *key {
+        SYSTEM_PROGRAM_ID => keyed_account_for_system_program().1,
+        TOKEN_PROGRAM_ID => token::keyed_account().1,
+        TOKEN_2022_PROGRAM_ID => token2022::keyed_account().1,
+        strategy::ASSOCIATED_TOKEN_PROGRAM_ID => associated_token::keyed_account().1,
+        _ if program
+            .sbf_programs()
+            .iter()
+            .any(|loaded| loaded.id == *key) =>
+        {
+            create_program_account_loader_v3(&(*key).into())
+        }
+        _ => return None,
+    };
+    Some(account)
+}
+
+/// The instruction and account set the SVM runs for `invocation`.
+fn svm_instruction(
+    invocation: &Invocation,
+) -> (
+    Instruction,
+    Vec<(solana_pubkey::Pubkey, solana_account::Account)>,
+) {
+    let metas = (0..invocation.accounts.len())
+        .map(|index| {
+            let account = invocation.account_at(index);
+            AccountMeta {
+                pubkey: account.key.into(),
+                is_signer: account.is_signer,
+                is_writable: account.is_writable,
+            }
+        })
+        .collect();
+    let accounts = invocation
+        .unique_accounts()
+        .into_iter()
+        .map(|account| {
+            let svm_account = program_account(invocation.program, &account.key).unwrap_or(solana_account::Account {
+                lamports: account.lamports,
+                data: account.data,
+                owner: account.owner.into(),
+                executable: account.executable,
+                rent_epoch: u64::MAX,
+            });
+            (account.key.into(), svm_account)
+        })
+        .collect();
+    let instruction = Instruction {
+        program_id: invocation.program.id().into(),
+        accounts: metas,
+        data: invocation.data.clone(),
+    };
+    (instruction, accounts)
+}
+
+/// `accounts` as held by the SVM, with the flags `invocation` passed them with.
+fn from_svm(
+    invocation: &Invocation,
+    accounts: &[(solana_pubkey::Pubkey, solana_account::Account)],
+) -> Vec<Account> {
+    invocation
+        .unique_accounts()
+        .into_iter()
+        .map(|account| {
+            let (_, held) = accounts
+                .iter()
+                .find(|(key, _)| key.to_bytes() == account.key)
+                .expect("account missing from the SVM's account set");
+            Account {
+                owner: held.owner.to_bytes(),
+                lamports: held.lamports,
+                data: held.data.clone(),
+                executable: held.executable,
+                ..account
+            }
+        })
+        .collect()
+}
+
+/// Run `invocation` under the SVM with the clock at `unix_timestamp`, checking
+/// conservation if it succeeds. Returns `Err` if the program aborted or created
+/// lamports or tokens.
+pub fn run_at(invocation: &Invocation, unix_timestamp: i64) -> Result<Outcome, String> {
+    let (instruction, accounts) = svm_instruction(invocation);
+    let result = SVMS.with(|svms| {
+        let mut svms = svms.borrow_mut();
+        let svm = svms
+            .entry(invocation.program)
+            .or_insert_with(|| svm(invocation.program));
+        svm.sysvars.clock.unix_timestamp = unix_timestamp;
+        svm.process_instruction(&instruction, &accounts)
+    });
+
+    match result.program_result {
+        ProgramResult::Success => {
+            let before = from_svm(invocation, &accounts);
+            let after = from_svm(invocation, &result.resulting_accounts);
+            check_conservation(&before, &after)?;
+            Ok(Outcome::Success {
+                accounts: after,
+                return_data: result.return_data,
+            })
+        }
+        ProgramResult::Failure(error) => Ok(Outcome::Error(u64::from(error))),
+        ProgramResult::UnknownError(InstructionError::ProgramFailedToComplete) => {
+            Err(String::from("program aborted"))
+        }
+        ProgramResult::UnknownError(error) => Ok(Outcome::Rejected(format!("{error:?}"))),
+    }
+}
+
+/// Token amount per mint over all initialized SPL Token and Token-2022 accounts
+/// in `accounts`.
+pub fn token_balances(accounts: &[Account]) -> BTreeMap<Pubkey, u128> {
+    let mut balances = BTreeMap::new();
+    for account in accounts {
+        let is_token_account = match account.owner {
+            TOKEN_PROGRAM_ID => account.data.len() == TOKEN_ACCOUNT_LEN,
+            TOKEN_2022_PROGRAM_ID => {
+                account.data.len() == TOKEN_ACCOUNT_LEN
+                    || account.data.get(TOKEN_ACCOUNT_LEN) == Some(&TOKEN_2022_ACCOUNT_TYPE_ACCOUNT)
+            }
+            _ => false,
+        };
+        if !is_token_account || account.data[TOKEN_ACCOUNT_STATE_OFFSET] == 0 {
+            continue;
+        }
+        let data = &account.data;
+        let mint: Pubkey = data[TOKEN_ACCOUNT_MINT_OFFSET..TOKEN_ACCOUNT_MINT_OFFSET + 32]
+            .try_into()
+            .unwrap();
+        let amount = u64::from_le_bytes(
+            data[TOKEN_ACCOUNT_AMOUNT_OFFSET..TOKEN_ACCOUNT_AMOUNT_OFFSET + 8]
+                .try_into()
+                .unwrap(),
+        );
+        *balances.entry(mint).or_insert(0) += amount as u128;
+    }
+    balances
+}
+
+/// Check that a successful instruction, CPIs included, moved lamports and tokens
+/// between the accounts it was passed without creating any.
+pub fn check_conservation(before: &[Account], after: &[Account]) -> Result<(), String> {
+    let total = |accounts: &[Account]| accounts.iter().map(|a| a.lamports as u128).sum::<u128>();
+    if total(after) != total(before) {
+        return Err(format!(
+            "lamports not conserved: {} before, {} after",
+            total(before),
+            total(after)
+        ));
+    }
+
+    let (pre_tokens, post_tokens) = (token_balances(before), token_balances(after));
+    for (mint, amount) in &post_tokens {
+        let previous = pre_tokens.get(mint).copied().unwrap_or(0);
+        if *amount > previous {
+            return Err(format!(
+                "{} tokens of mint {:?} created out of thin air",
+                amount - previous,
+                mint
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// [`run_at`] with the clock at [`NOW`]. This is the property every entrypoint
+/// test asserts.
+pub fn run(invocation: &Invocation) -> Result<Outcome, String> {
+    run_at(invocation, NOW)
+}
@@ -0,0 +1,347 @@
+//! The programs under test, loaded into the SVM from their SBF builds, and the
+//! instruction shapes each decodes.
+
+use std::path::PathBuf;
+
+use crate::strategy::{InstructionShape, Slot, ASSOCIATED_TOKEN_PROGRAM_ID};
+use crate::{Pubkey, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID};
+
+pub const ESCROW_ID: Pubkey = blueshift_pinocchio_escrow::ID;
+pub const VAULT_ID: Pubkey = blueshift_pinocchio_vault::ID;
+pub const SECP256R1_VAULT_ID: Pubkey = blueshift_secp256r1_vault::ID;
+// 33333333333333333333333333333333333333333333
+/// The `cpi_taker` test program, which calls the escrow through its `cpi` module.
+pub const CPI_TAKER_ID: Pubkey = [
+    0x1e, 0x3c, 0xd6, 0x28, 0x43, 0x80, 0x94, 0x0e, 0x08, 0x62, 0x4c, 0xb8, 0x33, 0x8b, 0x77, 0xdc,
+    0x33, 0x25, 0x75, 0xd1, 0x5f, 0xa3, 0x9a, 0x0f, 0x1d, 0xf1, 0x5e, 0xe0, 0x8f, 0xb8, 0x23, 0xee,
+];
+
+/// A program the SVM runs from its SBF build.
+pub struct SbfProgram {
+    pub id: Pubkey,
+    /// Crate directory, relative to the repository root.
+    pub dir: &'static str,
+    /// File name of the build under the crate's `target/deploy`, without `.so`.
+    pub name: &'static str,
+}
+
+impl SbfProgram {
+    /// The program's ELF, from `SBF_OUT_DIR` if set, otherwise from the crate's
+    /// `target/deploy`.
+    pub fn elf(&self) -> Vec<u8> {
+        let file = format!("{}.so", self.name);
+        let path = match std::env::var_os("SBF_OUT_DIR") {
+            Some(dir) => PathBuf::from(dir).join(file),
+            None => PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+                .join("..")
+                .join(self.dir)
+                .join("target/deploy")
+                .join(file),
+        };
+        std::fs::read(&path).unwrap_or_else(|error| {
+            panic!(
+                "{}: {error}; run `cargo build-sbf` in {} first",
+                path.display(),
+                self.dir
+            )
+        })
+    }
+}
+
+const ESCROW: SbfProgram = SbfProgram {
+    id: ESCROW_ID,
+    dir: "blueshift_pinocchio-escrow",
+    name: "blueshift_pinocchio_escrow",
+};
+const VAULT: SbfProgram = SbfProgram {
+    id: VAULT_ID,
+    dir: "blueshift_pinocchio-vault",
+    name: "blueshift_pinocchio_vault",
+};
+const SECP256R1_VAULT: SbfProgram = SbfProgram {
+    id: SECP256R1_VAULT_ID,
+    dir: "blueshift_secp256r1_vault",
+    name: "blueshift_secp256r1_vault",
+};
+const CPI_TAKER: SbfProgram = SbfProgram {
+    id: CPI_TAKER_ID,
+    dir: "blueshift_pinocchio-escrow/tests/cpi-taker",
+    name: "cpi_taker",
+};
+
+/// A program an invocation runs. The escrow and both vaults keep the same
+/// placeholder ID, so each runs in an SVM of its own, where that ID is its alone.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Program {
+    Escrow,
+    Vault,
+    Secp256r1Vault,
+    /// Runs with the escrow loaded, which it calls.
+    CpiTaker,
+}
+
+impl Program {
+    pub const fn id(self) -> Pubkey {
+        self.sbf_programs()[0].id
+    }
+
+    /// The SBF builds this program's SVM loads besides the system and token
+    /// programs: the program itself first, then any it calls. Their IDs are
+    /// distinct.
+    pub const fn sbf_programs(self) -> &'static [SbfProgram] {
+        match self {
+            Self::Escrow => &[ESCROW],
+            Self::Vault => &[VAULT],
+            Self::Secp256r1Vault => &[SECP256R1_VAULT],
+            Self::CpiTaker => &[CPI_TAKER, ESCROW],
+        }
+    }
+}
+
+/// Every program an invocation can run.
+pub const PROGRAMS: [Program; 4] = [
+    Program::Escrow,
+    Program::Vault,
+    Program::Secp256r1Vault,
+    Program::CpiTaker,
+];
+
+const fn shape(discriminator: u8, data_len: usize, slots: &'static [Slot]) -> InstructionShape {
+    InstructionShape {
+        discriminator,
+        data_len,
+        slots,
+    }
+}
+
+const SYSTEM: Slot = Slot::Program(SYSTEM_PROGRAM_ID);
+const TOKEN: Slot = Slot::Program(TOKEN_PROGRAM_ID);
+const ATA: Slot = Slot::Program(ASSOCIATED_TOKEN_PROGRAM_ID);
+
+const MAKE: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Any,
+    TOKEN,
+    ATA,
+    SYSTEM,
+];
+const MAKE_SPONSORED: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Any,
+    TOKEN,
+    ATA,
+    SYSTEM,
+    Slot::Signer,
+];
+const TAKE: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Escrow,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    Slot::Token,
+    Slot::Token,
+    SYSTEM,
+    TOKEN,
+    ATA,
+    Slot::Any,
+];
+const TAKE_ATTESTED: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Escrow,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    Slot::Token,
+    Slot::Token,
+    SYSTEM,
+    TOKEN,
+    ATA,
+    Slot::Instructions,
+];
+const REFUND: &[Slot] = &[
+    Slot::Signer,
+    Slot::Escrow,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    SYSTEM,
+    TOKEN,
+    Slot::Any,
+];
+const REFUND_ATTESTED: &[Slot] = &[
+    Slot::Signer,
+    Slot::Escrow,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    SYSTEM,
+    TOKEN,
+];
+const FILL_ORDER: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Any,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    Slot::Token,
+    Slot::Token,
+    Slot::Instructions,
+    SYSTEM,
+    TOKEN,
+];
+const TAKE_MANY_ONE: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    SYSTEM,
+    TOKEN,
+    ATA,
+    Slot::Any,
+    Slot::Escrow,
+    Slot::Token,
+    Slot::Token,
+    Slot::Any,
+];
+const TAKE_MANY_TWO: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    SYSTEM,
+    TOKEN,
+    ATA,
+    Slot::Any,
+    Slot::Escrow,
+    Slot::Token,
+    Slot::Token,
+    Slot::Any,
+    Slot::Any,
+    Slot::Escrow,
+    Slot::Token,
+    Slot::Token,
+    Slot::Any,
+];
+const SWEEP: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    TOKEN,
+];
+
+/// Make (with and without a rent recipient or payer), Take (with and without
+/// limits), Refund, MakeAttested, TakeAttested, RefundAttested, FillOrder,
+/// CancelOrder, TakeMany (one and two escrows), Migrate, Quote, Sweep (with and
+/// without the escrow kind), MakeLimit, TakeLimit (with and without a maximum)
+/// and RefundLimit.
+pub const ESCROW_SHAPES: &[InstructionShape] = &[
+    shape(0, 24, MAKE),
+    shape(0, 56, MAKE),
+    shape(0, 24, MAKE_SPONSORED),
+    shape(1, 0, TAKE),
+    shape(1, 16, TAKE),
+    shape(2, 0, REFUND),
+    shape(3, 57, MAKE),
+    shape(4, 0, TAKE_ATTESTED),
+    shape(5, 0, REFUND_ATTESTED),
+    shape(6, 0, FILL_ORDER),
+    shape(7, 8, &[Slot::Signer, Slot::Any, SYSTEM]),
+    shape(8, 1, TAKE_MANY_ONE),
+    shape(8, 1, TAKE_MANY_TWO),
+    shape(9, 0, &[Slot::Signer, Slot::Escrow, SYSTEM]),
+    shape(10, 0, &[Slot::Escrow, Slot::Token]),
+    shape(11, 8, SWEEP),
+    shape(11, 9, SWEEP),
+    shape(12, 64, MAKE),
+    shape(13, 0, TAKE),
+    shape(13, 8, TAKE),
+    shape(14, 0, REFUND_ATTESTED),
+];
+
+/// TokenDeposit/TokenWithdraw: owner, token vault PDA, mint, owner_token_account,
+/// vault_token_account, token_program, vault state.
+const TOKEN_VAULT: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    TOKEN,
+    Slot::Any,
+];
+
+/// TokenWithdraw to a recipient_token_account.
+const TOKEN_VAULT_TO: &[Slot] = &[
+    Slot::Signer,
+    Slot::Any,
+    Slot::Any,
+    Slot::Token,
+    Slot::Token,
+    TOKEN,
+    Slot::Any,
+    Slot::Token,
+];
+
+/// Deposit and Withdraw (all or part), with and without a vault state and
+/// recipient;
+/// Initialize and Close; the withdrawal delay's SetTimelock, RequestWithdraw,
+/// ExecuteWithdraw and CancelWithdraw; the multisig vault's CreateMultisig
+/// (2 signers) and MultisigWithdraw (co-signed by 2); TokenDeposit and
+/// TokenWithdraw (all or part, to the owner or a recipient); SetSpendingLimit
+/// and Spend (to the spender or a recipient); SetBeneficiary and Claim.
+pub const VAULT_SHAPES: &[InstructionShape] = &[
+    shape(0, 8, &[Slot::Signer, Slot::Any, SYSTEM]),
+    shape(0, 8, &[Slot::Signer, Slot::Any, SYSTEM, Slot::Any]),
+    shape(1, 0, &[Slot::Signer, Slot::Any, SYSTEM]),
+    shape(1, 0, &[Slot::Signer, Slot::Any, SYSTEM, Slot::Any]),
+    shape(1, 8, &[Slot::Signer, Slot::Any, SYSTEM]),
+    shape(1, 8, &[Slot::Signer, Slot::Any, SYSTEM, Slot::Any]),
+    shape(1, 8, &[Slot::Signer, Slot::Any, SYSTEM, Slot::Any, Slot::Any]),
+    shape(2, 0, &[Slot::Signer, Slot::Any, Slot::Any, SYSTEM]),
+    shape(3, 0, &[Slot::Signer, Slot::Any, Slot::Any, SYSTEM]),
+    shape(4, 8, &[Slot::Signer, Slot::Any]),
+    shape(5, 8, &[Slot::Signer, Slot::Any]),
+    shape(6, 0, &[Slot::Signer, Slot::Any, SYSTEM, Slot::Any]),
+    shape(7, 0, &[Slot::Signer, Slot::Any]),
+    shape(8, 73, &[Slot::Signer, Slot::Any, SYSTEM]),
+    shape(9, 8, &[Slot::Any, Slot::Any, Slot::Signer, Slot::Signer]),
+    shape(10, 8, TOKEN_VAULT),
+    shape(11, 0, TOKEN_VAULT),
+    shape(11, 8, TOKEN_VAULT),
+    shape(11, 8, TOKEN_VAULT_TO),
+    shape(12, 48, &[Slot::Signer, Slot::Any]),
+    shape(13, 8, &[Slot::Signer, Slot::Any, Slot::Any, SYSTEM, Slot::Any]),
+    shape(
+        13,
+        8,
+        &[Slot::Signer, Slot::Signer, Slot::Any, SYSTEM, Slot::Any, Slot::Any],
+    ),
+    shape(14, 40, &[Slot::Signer, Slot::Any]),
+    shape(15, 0, &[Slot::Signer, Slot::Any, Slot::Any, Slot::Any, SYSTEM]),
+];
+
+/// Deposit and Withdraw.
+pub const SECP256R1_VAULT_SHAPES: &[InstructionShape] = &[
+    shape(0, 41, &[Slot::Signer, Slot::Any, SYSTEM]),
+    shape(1, 1, &[Slot::Signer, Slot::Any, Slot::Instructions, SYSTEM]),
+];
@@ -0,0 +1,409 @@
+//! proptest strategies for arbitrary invocations.
+//!
+//! Keys, owners and data lengths are biased towards the values the programs
+//! compare against so generated inputs get past the first checks. Everything the
+//! runtime itself guarantees (distinct keys for distinct accounts, a well-formed
+//! instructions sysvar) holds for every generated invocation.
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+use crate::programs::Program;
+use crate::{
+    Account, AccountInput, Invocation, Pubkey, SYSTEM_PROGRAM_ID, TOKEN_ACCOUNT_LEN,
+    TOKEN_PROGRAM_ID,
+};
+
+// ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = [
+    0x8c, 0x97, 0x25, 0x8f, 0x4e, 0x24, 0x89, 0xf1, 0xbb, 0x3d, 0x10, 0x29, 0x14, 0x8e, 0x0d, 0x83,
+    0x0b, 0x5a, 0x13, 0x99, 0xda, 0xff, 0x10, 0x84, 0x04, 0x8e, 0x7b, 0xd8, 0xdb, 0xe9, 0xf8, 0x59,
+];
+// Sysvar1nstructions1111111111111111111111111
+pub const INSTRUCTIONS_SYSVAR_ID: Pubkey = [
+    0x06, 0xa7, 0xd5, 0x17, 0x18, 0x7b, 0xd1, 0x66, 0x35, 0xda, 0xd4, 0x04, 0x55, 0xfd, 0xc2, 0xc0,
+    0xc1, 0x24, 0xc6, 0x8f, 0x21, 0x56, 0x75, 0xa5, 0xdb, 0xba, 0xcb, 0x5f, 0x08, 0x00, 0x00, 0x00,
+];
+// BPFLoaderUpgradeab1e11111111111111111111111
+pub const BPF_LOADER_ID: Pubkey = [
+    0x02, 0xa8, 0xf6, 0x91, 0x4e, 0x88, 0xa1, 0xb0, 0xe2, 0x10, 0x15, 0x3e, 0xf7, 0x63, 0xae, 0x2b,
+    0x00, 0xc2, 0xb9, 0x3d, 0x16, 0xc1, 0x24, 0xd2, 0xc0, 0x53, 0x7a, 0x10, 0x04, 0x80, 0x00, 0x00,
+];
+// Sysvar1111111111111111111111111111111111111
+pub const SYSVAR_OWNER_ID: Pubkey = [
+    0x06, 0xa7, 0xd5, 0x17, 0x18, 0x75, 0xf7, 0x29, 0xc7, 0x3d, 0x93, 0x40, 0x8f, 0x21, 0x61, 0x20,
+    0x06, 0x7e, 0xd8, 0x8c, 0x76, 0xe0, 0x8c, 0x28, 0x7f, 0xc1, 0x94, 0x60, 0x00, 0x00, 0x00, 0x00,
+];
+// Secp256r1SigVerify1111111111111111111111111
+pub const SECP256R1_PROGRAM_ID: Pubkey = [
+    0x06, 0x92, 0x0d, 0xec, 0x2f, 0xea, 0x71, 0xb5, 0xb7, 0x23, 0x81, 0x4d, 0x74, 0x2d, 0xa9, 0x03,
+    0x1c, 0x83, 0xe7, 0x5f, 0xdb, 0x79, 0x5d, 0x56, 0x8e, 0x75, 0x47, 0x80, 0x20, 0x00, 0x00, 0x00,
+];
+// Ed25519SigVerify111111111111111111111111111
+pub const ED25519_PROGRAM_ID: Pubkey = [
+    0x03, 0x7d, 0x46, 0xd6, 0x7c, 0x93, 0xfb, 0xbe, 0x12, 0xf9, 0x42, 0x8f, 0x83, 0x8d, 0x40, 0xff,
+    0x05, 0x70, 0x74, 0x49, 0x27, 0xf4, 0x8a, 0x64, 0xfc, 0xca, 0x70, 0x44, 0x80, 0x00, 0x00, 0x00,
+];
+
+/// Data lengths of the accounts the programs load: SPL mint, v1 escrow, attested
+/// escrow, v2 escrow, SPL token account.
+const ACCOUNT_DATA_LENS: &[usize] = &[0, 82, 113, 146, 152, TOKEN_ACCOUNT_LEN];
+
+/// A key from a small pool of well-known and user keys, or a random one.
+pub fn key(program_id: Pubkey) -> impl Strategy<Value = Pubkey> {
+    prop_oneof![
+        2 => (1u8..=6).prop_map(|b| [b; 32]),
+        1 => Just(program_id),
+        1 => Just(SYSTEM_PROGRAM_ID),
+        1 => Just(TOKEN_PROGRAM_ID),
+        1 => Just(ASSOCIATED_TOKEN_PROGRAM_ID),
+        1 => any::<Pubkey>(),
+    ]
+}
+
+/// Lamport balances: empty, small, or anything up to `u64::MAX`.
+pub fn lamports() -> impl Strategy<Value = u64> {
+    prop_oneof![Just(0u64), 1u64..10_000_000_000, any::<u64>()]
+}
+
+/// Account data of an interesting length, or of any length up to 512 bytes.
+pub fn account_data() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        proptest::sample::select(ACCOUNT_DATA_LENS).prop_flat_map(|len| vec(any::<u8>(), len)),
+        vec(any::<u8>(), 0..512),
+    ]
+}
+
+/// An initialized SPL token account for a mint from the key pool.
+pub fn token_account(program_id: Pubkey) -> impl Strategy<Value = Account> {
+    (
+        key(program_id),
+        key(program_id),
+        key(program_id),
+        any::<u64>(),
+        lamports(),
+        any::<bool>(),
+    )
+        .prop_map(
+            |(address, mint, authority, amount, lamports, is_writable)| {
+                let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+                data[0..32].copy_from_slice(&mint);
+                data[32..64].copy_from_slice(&authority);
+                data[64..72].copy_from_slice(&amount.to_le_bytes());
+                data[108] = 1;
+                Account {
+                    key: address,
+                    owner: TOKEN_PROGRAM_ID,
+                    lamports,
+                    data,
+                    is_signer: false,
+                    is_writable,
+                    executable: false,
+                }
+            },
+        )
+}
+
+/// A v1 or current escrow account (`Escrow`/`EscrowV1` layout) owned by
+/// `program_id`, whose maker and mints come from the key pool.
+pub fn escrow_state(program_id: Pubkey) -> impl Strategy<Value = Account> {
+    (
+        (
+            key(program_id),
+            key(program_id),
+            key(program_id),
+            key(program_id),
+            key(program_id),
+        ),
+        (any::<u64>(), 1u64..1_000_000, any::<u8>(), any::<bool>()),
+        lamports(),
+        any::<bool>(),
+    )
+        .prop_map(
+            move |(
+                (address, maker, mint_a, mint_b, rent_recipient),
+                (seed, receive, bump, v1),
+                lamports,
+                is_writable,
+            )| {
+                let mut data = Vec::with_capacity(152);
+                data.extend_from_slice(&seed.to_le_bytes());
+                data.extend_from_slice(&maker);
+                data.extend_from_slice(&mint_a);
+                data.extend_from_slice(&mint_b);
+                data.extend_from_slice(&receive.to_le_bytes());
+                data.push(bump);
+                if !v1 {
+                    data.push(2);
+                    data.extend_from_slice(&rent_recipient);
+                    data.extend_from_slice(&[0; 6]);
+                }
+                Account {
+                    key: address,
+                    owner: program_id,
+                    lamports,
+                    data,
+                    is_signer: false,
+                    is_writable,
+                    executable: false,
+                }
+            },
+        )
+}
+
+/// Precompile instruction data: one signature whose offsets all point somewhere
+/// in (or just past) the instruction's own data, or random bytes.
+pub fn precompile_data() -> impl Strategy<Value = Vec<u8>> {
+    let structured = (vec(any::<u8>(), 0..256), vec(0u16..300, 4), any::<bool>()).prop_map(
+        |(payload, offsets, same_instruction)| {
+            let index = if same_instruction { u16::MAX } else { 0 };
+            let mut data = vec![1u8, 0];
+            for value in [
+                offsets[0], index, offsets[1], index, offsets[2], offsets[3], index,
+            ] {
+                data.extend_from_slice(&value.to_le_bytes());
+            }
+            data.extend_from_slice(&payload);
+            data
+        },
+    );
+    prop_oneof![3 => structured, 1 => vec(any::<u8>(), 0..128)]
+}
+
+/// An arbitrary instruction of the transaction, biased towards signature
+/// precompiles and the program itself.
+fn introspected_instruction(program_id: Pubkey) -> impl Strategy<Value = IntrospectedInstruction> {
+    (
+        prop_oneof![
+            Just(SECP256R1_PROGRAM_ID),
+            Just(ED25519_PROGRAM_ID),
+            Just(program_id),
+            any::<Pubkey>(),
+        ],
+        vec((0u8..4, key(program_id)), 0..4),
+        precompile_data(),
+    )
+}
+
+/// One instruction as recorded in the instructions sysvar: program id, account
+/// metas (flags, key) and data.
+pub type IntrospectedInstruction = (Pubkey, Vec<(u8, Pubkey)>, Vec<u8>);
+
+/// The instructions sysvar account for `instructions`, currently executing the
+/// one at index `current`, serialized exactly as the runtime writes it.
+pub fn instructions_sysvar_account(
+    instructions: &[IntrospectedInstruction],
+    current: u16,
+) -> Account {
+    let mut data = Vec::new();
+    data.extend_from_slice(&(instructions.len() as u16).to_le_bytes());
+    let offsets_at = data.len();
+    data.resize(offsets_at + 2 * instructions.len(), 0);
+    for (i, (program, metas, ix_data)) in instructions.iter().enumerate() {
+        let offset = data.len() as u16;
+        data[offsets_at + 2 * i..offsets_at + 2 * i + 2].copy_from_slice(&offset.to_le_bytes());
+        data.extend_from_slice(&(metas.len() as u16).to_le_bytes());
+        for (flags, meta_key) in metas {
+            data.push(*flags);
+            data.extend_from_slice(meta_key);
+        }
+        data.extend_from_slice(program);
+        data.extend_from_slice(&(ix_data.len() as u16).to_le_bytes());
+        data.extend_from_slice(ix_data);
+    }
+    data.extend_from_slice(&current.to_le_bytes());
+    Account {
+        key: INSTRUCTIONS_SYSVAR_ID,
+        owner: SYSVAR_OWNER_ID,
+        lamports: 1_000_000,
+        data,
+        is_signer: false,
+        is_writable: false,
+        executable: false,
+    }
+}
+
+/// The instructions sysvar for a transaction of 1..=4 instructions.
+pub fn instructions_sysvar(program_id: Pubkey) -> impl Strategy<Value = Account> {
+    vec(introspected_instruction(program_id), 1..=4)
+        .prop_flat_map(|instructions| {
+            let count = instructions.len();
+            (Just(instructions), 0..count)
+        })
+        .prop_map(|(instructions, current)| {
+            instructions_sysvar_account(&instructions, current as u16)
+        })
+}
+
+/// Any account: a generic one, a token account, an escrow or the instructions
+/// sysvar.
+pub fn account(program_id: Pubkey) -> impl Strategy<Value = Account> {
+    let generic = (
+        key(program_id),
+        prop_oneof![
+            Just(program_id),
+            Just(SYSTEM_PROGRAM_ID),
+            Just(TOKEN_PROGRAM_ID),
+            any::<Pubkey>(),
+        ],
+        lamports(),
+        account_data(),
+        any::<bool>(),
+        any::<bool>(),
+        prop::bool::weighted(0.05),
+    )
+        .prop_map(
+            |(key, owner, lamports, data, is_signer, is_writable, executable)| Account {
+                key,
+                owner,
+                lamports,
+                data,
+                is_signer,
+                is_writable,
+                executable,
+            },
+        );
+    prop_oneof![
+        6 => generic,
+        2 => token_account(program_id),
+        1 => escrow_state(program_id),
+        1 => instructions_sysvar(program_id),
+    ]
+}
+
+/// What an instruction expects at one account position.
+#[derive(Clone, Copy, Debug)]
+pub enum Slot {
+    Any,
+    /// A system-owned wallet that signs.
+    Signer,
+    /// An executable program account.
+    Program(Pubkey),
+    /// An escrow state account.
+    Escrow,
+    /// An SPL token account.
+    Token,
+    /// The instructions sysvar.
+    Instructions,
+}
+
+/// An instruction a program decodes: its discriminator, the length of the data
+/// after the discriminator, and what it expects at each account position.
+#[derive(Clone, Copy, Debug)]
+pub struct InstructionShape {
+    pub discriminator: u8,
+    pub data_len: usize,
+    pub slots: &'static [Slot],
+}
+
+/// An account matching `slot`.
+pub fn slot_account(program_id: Pubkey, slot: Slot) -> BoxedStrategy<Account> {
+    match slot {
+        Slot::Any => account(program_id).boxed(),
+        Slot::Signer => (key(program_id), lamports())
+            .prop_map(|(key, lamports)| Account {
+                key,
+                owner: SYSTEM_PROGRAM_ID,
+                lamports,
+                data: Vec::new(),
+                is_signer: true,
+                is_writable: true,
+                executable: false,
+            })
+            .boxed(),
+        Slot::Program(id) => Just(Account {
+            key: id,
+            owner: BPF_LOADER_ID,
+            lamports: 1,
+            data: Vec::new(),
+            is_signer: false,
+            is_writable: false,
+            executable: true,
+        })
+        .boxed(),
+        Slot::Escrow => escrow_state(program_id).boxed(),
+        Slot::Token => token_account(program_id).boxed(),
+        Slot::Instructions => instructions_sysvar(program_id).boxed(),
+    }
+}
+
+/// Instruction data: a known discriminator with its expected length, or any
+/// short byte string.
+fn shaped_data(shape: InstructionShape) -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        4 => vec(any::<u8>(), shape.data_len).prop_map(move |tail| [vec![shape.discriminator], tail].concat()),
+        1 => vec(any::<u8>(), 0..128).prop_map(move |tail| [vec![shape.discriminator], tail].concat()),
+        1 => vec(any::<u8>(), 0..64),
+    ]
+}
+
+/// Accounts for `shape`: usually one per slot, each matching its slot or,
+/// sometimes, arbitrary; otherwise any number of arbitrary accounts.
+fn shaped_accounts(
+    program_id: Pubkey,
+    shape: InstructionShape,
+    max_accounts: usize,
+) -> BoxedStrategy<Vec<Account>> {
+    let hinted: Vec<BoxedStrategy<Account>> = shape
+        .slots
+        .iter()
+        .map(|slot| {
+            prop_oneof![5 => slot_account(program_id, *slot), 1 => account(program_id)].boxed()
+        })
+        .collect();
+    prop_oneof![
+        4 => hinted,
+        1 => vec(account(program_id), 0..=max_accounts),
+    ]
+    .boxed()
+}
+
+/// An arbitrary invocation of `program`. Most follow one of `shapes`, so they
+/// get past the first checks; the rest have any accounts and data.
+///
+/// Accounts sharing a key are folded into duplicates of the first, and explicit
+/// duplicates always point at an earlier non-duplicate, as the runtime ensures.
+pub fn invocation(
+    program: Program,
+    shapes: &'static [InstructionShape],
+) -> impl Strategy<Value = Invocation> {
+    let program_id = program.id();
+    let max_accounts = shapes
+        .iter()
+        .map(|shape| shape.slots.len())
+        .max()
+        .unwrap_or(0)
+        + 2;
+    proptest::sample::select(shapes)
+        .prop_flat_map(move |shape| {
+            (
+                shaped_accounts(program_id, shape, max_accounts),
+                vec(prop::option::weighted(0.1, any::<u8>()), max_accounts),
+                shaped_data(shape),
+            )
+        })
+        .prop_map(move |(entries, duplicates, data)| {
+            let mut accounts: Vec<AccountInput> = Vec::with_capacity(entries.len());
+            for (i, (account, duplicate)) in entries.into_iter().zip(duplicates).enumerate() {
+                let first_with_key = accounts.iter().position(|input| {
+                    matches!(input, AccountInput::Account(existing) if existing.key == account.key)
+                });
+                let input = match (first_with_key, duplicate) {
+                    (Some(original), _) => AccountInput::Duplicate(original as u8),
+                    (None, Some(target)) if i > 0 => match &accounts[target as usize % i] {
+                        AccountInput::Duplicate(original) => AccountInput::Duplicate(*original),
+                        AccountInput::Account(_) => {
+                            AccountInput::Duplicate((target as usize % i) as u8)
+                        }
+                    },
+                    _ => AccountInput::Account(account),
+                };
+                accounts.push(input);
+            }
+            Invocation {
+                program,
+                accounts,
+                data,
+            }
+        })
+}
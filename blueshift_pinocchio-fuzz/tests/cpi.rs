@@ -0,0 +1,260 @@
+//! The escrow's `cpi` helpers, driven through the SBF build of the `cpi_taker`
+//! test program. Its calls into the escrow, and the escrow's own calls into the
+//! token programs, run as real CPIs under the SVM.
+
+use blueshift_pinocchio_escrow::{Escrow, EscrowQuote};
+use blueshift_pinocchio_fuzz::fixtures::{
+    associated_token_address, mint, pda, program, rent_exempt, signer, token_account, token_amount,
+    wallet, AccountData, TOKEN_ACCOUNT_LAMPORTS,
+};
+use blueshift_pinocchio_fuzz::programs::{Program, CPI_TAKER_ID, ESCROW_ID};
+use blueshift_pinocchio_fuzz::strategy::ASSOCIATED_TOKEN_PROGRAM_ID;
+use blueshift_pinocchio_fuzz::{
+    run, Account, AccountInput, Invocation, Outcome, Pubkey, SYSTEM_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use pinocchio::program_error::ProgramError;
+
+const TAKER: Pubkey = [1; 32];
+const MAKER: Pubkey = [2; 32];
+const MINT_A: Pubkey = [3; 32];
+const MINT_B: Pubkey = [4; 32];
+const RENT_RECIPIENT: Pubkey = [5; 32];
+const SEED: u64 = 7;
+
+/// `MAKER`'s escrow PDA for `SEED`, and its bump.
+fn escrow_address() -> (Pubkey, u8) {
+    pda(&[b"escrow", &MAKER, &SEED.to_le_bytes()], &ESCROW_ID)
+}
+
+/// The escrow's vault: its associated token account for token A.
+fn vault_address() -> Pubkey {
+    associated_token_address(&escrow_address().0, &MINT_A, &TOKEN_PROGRAM_ID)
+}
+
+fn escrow(receive: u64) -> Account {
+    let (address, bump) = escrow_address();
+    let mut data = AccountData::<{ Escrow::LEN }>::zeroed();
+    Escrow::load_mut(&mut data.0).unwrap().set_inner(
+        SEED,
+        MAKER,
+        MINT_A,
+        MINT_B,
+        receive,
+        [bump],
+        RENT_RECIPIENT,
+    );
+    Account {
+        owner: ESCROW_ID,
+        data: data.0.to_vec(),
+        ..wallet(address, rent_exempt(Escrow::LEN))
+    }
+}
+
+fn vault() -> Account {
+    token_account(
+        vault_address(),
+        TOKEN_PROGRAM_ID,
+        MINT_A,
+        escrow_address().0,
+        1_000,
+    )
+}
+
+/// `cpi_taker` call number `call` with `data`, over `accounts` and the escrow program.
+fn call(call: u8, data: &[u8], accounts: Vec<Account>) -> Invocation {
+    Invocation {
+        program: Program::CpiTaker,
+        accounts: accounts
+            .into_iter()
+            .chain([program(ESCROW_ID)])
+            .map(AccountInput::Account)
+            .collect(),
+        data: [&[call], data].concat(),
+    }
+}
+
+fn make(token_program: Pubkey, receive: u64) -> Invocation {
+    let data = [SEED, receive, 1_000].map(u64::to_le_bytes).concat();
+    call(
+        0,
+        &data,
+        vec![
+            signer(MAKER, 1_000_000_000),
+            wallet(escrow_address().0, 0),
+            mint(MINT_A, TOKEN_PROGRAM_ID),
+            mint(MINT_B, TOKEN_PROGRAM_ID),
+            token_account([10; 32], TOKEN_PROGRAM_ID, MINT_A, MAKER, 1_000),
+            wallet(vault_address(), 0),
+            program(token_program),
+            program(ASSOCIATED_TOKEN_PROGRAM_ID),
+            program(SYSTEM_PROGRAM_ID),
+        ],
+    )
+}
+
+fn take(escrow: Account, expected_receive: u64, min_amount_a: u64) -> Invocation {
+    let data = [expected_receive, min_amount_a]
+        .map(u64::to_le_bytes)
+        .concat();
+    call(
+        1,
+        &data,
+        vec![
+            signer(TAKER, 1_000_000_000),
+            wallet(MAKER, 1_000_000_000),
+            escrow,
+            mint(MINT_A, TOKEN_PROGRAM_ID),
+            mint(MINT_B, TOKEN_PROGRAM_ID),
+            vault(),
+            token_account([12; 32], TOKEN_PROGRAM_ID, MINT_A, TAKER, 0),
+            token_account([13; 32], TOKEN_PROGRAM_ID, MINT_B, TAKER, 500),
+            token_account([14; 32], TOKEN_PROGRAM_ID, MINT_B, MAKER, 0),
+            program(SYSTEM_PROGRAM_ID),
+            program(TOKEN_PROGRAM_ID),
+            program(ASSOCIATED_TOKEN_PROGRAM_ID),
+            wallet(RENT_RECIPIENT, 0),
+        ],
+    )
+}
+
+fn refund() -> Invocation {
+    call(
+        2,
+        &[],
+        vec![
+            signer(MAKER, 1_000_000_000),
+            escrow(400),
+            mint(MINT_A, TOKEN_PROGRAM_ID),
+            vault(),
+            token_account([10; 32], TOKEN_PROGRAM_ID, MINT_A, MAKER, 0),
+            program(SYSTEM_PROGRAM_ID),
+            program(TOKEN_PROGRAM_ID),
+            wallet(RENT_RECIPIENT, 0),
+        ],
+    )
+}
+
+fn sweep(token_account_owner: Pubkey) -> Invocation {
+    call(
+        3,
+        &SEED.to_le_bytes(),
+        vec![
+            signer(MAKER, 1_000_000_000),
+            wallet(escrow_address().0, 0),
+            mint(MINT_B, TOKEN_PROGRAM_ID),
+            token_account([15; 32], TOKEN_PROGRAM_ID, MINT_B, token_account_owner, 50),
+            token_account([14; 32], TOKEN_PROGRAM_ID, MINT_B, MAKER, 0),
+            program(TOKEN_PROGRAM_ID),
+        ],
+    )
+}
+
+fn quote(vault: Account) -> Invocation {
+    call(4, &[], vec![escrow(400), vault])
+}
+
+fn assert_fails(invocation: &Invocation, expected: ProgramError) {
+    match run(invocation) {
+        Ok(Outcome::Error(code)) => assert_eq!(code, u64::from(expected)),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+/// The account `key` as left by a successful call.
+fn after(invocation: &Invocation, key: Pubkey) -> Account {
+    match run(invocation) {
+        Ok(Outcome::Success { accounts, .. }) => {
+            accounts.into_iter().find(|a| a.key == key).unwrap()
+        }
+        other => panic!("expected success, got {other:?}"),
+    }
+}
+
+#[test]
+fn the_sbf_build_is_the_cpi_taker() {
+    assert_eq!(CPI_TAKER_ID, cpi_taker::ID);
+}
+
+#[test]
+fn make_opens_the_escrow() {
+    let invocation = make(TOKEN_PROGRAM_ID, 400);
+    assert_eq!(after(&invocation, escrow_address().0).owner, ESCROW_ID);
+    assert_eq!(token_amount(&after(&invocation, vault_address())), 1_000);
+    assert_eq!(token_amount(&after(&invocation, [10; 32])), 0);
+
+    assert_fails(&make([9; 32], 400), ProgramError::IncorrectProgramId);
+}
+
+#[test]
+fn make_checks_its_data_before_calling() {
+    assert_fails(
+        &make(TOKEN_PROGRAM_ID, 0),
+        ProgramError::InvalidInstructionData,
+    );
+}
+
+#[test]
+fn take_settles_the_escrow() {
+    let invocation = take(escrow(400), 400, 1_000);
+    assert_eq!(after(&invocation, escrow_address().0).lamports, 0);
+    assert_eq!(
+        after(&invocation, RENT_RECIPIENT).lamports,
+        rent_exempt(Escrow::LEN) + TOKEN_ACCOUNT_LAMPORTS
+    );
+    assert_eq!(token_amount(&after(&invocation, [12; 32])), 1_000);
+    assert_eq!(token_amount(&after(&invocation, [14; 32])), 400);
+
+    let without_terms = take(escrow(400), 0, 0);
+    assert_eq!(after(&without_terms, escrow_address().0).lamports, 0);
+}
+
+#[test]
+fn take_passes_the_takers_terms() {
+    assert_fails(&take(escrow(400), 401, 0), ProgramError::InvalidAccountData);
+    assert_fails(
+        &take(escrow(400), 0, 1_001),
+        ProgramError::InsufficientFunds,
+    );
+}
+
+#[test]
+fn take_rejects_another_escrow() {
+    let mut foreign = escrow(400);
+    foreign.owner = [9; 32];
+    assert_fails(&take(foreign, 400, 0), ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn refund_closes_the_escrow() {
+    let invocation = refund();
+    assert_eq!(after(&invocation, escrow_address().0).lamports, 0);
+    assert_eq!(
+        after(&invocation, RENT_RECIPIENT).lamports,
+        rent_exempt(Escrow::LEN) + TOKEN_ACCOUNT_LAMPORTS
+    );
+    assert_eq!(token_amount(&after(&invocation, [10; 32])), 1_000);
+}
+
+#[test]
+fn sweep_recovers_the_stray_account() {
+    let invocation = sweep(escrow_address().0);
+    assert_eq!(token_amount(&after(&invocation, [14; 32])), 50);
+    assert_eq!(after(&invocation, [15; 32]).lamports, 0);
+
+    assert_fails(&sweep(TAKER), ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn quote_returns_the_escrows_terms() {
+    match run(&quote(vault())) {
+        Ok(Outcome::Success { return_data, .. }) => {
+            let quote = EscrowQuote::from_bytes(&return_data).unwrap();
+            assert_eq!((quote.vault_amount, quote.receive), (1_000, 400));
+        }
+        other => panic!("expected a quote, got {other:?}"),
+    }
+    assert_fails(
+        &quote(wallet(vault_address(), 0)),
+        ProgramError::InvalidAccountOwner,
+    );
+}
@@ -0,0 +1,74 @@
+//! Each program's SBF build under the SVM against arbitrary account sets and
+//! instruction data. A run fails if the program aborts, and if a successful
+//! instruction, with every CPI it made, leaves more lamports or tokens than it
+//! was passed.
+
+use blueshift_pinocchio_fuzz::fixtures::{invocation, signer, token_account, wallet};
+use blueshift_pinocchio_fuzz::programs::{
+    Program, ESCROW_SHAPES, PROGRAMS, SECP256R1_VAULT_SHAPES, VAULT_SHAPES,
+};
+use blueshift_pinocchio_fuzz::strategy::invocation as arbitrary_invocation;
+use blueshift_pinocchio_fuzz::{check_conservation, run, Outcome, TOKEN_PROGRAM_ID};
+use proptest::prelude::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(2048))]
+
+    #[test]
+    fn escrow_entrypoint(invocation in arbitrary_invocation(Program::Escrow, ESCROW_SHAPES)) {
+        run(&invocation).map_err(TestCaseError::fail)?;
+    }
+
+    #[test]
+    fn vault_entrypoint(invocation in arbitrary_invocation(Program::Vault, VAULT_SHAPES)) {
+        run(&invocation).map_err(TestCaseError::fail)?;
+    }
+
+    #[test]
+    fn secp256r1_vault_entrypoint(invocation in arbitrary_invocation(Program::Secp256r1Vault, SECP256R1_VAULT_SHAPES)) {
+        run(&invocation).map_err(TestCaseError::fail)?;
+    }
+}
+
+#[test]
+fn each_svm_loads_programs_at_distinct_ids() {
+    for program in PROGRAMS {
+        let loaded = program.sbf_programs();
+        for (i, sbf) in loaded.iter().enumerate() {
+            assert!(
+                loaded[i + 1..].iter().all(|other| other.id != sbf.id),
+                "{program:?}: {} shares its ID",
+                sbf.name
+            );
+        }
+        assert_eq!(loaded[0].id, program.id());
+    }
+}
+
+#[test]
+fn unknown_discriminator_is_an_error() {
+    let invocation = invocation(Program::Escrow, 0xff, &[], vec![signer([1; 32], 10)]);
+    // ProgramError::InvalidInstructionData
+    assert!(matches!(run(&invocation), Ok(Outcome::Error(code)) if code == 3 << 32));
+}
+
+#[test]
+fn conservation_rejects_minted_lamports() {
+    let before = vec![wallet([1; 32], 100), wallet([2; 32], 0)];
+    let after = vec![wallet([1; 32], 100), wallet([2; 32], 1)];
+    assert!(check_conservation(&before, &after).is_err());
+
+    let after = vec![wallet([1; 32], 40), wallet([2; 32], 60)];
+    assert!(check_conservation(&before, &after).is_ok());
+}
+
+#[test]
+fn conservation_rejects_minted_tokens() {
+    let account =
+        |key: u8, amount| token_account([key; 32], TOKEN_PROGRAM_ID, [9; 32], [1; 32], amount);
+    let before = vec![account(1, 100), account(2, 0)];
+    assert!(check_conservation(&before, &[account(1, 100), account(2, 1)]).is_err());
+    assert!(check_conservation(&before, &[account(1, 40), account(2, 60)]).is_ok());
+    // Burning, or closing an empty account, is fine.
+    assert!(check_conservation(&before, &[account(1, 90), account(2, 0)]).is_ok());
+}
@@ -0,0 +1,617 @@
+//! Scenario runs of escrow instructions under the SVM. Each case sets up the
+//! accounts for one path at the addresses the escrow derives, and checks the
+//! outcome and the accounts the instruction leaves.
+
+use blueshift_pinocchio_escrow::{Escrow, EscrowQuote, EscrowV1, QUOTE_PRICE_SCALE};
+use blueshift_pinocchio_fuzz::fixtures::{
+    associated_token_address, invocation, mint, pda, program, rent_exempt, signer, token_account,
+    token_amount, wallet, AccountData, TOKEN_ACCOUNT_LAMPORTS,
+};
+use blueshift_pinocchio_fuzz::programs::{Program, ESCROW_ID};
+use blueshift_pinocchio_fuzz::strategy::{
+    instructions_sysvar_account, ASSOCIATED_TOKEN_PROGRAM_ID, ED25519_PROGRAM_ID,
+};
+use blueshift_pinocchio_fuzz::{
+    run, run_at, Account, AccountInput, Invocation, Outcome, Pubkey, NOW, SYSTEM_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use pinocchio::program_error::ProgramError;
+
+const TAKER: Pubkey = [1; 32];
+const MAKER: Pubkey = [2; 32];
+const MINT_A: Pubkey = [3; 32];
+const MINT_B: Pubkey = [4; 32];
+const RENT_RECIPIENT: Pubkey = [5; 32];
+const NONCE: u64 = 5;
+
+/// Run `invocation` and expect it to fail with `expected`.
+fn assert_fails(invocation: &Invocation, expected: ProgramError) {
+    match run(invocation) {
+        Ok(Outcome::Error(code)) => assert_eq!(code, u64::from(expected)),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+/// Run `invocation`, expect it to succeed and return the accounts it left.
+fn succeed(invocation: &Invocation) -> Vec<Account> {
+    match run(invocation) {
+        Ok(Outcome::Success { accounts, .. }) => accounts,
+        other => panic!("expected success, got {other:?}"),
+    }
+}
+
+/// The account at `key` among `accounts`.
+fn find(accounts: &[Account], key: Pubkey) -> &Account {
+    accounts.iter().find(|account| account.key == key).unwrap()
+}
+
+/// The receipt PDA of `MAKER`'s signed order `nonce`.
+fn receipt_address(nonce: u64) -> Pubkey {
+    pda(&[b"order", &MAKER, &nonce.to_le_bytes()], &ESCROW_ID).0
+}
+
+/// An order receipt that was already created by a fill or cancel.
+fn used_receipt() -> Account {
+    Account {
+        owner: ESCROW_ID,
+        ..wallet(receipt_address(NONCE), rent_exempt(0))
+    }
+}
+
+fn cancel_order(maker: Account, receipt: Account) -> Invocation {
+    invocation(
+        Program::Escrow,
+        7,
+        &NONCE.to_le_bytes(),
+        vec![maker, receipt, program(SYSTEM_PROGRAM_ID)],
+    )
+}
+
+#[test]
+fn cancel_order_requires_the_maker_signature() {
+    let invocation = cancel_order(
+        wallet(MAKER, 1_000_000_000),
+        wallet(receipt_address(NONCE), 0),
+    );
+    assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn cancel_order_creates_the_receipt() {
+    // A receipt address that was sent lamports beforehand is topped up instead.
+    for lamports in [0, 1_000] {
+        let invocation = cancel_order(
+            signer(MAKER, 1_000_000_000),
+            wallet(receipt_address(NONCE), lamports),
+        );
+        let accounts = succeed(&invocation);
+        let receipt = find(&accounts, receipt_address(NONCE));
+        assert_eq!(receipt.owner, ESCROW_ID);
+        assert_eq!(receipt.lamports, rent_exempt(0));
+    }
+
+    let elsewhere = cancel_order(
+        signer(MAKER, 1_000_000_000),
+        wallet(receipt_address(NONCE + 1), 0),
+    );
+    assert_fails(&elsewhere, ProgramError::InvalidSeeds);
+}
+
+#[test]
+fn cancel_order_rejects_a_used_nonce() {
+    let invocation = cancel_order(signer(MAKER, 1_000_000_000), used_receipt());
+    assert_fails(&invocation, ProgramError::AccountAlreadyInitialized);
+}
+
+/// An ed25519 verify instruction of `signer` signing `message`.
+fn ed25519_verify(signer: &Pubkey, message: &[u8]) -> Vec<u8> {
+    let (signature_offset, public_key_offset, message_offset) = (16u16, 80u16, 112u16);
+    let mut data = vec![1, 0];
+    for field in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(&[0; 64]);
+    data.extend_from_slice(signer);
+    data.extend_from_slice(message);
+    data
+}
+
+/// `MAKER`'s order of 1_000 token A for 250 token B, valid until `expiry`.
+fn order_message(expiry: i64) -> Vec<u8> {
+    let mut message = Vec::new();
+    for key in [ESCROW_ID, MAKER, MINT_A, MINT_B] {
+        message.extend_from_slice(&key);
+    }
+    for value in [1_000u64, 250, NONCE, expiry as u64] {
+        message.extend_from_slice(&value.to_le_bytes());
+    }
+    message
+}
+
+fn delegate_address() -> Pubkey {
+    pda(&[b"delegate", &MAKER], &ESCROW_ID).0
+}
+
+/// The maker's 1_000 token A, with the maker's delegate PDA approved for all of it.
+fn approved_maker_ata_a() -> Account {
+    let mut account = token_account([10; 32], TOKEN_PROGRAM_ID, MINT_A, MAKER, 1_000);
+    account.data[72..76].copy_from_slice(&1u32.to_le_bytes());
+    account.data[76..108].copy_from_slice(&delegate_address());
+    account.data[121..129].copy_from_slice(&1_000u64.to_le_bytes());
+    account
+}
+
+fn fill_order(taker: Account, order_signer: &Pubkey, receipt: Account, expiry: i64) -> Invocation {
+    let sysvar = instructions_sysvar_account(
+        &[
+            (ESCROW_ID, Vec::new(), vec![6]),
+            (
+                ED25519_PROGRAM_ID,
+                Vec::new(),
+                ed25519_verify(order_signer, &order_message(expiry)),
+            ),
+        ],
+        0,
+    );
+    invocation(
+        Program::Escrow,
+        6,
+        &[],
+        vec![
+            taker,
+            wallet(MAKER, 1_000_000_000),
+            wallet(delegate_address(), 0),
+            receipt,
+            mint(MINT_A, TOKEN_PROGRAM_ID),
+            mint(MINT_B, TOKEN_PROGRAM_ID),
+            approved_maker_ata_a(),
+            token_account([11; 32], TOKEN_PROGRAM_ID, MINT_A, TAKER, 0),
+            token_account([12; 32], TOKEN_PROGRAM_ID, MINT_B, TAKER, 1_000),
+            token_account([13; 32], TOKEN_PROGRAM_ID, MINT_B, MAKER, 0),
+            sysvar,
+            program(SYSTEM_PROGRAM_ID),
+            program(TOKEN_PROGRAM_ID),
+        ],
+    )
+}
+
+#[test]
+fn fill_order_settles_the_makers_signed_order() {
+    let invocation = fill_order(
+        signer(TAKER, 1_000_000_000),
+        &MAKER,
+        wallet(receipt_address(NONCE), 0),
+        i64::MAX,
+    );
+    let accounts = succeed(&invocation);
+    assert_eq!(token_amount(find(&accounts, [10; 32])), 0);
+    assert_eq!(token_amount(find(&accounts, [11; 32])), 1_000);
+    assert_eq!(token_amount(find(&accounts, [12; 32])), 750);
+    assert_eq!(token_amount(find(&accounts, [13; 32])), 250);
+    assert_eq!(find(&accounts, receipt_address(NONCE)).owner, ESCROW_ID);
+}
+
+#[test]
+fn fill_order_requires_the_taker_and_maker_signatures() {
+    let receipt = || wallet(receipt_address(NONCE), 0);
+    let invocation = fill_order(wallet(TAKER, 1_000_000_000), &MAKER, receipt(), i64::MAX);
+    assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+
+    let invocation = fill_order(signer(TAKER, 1_000_000_000), &TAKER, receipt(), i64::MAX);
+    assert_fails(&invocation, ProgramError::MissingRequiredSignature);
+}
+
+#[test]
+fn fill_order_rejects_a_filled_or_cancelled_order() {
+    let invocation = fill_order(
+        signer(TAKER, 1_000_000_000),
+        &MAKER,
+        used_receipt(),
+        i64::MAX,
+    );
+    assert_fails(&invocation, ProgramError::AccountAlreadyInitialized);
+}
+
+#[test]
+fn fill_order_expires() {
+    let invocation = fill_order(
+        signer(TAKER, 1_000_000_000),
+        &MAKER,
+        wallet(receipt_address(NONCE), 0),
+        NOW,
+    );
+    assert!(matches!(
+        run_at(&invocation, NOW),
+        Ok(Outcome::Success { .. })
+    ));
+    match run_at(&invocation, NOW + 1) {
+        Ok(Outcome::Error(code)) => {
+            assert_eq!(code, u64::from(ProgramError::InvalidInstructionData))
+        }
+        other => panic!("expected the order to have expired, got {other:?}"),
+    }
+}
+
+/// `MAKER`'s escrow PDA for `seed`, and its bump.
+fn escrow_address(seed: u64) -> (Pubkey, u8) {
+    pda(&[b"escrow", &MAKER, &seed.to_le_bytes()], &ESCROW_ID)
+}
+
+/// `MAKER`'s escrow `seed` of token A for `receive` token B.
+fn escrow(seed: u64, receive: u64) -> Account {
+    let (address, bump) = escrow_address(seed);
+    let mut data = AccountData::<{ Escrow::LEN }>::zeroed();
+    Escrow::load_mut(&mut data.0).unwrap().set_inner(
+        seed,
+        MAKER,
+        MINT_A,
+        MINT_B,
+        receive,
+        [bump],
+        RENT_RECIPIENT,
+    );
+    Account {
+        owner: ESCROW_ID,
+        data: data.0.to_vec(),
+        ..wallet(address, rent_exempt(Escrow::LEN))
+    }
+}
+
+/// The vault of escrow `seed`, holding 1_000 token A.
+fn vault(seed: u64) -> Account {
+    let escrow = escrow_address(seed).0;
+    token_account(
+        associated_token_address(&escrow, &MINT_A, &TOKEN_PROGRAM_ID),
+        TOKEN_PROGRAM_ID,
+        MINT_A,
+        escrow,
+        1_000,
+    )
+}
+
+/// A `TakeMany` escrow group: maker, escrow, vault, maker_ata_b, rent_recipient.
+struct Group {
+    escrow: Account,
+    vault: Account,
+    maker_ata_b: Account,
+}
+
+fn group(seed: u8, receive: u64) -> Group {
+    Group {
+        escrow: escrow(seed as u64, receive),
+        vault: vault(seed as u64),
+        maker_ata_b: token_account([seed + 2; 32], TOKEN_PROGRAM_ID, MINT_B, MAKER, 0),
+    }
+}
+
+fn take_many(taker_balance_b: u64, groups: &[Group], skip_unfillable: bool) -> Invocation {
+    let mut accounts: Vec<AccountInput> = [
+        signer(TAKER, 1_000_000_000),
+        mint(MINT_A, TOKEN_PROGRAM_ID),
+        mint(MINT_B, TOKEN_PROGRAM_ID),
+        token_account([10; 32], TOKEN_PROGRAM_ID, MINT_A, TAKER, 0),
+        token_account([11; 32], TOKEN_PROGRAM_ID, MINT_B, TAKER, taker_balance_b),
+        program(SYSTEM_PROGRAM_ID),
+        program(TOKEN_PROGRAM_ID),
+        program(ASSOCIATED_TOKEN_PROGRAM_ID),
+    ]
+    .into_iter()
+    .map(AccountInput::Account)
+    .collect();
+
+    // Accounts shared between groups are passed once and repeated after that.
+    let (maker, rent_recipient) = (
+        wallet(MAKER, 1_000_000_000),
+        wallet(RENT_RECIPIENT, 1_000_000_000),
+    );
+    let mut seen: Vec<(Pubkey, u8)> = Vec::new();
+    for group in groups {
+        for account in [
+            &maker,
+            &group.escrow,
+            &group.vault,
+            &group.maker_ata_b,
+            &rent_recipient,
+        ] {
+            match seen.iter().find(|(key, _)| *key == account.key) {
+                Some((_, index)) => accounts.push(AccountInput::Duplicate(*index)),
+                None => {
+                    seen.push((account.key, accounts.len() as u8));
+                    accounts.push(AccountInput::Account(account.clone()));
+                }
+            }
+        }
+    }
+
+    Invocation {
+        program: Program::Escrow,
+        accounts,
+        data: vec![8, skip_unfillable as u8],
+    }
+}
+
+/// Run `invocation` and expect it to succeed without changing any account.
+fn assert_settles_nothing(invocation: &Invocation) {
+    let unchanged = |accounts: Vec<Account>| -> Vec<Account> {
+        accounts.into_iter().filter(|a| !a.executable).collect()
+    };
+    assert_eq!(
+        unchanged(succeed(invocation)),
+        unchanged(invocation.unique_accounts())
+    );
+}
+
+#[test]
+fn take_many_settles_fillable_escrows() {
+    let invocation = take_many(1_000, &[group(20, 400), group(30, 600)], false);
+    let accounts = succeed(&invocation);
+
+    assert_eq!(token_amount(find(&accounts, [10; 32])), 2_000);
+    assert_eq!(token_amount(find(&accounts, [11; 32])), 0);
+    assert_eq!(token_amount(find(&accounts, [22; 32])), 400);
+    assert_eq!(token_amount(find(&accounts, [32; 32])), 600);
+    for seed in [20, 30] {
+        assert_eq!(find(&accounts, escrow_address(seed).0).lamports, 0);
+        assert_eq!(find(&accounts, vault(seed).key).lamports, 0);
+    }
+    assert_eq!(
+        find(&accounts, RENT_RECIPIENT).lamports,
+        1_000_000_000 + 2 * (rent_exempt(Escrow::LEN) + TOKEN_ACCOUNT_LAMPORTS)
+    );
+}
+
+#[test]
+fn take_many_rejects_or_skips_a_mismatched_vault() {
+    let mut bad = group(20, 400);
+    bad.vault = token_account([21; 32], TOKEN_PROGRAM_ID, MINT_B, bad.escrow.key, 1_000);
+    assert_fails(
+        &take_many(1_000, std::slice::from_ref(&bad), false),
+        ProgramError::InvalidAccountOwner,
+    );
+    assert_settles_nothing(&take_many(1_000, &[bad], true));
+
+    let mut foreign = group(20, 400);
+    foreign.vault = token_account([21; 32], TOKEN_PROGRAM_ID, MINT_A, TAKER, 1_000);
+    assert_fails(
+        &take_many(1_000, std::slice::from_ref(&foreign), false),
+        ProgramError::InvalidAccountOwner,
+    );
+    assert_settles_nothing(&take_many(1_000, &[foreign], true));
+}
+
+#[test]
+fn take_many_rejects_or_skips_a_mismatched_maker_ata() {
+    let mut bad = group(20, 400);
+    bad.maker_ata_b = token_account([22; 32], TOKEN_PROGRAM_ID, MINT_B, TAKER, 0);
+    assert_fails(
+        &take_many(1_000, std::slice::from_ref(&bad), false),
+        ProgramError::InvalidAccountOwner,
+    );
+    assert_settles_nothing(&take_many(1_000, &[bad], true));
+}
+
+#[test]
+fn take_many_rejects_or_skips_an_escrow_off_its_address() {
+    let mut moved = group(20, 400);
+    moved.escrow.key = [23; 32];
+    moved.vault = token_account([21; 32], TOKEN_PROGRAM_ID, MINT_A, [23; 32], 1_000);
+    assert_fails(
+        &take_many(1_000, std::slice::from_ref(&moved), false),
+        ProgramError::InvalidSeeds,
+    );
+    assert_settles_nothing(&take_many(1_000, &[moved], true));
+}
+
+#[test]
+fn take_many_checks_the_takers_running_balance() {
+    // Each escrow is affordable on its own, but not both together.
+    let groups = [group(20, 600), group(30, 600)];
+    assert_fails(
+        &take_many(1_000, &groups, false),
+        ProgramError::InsufficientFunds,
+    );
+
+    assert_fails(
+        &take_many(599, &[group(20, 600)], false),
+        ProgramError::InsufficientFunds,
+    );
+    assert_settles_nothing(&take_many(599, &[group(20, 600)], true));
+}
+
+#[test]
+fn take_many_rejects_a_repeated_escrow() {
+    let groups = [group(20, 100), group(20, 100)];
+    assert_fails(
+        &take_many(1_000, &groups, false),
+        ProgramError::InvalidArgument,
+    );
+}
+
+#[test]
+fn take_many_skip_flag_is_zero_or_one() {
+    let mut invocation = take_many(1_000, &[group(20, 400)], false);
+    invocation.data = vec![8, 2];
+    assert_fails(&invocation, ProgramError::InvalidInstructionData);
+}
+
+fn quote(escrow: Account, vault: Account) -> Invocation {
+    invocation(Program::Escrow, 10, &[], vec![escrow, vault])
+}
+
+#[test]
+fn quote_returns_the_escrows_terms() {
+    match run(&quote(escrow(20, 400), vault(20))) {
+        Ok(Outcome::Success { return_data, .. }) => {
+            let quote = EscrowQuote::from_bytes(&return_data).unwrap();
+            assert_eq!((quote.seed, quote.maker), (20, MAKER));
+            assert_eq!((quote.mint_a, quote.mint_b), (MINT_A, MINT_B));
+            assert_eq!((quote.vault_amount, quote.receive), (1_000, 400));
+            assert_eq!(quote.price, 400 * QUOTE_PRICE_SCALE / 1_000);
+        }
+        other => panic!("expected a quote, got {other:?}"),
+    }
+}
+
+#[test]
+fn quote_checks_the_escrow_and_vault_addresses() {
+    let moved = Account {
+        key: [23; 32],
+        ..escrow(20, 400)
+    };
+    assert_fails(&quote(moved, vault(20)), ProgramError::InvalidSeeds);
+
+    let stray = Account {
+        key: [21; 32],
+        ..vault(20)
+    };
+    assert_fails(&quote(escrow(20, 400), stray), ProgramError::InvalidSeeds);
+}
+
+#[test]
+fn quote_rejects_other_escrow_layouts() {
+    let mut attested = escrow(20, 400);
+    attested.data.truncate(146);
+    assert_fails(
+        &quote(attested, vault(20)),
+        ProgramError::InvalidAccountData,
+    );
+
+    let foreign = Account {
+        owner: [9; 32],
+        ..escrow(20, 400)
+    };
+    assert_fails(
+        &quote(foreign, vault(20)),
+        ProgramError::InvalidAccountOwner,
+    );
+}
+
+/// Refund of `escrow` 21 to `MAKER`, with its rent going to `rent_recipient`.
+fn refund(escrow: Account, rent_recipient: Pubkey) -> Invocation {
+    let mut invocation = invocation(
+        Program::Escrow,
+        2,
+        &[],
+        vec![
+            signer(MAKER, 1_000_000_000),
+            escrow,
+            mint(MINT_A, TOKEN_PROGRAM_ID),
+            vault(21),
+            token_account([16; 32], TOKEN_PROGRAM_ID, MINT_A, MAKER, 0),
+            program(SYSTEM_PROGRAM_ID),
+            program(TOKEN_PROGRAM_ID),
+            wallet(rent_recipient, 0),
+        ],
+    );
+    if rent_recipient == MAKER {
+        *invocation.accounts.last_mut().unwrap() = AccountInput::Duplicate(0);
+    }
+    invocation
+}
+
+#[test]
+fn refund_takes_a_v1_escrow_not_yet_migrated() {
+    let accounts = succeed(&refund(escrow(21, 400), RENT_RECIPIENT));
+    assert_eq!(token_amount(find(&accounts, [16; 32])), 1_000);
+    assert_eq!(
+        find(&accounts, RENT_RECIPIENT).lamports,
+        rent_exempt(Escrow::LEN) + TOKEN_ACCOUNT_LAMPORTS
+    );
+
+    let mut v1 = escrow(21, 400);
+    v1.data.truncate(EscrowV1::LEN);
+    v1.lamports = rent_exempt(EscrowV1::LEN);
+    let accounts = succeed(&refund(v1.clone(), MAKER));
+    assert_eq!(token_amount(find(&accounts, [16; 32])), 1_000);
+    assert_eq!(
+        find(&accounts, MAKER).lamports,
+        1_000_000_000 + rent_exempt(EscrowV1::LEN) + TOKEN_ACCOUNT_LAMPORTS
+    );
+    // A v1 escrow has no rent recipient but its maker.
+    assert_fails(
+        &refund(v1, RENT_RECIPIENT),
+        ProgramError::InvalidAccountOwner,
+    );
+}
+
+/// `MAKER`'s escrow PDA for seed 7 of the Sweep `kind` (an escrow without one).
+fn sweep_escrow(kind: Option<u8>) -> Pubkey {
+    let prefix: &[u8] = match kind {
+        None | Some(0) => b"escrow",
+        Some(1) => b"attested",
+        _ => b"limit",
+    };
+    pda(&[prefix, &MAKER, &7u64.to_le_bytes()], &ESCROW_ID).0
+}
+
+/// Sweep of 50 token B stuck in a `token_program` account owned by the closed
+/// escrow PDA of `kind`.
+fn sweep(token_program: Pubkey, kind: Option<u8>) -> Invocation {
+    let escrow = sweep_escrow(kind);
+    let mut data = 7u64.to_le_bytes().to_vec();
+    data.extend(kind);
+    invocation(
+        Program::Escrow,
+        11,
+        &data,
+        vec![
+            signer(MAKER, 1_000_000_000),
+            wallet(escrow, 0),
+            mint(MINT_B, token_program),
+            token_account([15; 32], token_program, MINT_B, escrow, 50),
+            token_account([14; 32], token_program, MINT_B, MAKER, 0),
+            program(token_program),
+        ],
+    )
+}
+
+#[test]
+fn sweep_recovers_a_stray_account_of_either_token_program() {
+    for token_program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let accounts = succeed(&sweep(token_program, None));
+        assert_eq!(token_amount(find(&accounts, [14; 32])), 50);
+        assert_eq!(find(&accounts, [15; 32]).lamports, 0);
+        assert_eq!(
+            find(&accounts, MAKER).lamports,
+            1_000_000_000 + TOKEN_ACCOUNT_LAMPORTS
+        );
+    }
+    assert_fails(&sweep([9; 32], None), ProgramError::IncorrectProgramId);
+}
+
+#[test]
+fn sweep_checks_the_token_account_belongs_to_the_token_program() {
+    let mut invocation = sweep(TOKEN_2022_PROGRAM_ID, None);
+    let AccountInput::Account(token_program) = &mut invocation.accounts[5] else {
+        unreachable!()
+    };
+    token_program.key = TOKEN_PROGRAM_ID;
+    assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn sweep_accepts_each_escrow_kind() {
+    for kind in [None, Some(0), Some(1), Some(2)] {
+        succeed(&sweep(TOKEN_PROGRAM_ID, kind));
+    }
+    assert_fails(
+        &sweep(TOKEN_PROGRAM_ID, Some(3)),
+        ProgramError::InvalidInstructionData,
+    );
+}
+
+#[test]
+fn sweep_checks_the_escrow_matches_the_kind() {
+    // An escrow PDA, swept as if it were the attested escrow of the same seed.
+    let mut invocation = sweep(TOKEN_PROGRAM_ID, None);
+    invocation.data.push(1);
+    assert_fails(&invocation, ProgramError::InvalidSeeds);
+}
@@ -0,0 +1,252 @@
+//! The hand-written decoders, on arbitrary bytes: they never panic, and they
+//! accept exactly the inputs their layouts describe.
+
+use blueshift_pinocchio_escrow::{
+    AttestedEscrow, CancelOrderInstructionData, Escrow, EscrowV1, LimitEscrow,
+    MakeAttestedInstructionData, MakeInstructionData, MakeLimitInstructionData, PriceFeed,
+    SignedOrder, SweepInstructionData, TakeInstructionData, TakeLimitInstructionData,
+    TakeManyInstructionData, ESCROW_VERSION,
+};
+use proptest::collection::vec;
+use proptest::prelude::*;
+
+/// Copy into an 8-byte aligned buffer, as account data is on-chain.
+fn aligned(bytes: &[u8]) -> Vec<u64> {
+    let mut words = vec![0u64; bytes.len().div_ceil(8)];
+    // SAFETY: `words` spans at least `bytes.len()` bytes.
+    unsafe {
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), words.as_mut_ptr() as *mut u8, bytes.len())
+    };
+    words
+}
+
+fn as_bytes(words: &mut [u64], len: usize) -> &mut [u8] {
+    // SAFETY: `words` was sized for `len` bytes by `aligned`.
+    unsafe { std::slice::from_raw_parts_mut(words.as_mut_ptr() as *mut u8, len) }
+}
+
+fn u64_at(data: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap())
+}
+
+/// Lengths around each layout's boundary, plus anything up to 256 bytes.
+fn bytes() -> impl Strategy<Value = Vec<u8>> {
+    prop_oneof![
+        proptest::sample::select(
+            &[
+                0usize, 1, 7, 8, 9, 23, 24, 25, 40, 41, 42, 56, 57, 58, 59, 63, 64, 65, 79, 80, 81,
+                73, 105, 112, 113, 114, 145, 146, 147, 151, 152, 153, 154, 160, 193, 194, 195, 362,
+                363, 364
+            ][..]
+        )
+        .prop_flat_map(|len| vec(any::<u8>(), len)),
+        vec(any::<u8>(), 0..256),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn escrow_make_instruction_data(data in bytes()) {
+        let parsed = MakeInstructionData::try_from(data.as_slice());
+        let valid = (data.len() == MakeInstructionData::LEN || data.len() == MakeInstructionData::LEN + 32)
+            && u64_at(&data, 8) != 0
+            && u64_at(&data, 16) != 0;
+        prop_assert_eq!(parsed.is_ok(), valid);
+        if let Ok(make) = parsed {
+            prop_assert_eq!(make.seed, u64_at(&data, 0));
+            prop_assert_eq!(make.receive, u64_at(&data, 8));
+            prop_assert_eq!(make.amount, u64_at(&data, 16));
+            let rent_recipient = &data[MakeInstructionData::LEN..];
+            prop_assert_eq!(
+                make.rent_recipient.as_ref().map(|key| &key[..]),
+                (!rent_recipient.is_empty()).then_some(rent_recipient)
+            );
+        }
+    }
+
+    #[test]
+    fn escrow_make_attested_instruction_data(data in bytes()) {
+        let parsed = MakeAttestedInstructionData::try_from(data.as_slice());
+        if let Ok(make) = parsed {
+            prop_assert!(data.len() >= MakeAttestedInstructionData::LEN);
+            prop_assert_eq!(&make.attester[..], &data[24..57]);
+        }
+    }
+
+    #[test]
+    fn escrow_make_limit_instruction_data(data in bytes()) {
+        let parsed = MakeLimitInstructionData::try_from(data.as_slice());
+        let valid = data.len() == MakeLimitInstructionData::LEN
+            && u64_at(&data, 8) != 0
+            && u64_at(&data, 16) != 0;
+        prop_assert_eq!(parsed.is_ok(), valid);
+        if let Ok(make) = parsed {
+            prop_assert_eq!(make.max_price_age, u64_at(&data, 24));
+            prop_assert_eq!(&make.price_feed[..], &data[32..64]);
+        }
+    }
+
+    #[test]
+    fn escrow_other_instruction_data(data in bytes()) {
+        let _ = SignedOrder::try_from(data.as_slice());
+        let _ = TakeManyInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(CancelOrderInstructionData::try_from(data.as_slice()).is_ok(), data.len() == 8);
+        prop_assert_eq!(
+            SweepInstructionData::try_from(data.as_slice()).is_ok(),
+            data.len() == 8 || (data.len() == 9 && data[8] <= 2)
+        );
+        prop_assert_eq!(
+            TakeInstructionData::try_from(data.as_slice()).is_ok(),
+            data.is_empty() || data.len() == 16
+        );
+        prop_assert_eq!(
+            TakeLimitInstructionData::try_from(data.as_slice()).is_ok(),
+            data.is_empty() || data.len() == 8
+        );
+    }
+
+    #[test]
+    fn escrow_state_load(data in bytes()) {
+        let mut words = aligned(&data);
+        let bytes = as_bytes(&mut words, data.len());
+
+        let current = Escrow::LEN == data.len() && data[EscrowV1::LEN] == ESCROW_VERSION;
+        prop_assert_eq!(Escrow::load(bytes).is_ok(), current);
+        prop_assert_eq!(EscrowV1::load(bytes).is_ok(), data.len() == EscrowV1::LEN);
+        prop_assert_eq!(AttestedEscrow::load(bytes).is_ok(), data.len() == AttestedEscrow::LEN);
+        prop_assert_eq!(LimitEscrow::load(bytes).is_ok(), data.len() == LimitEscrow::LEN);
+        prop_assert_eq!(PriceFeed::load(bytes).is_ok(), data.len() >= PriceFeed::LEN);
+        prop_assert_eq!(Escrow::load_mut(bytes).is_ok(), data.len() == Escrow::LEN);
+        if let Ok(escrow) = Escrow::load(bytes) {
+            prop_assert_eq!(escrow.seed, u64_at(&data, 0));
+            prop_assert_eq!(escrow.receive, u64_at(&data, 104));
+            prop_assert_eq!(&escrow.rent_recipient[..], &data[114..146]);
+        }
+    }
+
+    #[test]
+    fn vault_deposit_instruction_data(data in bytes()) {
+        let parsed = blueshift_pinocchio_vault::DepositInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(parsed.is_ok(), data.len() == 8 && u64_at(&data, 0) != 0);
+    }
+
+    #[test]
+    fn vault_withdraw_instruction_data(data in bytes()) {
+        let parsed = blueshift_pinocchio_vault::WithdrawInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(parsed.is_ok(), data.is_empty() || (data.len() == 8 && u64_at(&data, 0) != 0));
+        if let Ok(withdraw) = parsed {
+            prop_assert_eq!(withdraw.amount, (!data.is_empty()).then(|| u64_at(&data, 0)));
+        }
+    }
+
+    #[test]
+    fn vault_timelock_instruction_data(data in bytes()) {
+        use blueshift_pinocchio_vault::{RequestWithdrawInstructionData, SetTimelockInstructionData};
+
+        let set = SetTimelockInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(set.is_ok(), data.len() == 8 && (u64_at(&data, 0) as i64) > 0);
+        let request = RequestWithdrawInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(request.is_ok(), data.len() == 8 && u64_at(&data, 0) != 0);
+    }
+
+    #[test]
+    fn vault_create_multisig_instruction_data(data in bytes()) {
+        use blueshift_pinocchio_vault::{CreateMultisigInstructionData, MAX_MULTISIG_SIGNERS};
+
+        let parsed = CreateMultisigInstructionData::try_from(data.as_slice());
+        let signers: Vec<&[u8]> = data.get(9..).unwrap_or_default().chunks(32).collect();
+        let distinct = signers
+            .iter()
+            .enumerate()
+            .all(|(i, signer)| !signers[..i].contains(signer));
+        prop_assert_eq!(
+            parsed.is_ok(),
+            data.len() > 9
+                && (data.len() - 9) % 32 == 0
+                && signers.len() <= MAX_MULTISIG_SIGNERS
+                && (1..=signers.len()).contains(&(data[8] as usize))
+                && distinct
+        );
+        if let Ok(create) = parsed {
+            prop_assert_eq!(create.seed, u64_at(&data, 0));
+            prop_assert_eq!(create.signer_count(), signers.len());
+        }
+    }
+
+    #[test]
+    fn vault_spending_limit_instruction_data(data in bytes()) {
+        use blueshift_pinocchio_vault::{SetSpendingLimitInstructionData, SpendInstructionData};
+
+        let set = SetSpendingLimitInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(
+            set.is_ok(),
+            data.len() == 48 && (u64_at(&data, 32) == 0 || (u64_at(&data, 40) as i64) > 0)
+        );
+        let spend = SpendInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(spend.is_ok(), data.len() == 8 && u64_at(&data, 0) != 0);
+    }
+
+    #[test]
+    fn vault_set_beneficiary_instruction_data(data in bytes()) {
+        let parsed =
+            blueshift_pinocchio_vault::SetBeneficiaryInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(parsed.is_ok(), data.len() == 40 && (u64_at(&data, 32) as i64) >= 0);
+    }
+
+    #[test]
+    fn vault_state_load(data in bytes()) {
+        use blueshift_pinocchio_vault::VaultState;
+
+        let mut words = aligned(&data);
+        let bytes = as_bytes(&mut words, data.len());
+        prop_assert_eq!(VaultState::load(bytes).is_ok(), data.len() == VaultState::LEN);
+        prop_assert_eq!(
+            blueshift_pinocchio_vault::MultisigVault::load(bytes).is_ok(),
+            data.len() == blueshift_pinocchio_vault::MultisigVault::LEN
+                && data[41] as usize <= blueshift_pinocchio_vault::MAX_MULTISIG_SIGNERS
+        );
+        if let Ok(state) = VaultState::load_mut(bytes) {
+            let total = u64_at(&data, 40);
+            prop_assert_eq!(state.record_deposit(1).is_ok(), total != u64::MAX);
+            prop_assert_eq!(state.total_deposited, total.saturating_add(1));
+
+            // A spend within the window counts against the limit unless cosigned.
+            let (limit, period, start, spent) = (
+                u64_at(&data, 88),
+                u64_at(&data, 96) as i64,
+                u64_at(&data, 104) as i64,
+                u64_at(&data, 112),
+            );
+            let now = start.saturating_add(period).saturating_sub(1);
+            let in_window = now >= start && now < start.saturating_add(period);
+            if in_window {
+                prop_assert_eq!(state.record_spend(1, now, false).is_ok(), spent < limit);
+            }
+            // Claimable only with recovery on and the period elapsed.
+            let (period, last_active) = (u64_at(&data, 152) as i64, u64_at(&data, 160) as i64);
+            if let Some(deadline) = last_active.checked_add(period).filter(|_| period > 0) {
+                prop_assert!(state.claimable(deadline) && !state.claimable(deadline - 1));
+            }
+            prop_assert_eq!(state.claimable(i64::MAX), period > 0);
+
+            let cosigned = state.record_spend(1, now, true);
+            prop_assert!(cosigned.is_ok() || state.window_spent == u64::MAX);
+        }
+    }
+
+    #[test]
+    fn secp256r1_vault_deposit_instruction_data(data in bytes()) {
+        let parsed = blueshift_secp256r1_vault::DepositInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(parsed.is_ok(), data.len() == 33 + 8);
+        if let Ok(deposit) = parsed {
+            prop_assert_eq!(&deposit.pubkey[..], &data[..33]);
+            prop_assert_eq!(deposit.amount, u64_at(&data, 33));
+        }
+    }
+
+    #[test]
+    fn secp256r1_vault_withdraw_instruction_data(data in bytes()) {
+        let parsed = blueshift_secp256r1_vault::WithdrawInstructionData::try_from(data.as_slice());
+        prop_assert_eq!(parsed.is_ok(), !data.is_empty());
+    }
+}
@@ -0,0 +1,740 @@
+//! Scenario runs of lamport and token vault instructions under the SVM, with the
+//! vault, state and token vault accounts at the addresses the vault derives.
+
+use blueshift_pinocchio_fuzz::fixtures::{
+    associated_token_address, mint, pda, program, rent_exempt, signer, token_account, token_amount,
+    wallet, AccountData,
+};
+use blueshift_pinocchio_fuzz::programs::{Program, VAULT_ID};
+use blueshift_pinocchio_fuzz::{
+    run, run_at, Account, AccountInput, Invocation, Outcome, Pubkey, NOW, SYSTEM_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use blueshift_pinocchio_vault::{MultisigVault, VaultError, VaultState};
+use pinocchio::program_error::ProgramError;
+
+const OWNER: Pubkey = [1; 32];
+const OTHER_OWNER: Pubkey = [2; 32];
+const MULTISIG: Pubkey = [5; 32];
+const RECIPIENT: Pubkey = [6; 32];
+const SIGNERS: [Pubkey; 3] = [[7; 32], [8; 32], [9; 32]];
+const MINT: Pubkey = [10; 32];
+const OTHER_MINT: Pubkey = [11; 32];
+const SPENDER: Pubkey = [12; 32];
+const BENEFICIARY: Pubkey = [13; 32];
+
+/// `OWNER`'s vault PDA and its bump.
+fn vault_address() -> (Pubkey, u8) {
+    pda(&[b"vault", &OWNER], &VAULT_ID)
+}
+
+/// `OWNER`'s state PDA and its bump.
+fn state_address() -> (Pubkey, u8) {
+    pda(&[b"state", &OWNER], &VAULT_ID)
+}
+
+type StateData = AccountData<{ VaultState::LEN }>;
+
+/// `owner`'s initialized vault state at `OWNER`'s state address, after `edit`.
+fn state_with(owner: Pubkey, edit: impl FnOnce(&mut VaultState)) -> Account {
+    let mut data = StateData::zeroed();
+    let state = VaultState::load_mut(&mut data.0).unwrap();
+    state.set_inner(owner, 1_000, [vault_address().1], [state_address().1]);
+    edit(state);
+    Account {
+        owner: VAULT_ID,
+        data: data.0.to_vec(),
+        ..wallet(state_address().0, rent_exempt(VaultState::LEN))
+    }
+}
+
+fn state(owner: Pubkey) -> Account {
+    state_with(owner, |_| {})
+}
+
+/// The aligned data of a vault state `account`, to load.
+fn state_data(account: &Account) -> StateData {
+    AccountData::of(account)
+}
+
+fn invocation(discriminator: u8, data: &[u8], accounts: Vec<Account>) -> Invocation {
+    blueshift_pinocchio_fuzz::fixtures::invocation(Program::Vault, discriminator, data, accounts)
+}
+
+/// Run `invocation` and expect it to fail with `expected`.
+fn assert_fails(invocation: &Invocation, expected: ProgramError) {
+    match run(invocation) {
+        Ok(Outcome::Error(code)) => assert_eq!(code, u64::from(expected)),
+        other => panic!("expected {expected:?}, got {other:?}"),
+    }
+}
+
+/// Run `invocation`, expect it to succeed and return the accounts it left.
+fn succeed(invocation: &Invocation) -> Vec<Account> {
+    match run(invocation) {
+        Ok(Outcome::Success { accounts, .. }) => accounts,
+        other => panic!("expected success, got {other:?}"),
+    }
+}
+
+/// The account at `key` among `accounts`.
+fn find(accounts: &[Account], key: Pubkey) -> &Account {
+    accounts.iter().find(|account| account.key == key).unwrap()
+}
+
+fn deposit(state: Option<Account>) -> Invocation {
+    let accounts = [
+        signer(OWNER, 10_000_000),
+        wallet(vault_address().0, 0),
+        program(SYSTEM_PROGRAM_ID),
+    ];
+    invocation(
+        0,
+        &2_000_000u64.to_le_bytes(),
+        accounts.into_iter().chain(state).collect(),
+    )
+}
+
+fn withdraw(state: Option<Account>) -> Invocation {
+    withdraw_to(state, &[])
+}
+
+/// Withdraw of everything, passing `recipients` after the state.
+fn withdraw_to(state: Option<Account>, recipients: &[Account]) -> Invocation {
+    let accounts = [
+        signer(OWNER, 10_000_000),
+        wallet(vault_address().0, 2_000_000),
+        program(SYSTEM_PROGRAM_ID),
+    ];
+    invocation(
+        1,
+        &[],
+        accounts
+            .into_iter()
+            .chain(state)
+            .chain(recipients.iter().cloned())
+            .collect(),
+    )
+}
+
+#[test]
+fn deposit_and_withdraw_take_an_optional_state() {
+    let accounts = succeed(&deposit(None));
+    assert_eq!(find(&accounts, vault_address().0).lamports, 2_000_000);
+    let accounts = succeed(&withdraw(None));
+    assert_eq!(find(&accounts, OWNER).lamports, 12_000_000);
+
+    let mut extra = deposit(Some(state(OWNER)));
+    extra
+        .accounts
+        .push(AccountInput::Account(wallet(RECIPIENT, 0)));
+    assert_fails(&extra, ProgramError::InvalidArgument);
+}
+
+#[test]
+fn deposit_and_withdraw_use_the_owners_state() {
+    let accounts = succeed(&deposit(Some(state(OWNER))));
+    assert_eq!(find(&accounts, vault_address().0).lamports, 2_000_000);
+    let data = state_data(find(&accounts, state_address().0));
+    assert_eq!(
+        VaultState::load(&data.0).unwrap().total_deposited,
+        2_000_000
+    );
+
+    let accounts = succeed(&withdraw(Some(state(OWNER))));
+    assert_eq!(find(&accounts, vault_address().0).lamports, 0);
+    assert_eq!(find(&accounts, OWNER).lamports, 12_000_000);
+
+    for invocation in [
+        deposit(Some(state(OTHER_OWNER))),
+        withdraw(Some(state(OTHER_OWNER))),
+    ] {
+        assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+    }
+}
+
+#[test]
+fn uninitialized_state_address_is_no_recipient() {
+    // Until the owner creates a state, its [b"state", owner] PDA may still be
+    // passed, and is skipped.
+    succeed(&deposit(Some(wallet(state_address().0, 0))));
+    let accounts = succeed(&withdraw(Some(wallet(state_address().0, 0))));
+    assert_eq!(find(&accounts, OWNER).lamports, 12_000_000);
+    assert_eq!(find(&accounts, state_address().0).lamports, 0);
+
+    // Another owner's is no state but a wallet, which only Withdraw takes.
+    let elsewhere = pda(&[b"state", &OTHER_OWNER], &VAULT_ID).0;
+    assert_fails(
+        &deposit(Some(wallet(elsewhere, 0))),
+        ProgramError::InvalidArgument,
+    );
+    let accounts = succeed(&withdraw(Some(wallet(elsewhere, 0))));
+    assert_eq!(find(&accounts, elsewhere).lamports, 2_000_000);
+}
+
+#[test]
+fn withdraw_pays_a_recipient_wallet() {
+    let invocation = withdraw_to(Some(state(OWNER)), &[wallet(RECIPIENT, 1_000_000)]);
+    let accounts = succeed(&invocation);
+    assert_eq!(find(&accounts, RECIPIENT).lamports, 3_000_000);
+    assert_eq!(find(&accounts, OWNER).lamports, 10_000_000);
+
+    let program_owned = Account {
+        owner: VAULT_ID,
+        ..wallet(RECIPIENT, 0)
+    };
+    assert_fails(
+        &withdraw_to(Some(state(OWNER)), &[program_owned]),
+        ProgramError::InvalidAccountOwner,
+    );
+    assert_fails(
+        &withdraw_to(
+            Some(state(OWNER)),
+            &[wallet(RECIPIENT, 0), wallet(OTHER_OWNER, 0)],
+        ),
+        ProgramError::InvalidArgument,
+    );
+}
+
+#[test]
+fn withdraw_to_a_recipient_still_needs_the_owner() {
+    let mut invocation = withdraw_to(Some(state(OWNER)), &[signer(RECIPIENT, 0)]);
+    let AccountInput::Account(owner) = &mut invocation.accounts[0] else {
+        unreachable!()
+    };
+    owner.is_signer = false;
+    assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+}
+
+/// Lamports the multisig vault holds in these scenarios.
+const MULTISIG_LAMPORTS: u64 = rent_exempt(MultisigVault::LEN) + 5_000_000;
+
+/// A 2-of-3 multisig vault of `SIGNERS`.
+fn multisig() -> Account {
+    let mut data = AccountData::<{ MultisigVault::LEN }>::zeroed();
+    let multisig = MultisigVault::load_mut(&mut data.0).unwrap();
+    multisig.creator = OWNER;
+    multisig.seed = 7;
+    multisig.threshold = 2;
+    multisig.signer_count = 3;
+    multisig.bump = [255];
+    multisig.signers[..3].copy_from_slice(&SIGNERS);
+    Account {
+        owner: VAULT_ID,
+        data: data.0.to_vec(),
+        ..wallet(MULTISIG, MULTISIG_LAMPORTS)
+    }
+}
+
+/// MultisigWithdraw of `amount`, co-signed by `signers`.
+fn multisig_withdraw(amount: u64, signers: &[AccountInput]) -> Invocation {
+    Invocation {
+        program: Program::Vault,
+        accounts: [
+            AccountInput::Account(multisig()),
+            AccountInput::Account(wallet(RECIPIENT, 1_000_000)),
+        ]
+        .into_iter()
+        .chain(signers.iter().cloned())
+        .collect(),
+        data: [&[9][..], &amount.to_le_bytes()].concat(),
+    }
+}
+
+fn cosigner(key: Pubkey) -> AccountInput {
+    AccountInput::Account(signer(key, 1_000_000))
+}
+
+#[test]
+fn multisig_counts_a_signer_passed_twice_once() {
+    let twice = [cosigner(SIGNERS[0]), AccountInput::Duplicate(2)];
+    assert_fails(
+        &multisig_withdraw(MULTISIG_LAMPORTS, &twice),
+        ProgramError::Custom(VaultError::NotEnoughSigners as u32),
+    );
+    let same_key_twice = [cosigner(SIGNERS[0]), cosigner(SIGNERS[0])];
+    assert_fails(
+        &multisig_withdraw(MULTISIG_LAMPORTS, &same_key_twice),
+        ProgramError::Custom(VaultError::NotEnoughSigners as u32),
+    );
+}
+
+#[test]
+fn multisig_ignores_unlisted_and_unsigned_accounts() {
+    let unsigned = AccountInput::Account(wallet(SIGNERS[1], 1_000_000));
+    for second in [cosigner(OWNER), unsigned] {
+        assert_fails(
+            &multisig_withdraw(MULTISIG_LAMPORTS, &[cosigner(SIGNERS[0]), second]),
+            ProgramError::Custom(VaultError::NotEnoughSigners as u32),
+        );
+    }
+}
+
+#[test]
+fn multisig_partial_withdrawal_keeps_the_vault() {
+    let signers = [cosigner(SIGNERS[0]), cosigner(SIGNERS[1])];
+    let accounts = succeed(&multisig_withdraw(5_000_000, &signers));
+    assert_eq!(
+        find(&accounts, MULTISIG).lamports,
+        rent_exempt(MultisigVault::LEN)
+    );
+    assert_eq!(find(&accounts, MULTISIG).owner, VAULT_ID);
+    assert_eq!(find(&accounts, RECIPIENT).lamports, 6_000_000);
+
+    assert_fails(
+        &multisig_withdraw(5_000_001, &signers),
+        ProgramError::Custom(VaultError::NotRentExempt as u32),
+    );
+}
+
+#[test]
+fn multisig_full_withdrawal_closes_the_vault() {
+    let invocation = multisig_withdraw(
+        MULTISIG_LAMPORTS,
+        &[cosigner(SIGNERS[2]), cosigner(SIGNERS[0])],
+    );
+    let accounts = succeed(&invocation);
+    let closed = find(&accounts, MULTISIG);
+    assert_eq!(closed.lamports, 0);
+    assert!(closed.data.is_empty());
+    assert_eq!(
+        find(&accounts, RECIPIENT).lamports,
+        1_000_000 + MULTISIG_LAMPORTS
+    );
+}
+
+#[test]
+fn multisig_withdrawal_is_not_the_creators_activity() {
+    let signers = [
+        cosigner(SIGNERS[0]),
+        cosigner(SIGNERS[1]),
+        AccountInput::Account(state(OWNER)),
+    ];
+    let accounts = succeed(&multisig_withdraw(5_000_000, &signers));
+    let data = state_data(find(&accounts, state_address().0));
+    assert_eq!(VaultState::load(&data.0).unwrap().last_active, 1_000);
+}
+
+#[test]
+fn multisig_withdrawal_above_the_balance_is_rejected() {
+    let signers = [cosigner(SIGNERS[0]), cosigner(SIGNERS[1])];
+    assert_fails(
+        &multisig_withdraw(MULTISIG_LAMPORTS + 1, &signers),
+        ProgramError::InsufficientFunds,
+    );
+}
+
+/// `OWNER`'s token vault PDA for `MINT`.
+fn token_vault_address() -> Pubkey {
+    pda(&[b"vault", &OWNER, &MINT], &VAULT_ID).0
+}
+
+/// Token vault accounts under `token_program`, with 1_000 tokens each in the
+/// owner's and the vault's token accounts and no vault state, then `recipient`.
+fn token_vault_accounts(token_program: Pubkey, recipient: Option<Account>) -> Vec<Account> {
+    let vault = token_vault_address();
+    let accounts = [
+        signer(OWNER, 10_000_000),
+        wallet(vault, 0),
+        mint(MINT, token_program),
+        token_account([20; 32], token_program, MINT, OWNER, 1_000),
+        token_account(
+            associated_token_address(&vault, &MINT, &token_program),
+            token_program,
+            MINT,
+            vault,
+            1_000,
+        ),
+        program(token_program),
+        wallet(state_address().0, 0),
+    ];
+    accounts.into_iter().chain(recipient).collect()
+}
+
+/// TokenDeposit of 400 under `token_program`.
+fn token_deposit(token_program: Pubkey) -> Invocation {
+    invocation(
+        10,
+        &400u64.to_le_bytes(),
+        token_vault_accounts(token_program, None),
+    )
+}
+
+/// TokenWithdraw of everything under `token_program`, optionally to `recipient`.
+fn token_withdraw(token_program: Pubkey, recipient: Option<Account>) -> Invocation {
+    invocation(11, &[], token_vault_accounts(token_program, recipient))
+}
+
+#[test]
+fn token_vault_takes_spl_token_and_token_2022() {
+    for token_program in [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID] {
+        let vault_token_account =
+            associated_token_address(&token_vault_address(), &MINT, &token_program);
+
+        let accounts = succeed(&token_deposit(token_program));
+        assert_eq!(token_amount(find(&accounts, [20; 32])), 600);
+        assert_eq!(token_amount(find(&accounts, vault_token_account)), 1_400);
+
+        let accounts = succeed(&token_withdraw(token_program, None));
+        assert_eq!(token_amount(find(&accounts, [20; 32])), 2_000);
+        assert_eq!(token_amount(find(&accounts, vault_token_account)), 0);
+    }
+    assert_fails(
+        &token_withdraw([12; 32], None),
+        ProgramError::IncorrectProgramId,
+    );
+}
+
+#[test]
+fn token_vault_instructions_mark_the_owner_active() {
+    for mut invocation in [
+        token_deposit(TOKEN_PROGRAM_ID),
+        token_withdraw(TOKEN_PROGRAM_ID, None),
+    ] {
+        invocation.accounts[6] = AccountInput::Account(state(OWNER));
+        let accounts = succeed(&invocation);
+        let data = state_data(find(&accounts, state_address().0));
+        assert_eq!(VaultState::load(&data.0).unwrap().last_active, NOW);
+
+        invocation.accounts[6] = AccountInput::Account(state(OTHER_OWNER));
+        assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+    }
+}
+
+#[test]
+fn token_vault_accounts_belong_to_the_token_program() {
+    let mut invocation = token_withdraw(TOKEN_2022_PROGRAM_ID, None);
+    let AccountInput::Account(vault_token_account) = &mut invocation.accounts[4] else {
+        unreachable!()
+    };
+    vault_token_account.owner = TOKEN_PROGRAM_ID;
+    assert_fails(&invocation, ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn token_vault_token_account_is_the_vaults_ata() {
+    let mut invocation = token_withdraw(TOKEN_PROGRAM_ID, None);
+    let AccountInput::Account(vault_token_account) = &mut invocation.accounts[4] else {
+        unreachable!()
+    };
+    vault_token_account.key = [21; 32];
+    assert_fails(&invocation, ProgramError::InvalidSeeds);
+}
+
+#[test]
+fn token_withdraw_pays_a_recipient_of_the_mint() {
+    let recipient =
+        |token_program, mint| Some(token_account([22; 32], token_program, mint, RECIPIENT, 0));
+    let accounts = succeed(&token_withdraw(
+        TOKEN_PROGRAM_ID,
+        recipient(TOKEN_PROGRAM_ID, MINT),
+    ));
+    assert_eq!(token_amount(find(&accounts, [22; 32])), 1_000);
+    assert_eq!(token_amount(find(&accounts, [20; 32])), 1_000);
+
+    assert_fails(
+        &token_withdraw(TOKEN_PROGRAM_ID, recipient(TOKEN_PROGRAM_ID, OTHER_MINT)),
+        ProgramError::InvalidAccountData,
+    );
+    assert_fails(
+        &token_withdraw(TOKEN_PROGRAM_ID, recipient(TOKEN_2022_PROGRAM_ID, MINT)),
+        ProgramError::InvalidAccountOwner,
+    );
+}
+
+/// `OWNER`'s vault state with `SPENDER` allowed 1_000_000 lamports a day.
+fn spending_state() -> Account {
+    state_with(OWNER, |state| {
+        state.spender = SPENDER;
+        state.spending_limit = 1_000_000;
+        state.period = 86_400;
+    })
+}
+
+/// Spend of 500_000 lamports by `spender`, passing `recipients` after the state.
+fn spend(spender: Pubkey, recipients: &[Account]) -> Invocation {
+    let accounts = [
+        signer(spender, 1_000_000),
+        wallet(OWNER, 10_000_000),
+        wallet(vault_address().0, 5_000_000),
+        program(SYSTEM_PROGRAM_ID),
+        spending_state(),
+    ];
+    invocation(
+        13,
+        &500_000u64.to_le_bytes(),
+        accounts
+            .into_iter()
+            .chain(recipients.iter().cloned())
+            .collect(),
+    )
+}
+
+#[test]
+fn only_the_states_spender_may_spend() {
+    let accounts = succeed(&spend(SPENDER, &[]));
+    assert_eq!(find(&accounts, SPENDER).lamports, 1_500_000);
+    assert_eq!(find(&accounts, vault_address().0).lamports, 4_500_000);
+
+    assert_fails(&spend(OTHER_OWNER, &[]), ProgramError::InvalidAccountOwner);
+
+    let mut unsigned = spend(SPENDER, &[]);
+    let AccountInput::Account(spender) = &mut unsigned.accounts[0] else {
+        unreachable!()
+    };
+    spender.is_signer = false;
+    assert_fails(&unsigned, ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn spend_pays_a_recipient_wallet() {
+    let accounts = succeed(&spend(SPENDER, &[wallet(RECIPIENT, 1_000_000)]));
+    assert_eq!(find(&accounts, RECIPIENT).lamports, 1_500_000);
+
+    let program_owned = Account {
+        owner: VAULT_ID,
+        ..wallet(RECIPIENT, 0)
+    };
+    assert_fails(
+        &spend(SPENDER, &[program_owned]),
+        ProgramError::InvalidAccountOwner,
+    );
+    assert_fails(
+        &spend(SPENDER, &[wallet(RECIPIENT, 0), wallet(OTHER_OWNER, 0)]),
+        ProgramError::InvalidArgument,
+    );
+}
+
+/// Seconds of owner inactivity after which `BENEFICIARY` may claim.
+const INACTIVITY_PERIOD: i64 = 365 * 86_400;
+
+/// `OWNER`'s vault state naming `BENEFICIARY`; the owner was last active at 1_000.
+fn beneficiary_state() -> Account {
+    state_with(OWNER, |state| {
+        state.beneficiary = BENEFICIARY;
+        state.inactivity_period = INACTIVITY_PERIOD;
+    })
+}
+
+/// Claim of `OWNER`'s vault by `beneficiary`.
+fn claim(beneficiary: Pubkey) -> Invocation {
+    let accounts = vec![
+        signer(beneficiary, 1_000_000),
+        wallet(OWNER, 0),
+        wallet(vault_address().0, 5_000_000),
+        beneficiary_state(),
+        program(SYSTEM_PROGRAM_ID),
+    ];
+    invocation(15, &[], accounts)
+}
+
+#[test]
+fn only_the_states_beneficiary_may_claim() {
+    let accounts = succeed(&claim(BENEFICIARY));
+    assert_eq!(
+        find(&accounts, BENEFICIARY).lamports,
+        6_000_000 + rent_exempt(VaultState::LEN)
+    );
+    assert_eq!(find(&accounts, vault_address().0).lamports, 0);
+    assert_eq!(find(&accounts, state_address().0).lamports, 0);
+
+    assert_fails(&claim(OTHER_OWNER), ProgramError::InvalidAccountOwner);
+
+    let mut unsigned = claim(BENEFICIARY);
+    let AccountInput::Account(beneficiary) = &mut unsigned.accounts[0] else {
+        unreachable!()
+    };
+    beneficiary.is_signer = false;
+    assert_fails(&unsigned, ProgramError::InvalidAccountOwner);
+}
+
+#[test]
+fn claim_waits_for_the_inactivity_period() {
+    let invocation = claim(BENEFICIARY);
+    match run_at(&invocation, 1_000 + INACTIVITY_PERIOD - 1) {
+        Ok(Outcome::Error(code)) => assert_eq!(
+            code,
+            u64::from(ProgramError::Custom(VaultError::OwnerStillActive as u32))
+        ),
+        other => panic!("expected the owner to still be active, got {other:?}"),
+    }
+    assert!(matches!(
+        run_at(&invocation, 1_000 + INACTIVITY_PERIOD),
+        Ok(Outcome::Success { .. })
+    ));
+}
+
+/// `OWNER`'s vault state with a one-hour withdrawal delay and, if `pending`, a
+/// request for 500_000 lamports that unlocks at `NOW`.
+fn timelocked_state(pending: bool) -> Account {
+    state_with(OWNER, |state| {
+        state.delay = 3_600;
+        if pending {
+            state.pending_amount = 500_000;
+            state.unlock_at = NOW;
+        }
+    })
+}
+
+/// SetTimelock, RequestWithdraw or CancelWithdraw by `OWNER` with `data`.
+fn timelock(discriminator: u8, data: &[u8], state: Account) -> Invocation {
+    invocation(discriminator, data, vec![signer(OWNER, 10_000_000), state])
+}
+
+fn execute_withdraw(state: Account) -> Invocation {
+    invocation(
+        6,
+        &[],
+        vec![
+            signer(OWNER, 10_000_000),
+            wallet(vault_address().0, 5_000_000),
+            program(SYSTEM_PROGRAM_ID),
+            state,
+        ],
+    )
+}
+
+#[test]
+fn a_withdrawal_delay_locks_withdraw_and_close() {
+    let locked = ProgramError::Custom(VaultError::WithdrawalLocked as u32);
+    assert_fails(&withdraw(Some(timelocked_state(false))), locked);
+
+    let close = invocation(
+        3,
+        &[],
+        vec![
+            signer(OWNER, 10_000_000),
+            wallet(vault_address().0, 2_000_000),
+            timelocked_state(false),
+            program(SYSTEM_PROGRAM_ID),
+        ],
+    );
+    assert_fails(&close, locked);
+}
+
+#[test]
+fn a_withdrawal_delay_locks_spend_and_claim() {
+    let locked = ProgramError::Custom(VaultError::WithdrawalLocked as u32);
+
+    let mut spend = spend(SPENDER, &[]);
+    spend.accounts[4] = AccountInput::Account(state_with(OWNER, |state| {
+        state.spender = SPENDER;
+        state.spending_limit = 1_000_000;
+        state.period = 86_400;
+        state.delay = 3_600;
+    }));
+    assert_fails(&spend, locked);
+
+    let mut claim = claim(BENEFICIARY);
+    claim.accounts[3] = AccountInput::Account(state_with(OWNER, |state| {
+        state.beneficiary = BENEFICIARY;
+        state.inactivity_period = INACTIVITY_PERIOD;
+        state.delay = 3_600;
+    }));
+    assert_fails(&claim, locked);
+}
+
+#[test]
+fn a_withdrawal_delay_leaves_spending_off() {
+    let set_spending_limit = |limit: u64| {
+        let data = [&SPENDER[..], &limit.to_le_bytes(), &86_400i64.to_le_bytes()].concat();
+        invocation(
+            12,
+            &data,
+            vec![signer(OWNER, 10_000_000), timelocked_state(false)],
+        )
+    };
+    assert_fails(
+        &set_spending_limit(1_000_000),
+        ProgramError::Custom(VaultError::WithdrawalLocked as u32),
+    );
+    succeed(&set_spending_limit(0));
+}
+
+#[test]
+fn a_withdrawal_delay_leaves_recovery_off() {
+    let set_beneficiary = |period: i64| {
+        let data = [&BENEFICIARY[..], &period.to_le_bytes()].concat();
+        invocation(
+            14,
+            &data,
+            vec![signer(OWNER, 10_000_000), timelocked_state(false)],
+        )
+    };
+    assert_fails(
+        &set_beneficiary(INACTIVITY_PERIOD),
+        ProgramError::Custom(VaultError::WithdrawalLocked as u32),
+    );
+    succeed(&set_beneficiary(0));
+}
+
+#[test]
+fn set_timelock_only_raises_the_delay() {
+    let accounts = succeed(&timelock(
+        4,
+        &7_200i64.to_le_bytes(),
+        timelocked_state(false),
+    ));
+    let data = state_data(find(&accounts, state_address().0));
+    assert_eq!(VaultState::load(&data.0).unwrap().delay, 7_200);
+
+    assert_fails(
+        &timelock(4, &1_800i64.to_le_bytes(), timelocked_state(false)),
+        ProgramError::Custom(VaultError::WithdrawalLocked as u32),
+    );
+}
+
+#[test]
+fn request_withdraw_starts_the_delay() {
+    let accounts = succeed(&timelock(
+        5,
+        &500_000u64.to_le_bytes(),
+        timelocked_state(false),
+    ));
+    let data = state_data(find(&accounts, state_address().0));
+    let requested = VaultState::load(&data.0).unwrap();
+    assert_eq!(requested.pending_amount, 500_000);
+    assert_eq!(requested.unlock_at, NOW + 3_600);
+
+    assert_fails(
+        &timelock(5, &500_000u64.to_le_bytes(), state(OWNER)),
+        ProgramError::Custom(VaultError::TimelockNotSet as u32),
+    );
+}
+
+#[test]
+fn execute_withdraw_pays_the_owner_once_unlocked() {
+    let invocation = execute_withdraw(timelocked_state(true));
+    match run_at(&invocation, NOW - 1) {
+        Ok(Outcome::Error(code)) => assert_eq!(
+            code,
+            u64::from(ProgramError::Custom(VaultError::WithdrawalLocked as u32))
+        ),
+        other => panic!("expected the withdrawal to be locked, got {other:?}"),
+    }
+
+    let accounts = succeed(&invocation);
+    assert_eq!(find(&accounts, OWNER).lamports, 10_500_000);
+    assert_eq!(find(&accounts, vault_address().0).lamports, 4_500_000);
+    let data = state_data(find(&accounts, state_address().0));
+    let state = VaultState::load(&data.0).unwrap();
+    assert_eq!(state.pending_amount, 0);
+    assert_eq!(state.total_withdrawn, 500_000);
+
+    assert_fails(
+        &execute_withdraw(timelocked_state(false)),
+        ProgramError::Custom(VaultError::NoPendingWithdrawal as u32),
+    );
+}
+
+#[test]
+fn cancel_withdraw_drops_the_request() {
+    let accounts = succeed(&timelock(7, &[], timelocked_state(true)));
+    let data = state_data(find(&accounts, state_address().0));
+    assert_eq!(VaultState::load(&data.0).unwrap().pending_amount, 0);
+
+    assert_fails(
+        &timelock(7, &[], timelocked_state(false)),
+        ProgramError::Custom(VaultError::NoPendingWithdrawal as u32),
+    );
+}
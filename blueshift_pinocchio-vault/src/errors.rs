@@ -0,0 +1,28 @@
+use pinocchio::program_error::ProgramError;
+
+/// Vault errors, returned as `ProgramError::Custom(code)`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VaultError {
+    /// A deposit or withdrawal would leave the vault holding lamports but below
+    /// the rent-exempt minimum for a zero-data account.
+    NotRentExempt = 0,
+    /// The pending withdrawal's unlock time has not been reached, or a withdrawal
+    /// delay is set and the lamports must go through RequestWithdraw.
+    WithdrawalLocked = 1,
+    /// No withdrawal has been requested.
+    NoPendingWithdrawal = 2,
+    /// Fewer of the multisig's signers signed than its threshold requires.
+    NotEnoughSigners = 3,
+    /// A spend would take the spender past the window's limit without the owner.
+    SpendingLimitExceeded = 4,
+    /// The owner was active within the inactivity period, or recovery is off.
+    OwnerStillActive = 5,
+    /// A withdrawal was requested without a withdrawal delay set.
+    TimelockNotSet = 6,
+}
+
+impl From<VaultError> for ProgramError {
+    fn from(error: VaultError) -> Self {
+        ProgramError::Custom(error as u32)
+    }
+}
@@ -0,0 +1,41 @@
+use pinocchio::{
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::instructions::request_withdraw::TimelockAccounts;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// CancelWithdraw instruction: drop the pending withdrawal request.
+pub struct CancelWithdraw<'a> {
+    pub accounts: TimelockAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for CancelWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = TimelockAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> CancelWithdraw<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut_data()?;
+        let state = VaultState::load_mut(&mut data)?;
+        if state.pending_amount == 0 {
+            return Err(VaultError::NoPendingWithdrawal.into());
+        }
+        state.pending_amount = 0;
+        state.unlock_at = 0;
+        state.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use pinocchio::{
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::check_vault;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// Claim accounts: [beneficiary (signer), owner, vault PDA, vault state,
+/// system_program].
+pub struct ClaimAccounts<'a> {
+    pub beneficiary: &'a AccountInfo,
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub state: &'a AccountInfo,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for ClaimAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [beneficiary, owner, vault, state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !beneficiary.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !vault.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // The state must name this beneficiary; check_vault then ties it to the
+        // owner and verifies the vault PDA.
+        if !state.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if VaultState::load(&state.try_borrow_data()?)?.beneficiary != *beneficiary.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let bump = check_vault(vault, owner.key(), Some(state))?;
+
+        // The owner must have been inactive for the whole period.
+        if !VaultState::load(&state.try_borrow_data()?)?.claimable(Clock::get()?.unix_timestamp) {
+            return Err(VaultError::OwnerStillActive.into());
+        }
+
+        Ok(Self {
+            beneficiary,
+            owner,
+            vault,
+            state,
+            bumps: [bump],
+        })
+    }
+}
+
+/// Claim instruction: after `inactivity_period` seconds without owner activity,
+/// the beneficiary drains the vault and closes its state, like `Close` does for
+/// the owner, and like it is refused while a withdrawal delay is set.
+pub struct Claim<'a> {
+    pub accounts: ClaimAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Claim<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = ClaimAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Claim<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let lamports = self.accounts.vault.lamports();
+        if lamports != 0 {
+            if VaultState::load(&self.accounts.state.try_borrow_data()?)?.delay != 0 {
+                return Err(VaultError::WithdrawalLocked.into());
+            }
+
+            let seeds = [
+                Seed::from(b"vault"),
+                Seed::from(self.accounts.owner.key().as_ref()),
+                Seed::from(&self.accounts.bumps),
+            ];
+            let signers = [Signer::from(&seeds)];
+
+            Transfer {
+                from: self.accounts.vault,
+                to: self.accounts.beneficiary,
+                lamports,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // The state is program-owned: move its lamports directly, then close it.
+        let state_lamports = self.accounts.state.lamports();
+        *self.accounts.beneficiary.try_borrow_mut_lamports()? = self
+            .accounts
+            .beneficiary
+            .lamports()
+            .checked_add(state_lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *self.accounts.state.try_borrow_mut_lamports()? = 0;
+        self.accounts.state.close()
+    }
+}
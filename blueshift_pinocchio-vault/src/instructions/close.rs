@@ -0,0 +1,102 @@
+use pinocchio::{
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::check_vault;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// Close accounts: [owner (signer), vault PDA, vault state, system_program].
+pub struct CloseAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub state: &'a AccountInfo,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for CloseAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, vault, state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the vault owner may close.
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !vault.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // Verify the state belongs to the owner and the vault is theirs.
+        let bump = check_vault(vault, owner.key(), Some(state))?;
+
+        Ok(Self {
+            owner,
+            vault,
+            state,
+            bumps: [bump],
+        })
+    }
+}
+
+/// Close instruction: drain the vault to the owner and close its state account,
+/// returning the state's rent to the owner too. With a withdrawal delay set, the
+/// vault must first be emptied through RequestWithdraw and ExecuteWithdraw.
+pub struct Close<'a> {
+    pub accounts: CloseAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Close<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = CloseAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Close<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let lamports = self.accounts.vault.lamports();
+        if lamports != 0 {
+            if VaultState::load(&self.accounts.state.try_borrow_data()?)?.delay != 0 {
+                return Err(VaultError::WithdrawalLocked.into());
+            }
+
+            let seeds = [
+                Seed::from(b"vault"),
+                Seed::from(self.accounts.owner.key().as_ref()),
+                Seed::from(&self.accounts.bumps),
+            ];
+            let signers = [Signer::from(&seeds)];
+
+            Transfer {
+                from: self.accounts.vault,
+                to: self.accounts.owner,
+                lamports,
+            }
+            .invoke_signed(&signers)?;
+        }
+
+        // The state is program-owned: move its lamports directly, then close it.
+        let state_lamports = self.accounts.state.lamports();
+        *self.accounts.owner.try_borrow_mut_lamports()? = self
+            .accounts
+            .owner
+            .lamports()
+            .checked_add(state_lamports)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *self.accounts.state.try_borrow_mut_lamports()? = 0;
+        self.accounts.state.close()
+    }
+}
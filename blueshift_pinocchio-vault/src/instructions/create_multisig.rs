@@ -0,0 +1,160 @@
+use core::mem::size_of;
+use pinocchio::{
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::instructions::helpers::find_multisig_address;
+use crate::state::{MultisigVault, MAX_MULTISIG_SIGNERS};
+
+use pinocchio::account_info::AccountInfo;
+
+/// CreateMultisig accounts: [creator (signer), multisig vault PDA, system_program].
+pub struct CreateMultisigAccounts<'a> {
+    pub creator: &'a AccountInfo,
+    pub multisig: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for CreateMultisigAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [creator, multisig, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Creator signs and pays for the account; they need not be a signer of it.
+        if !creator.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { creator, multisig })
+    }
+}
+
+/// CreateMultisig instruction data: seed (u64, little-endian), threshold (u8), then
+/// 1 to `MAX_MULTISIG_SIGNERS` distinct signer pubkeys (32 bytes each).
+pub struct CreateMultisigInstructionData<'a> {
+    pub seed: u64,
+    pub threshold: u8,
+    pub signers: &'a [u8],
+}
+
+impl<'a> CreateMultisigInstructionData<'a> {
+    /// The `index`th signer pubkey.
+    pub fn signer(&self, index: usize) -> Pubkey {
+        self.signers[index * 32..(index + 1) * 32]
+            .try_into()
+            .unwrap()
+    }
+
+    pub fn signer_count(&self) -> usize {
+        self.signers.len() / 32
+    }
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for CreateMultisigInstructionData<'a> {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        const HEADER: usize = size_of::<u64>() + size_of::<u8>();
+        if data.len() < HEADER {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let (header, signers) = data.split_at(HEADER);
+        if signers.is_empty()
+            || signers.len() % 32 != 0
+            || signers.len() / 32 > MAX_MULTISIG_SIGNERS
+        {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let parsed = Self {
+            seed: u64::from_le_bytes(header[..8].try_into().unwrap()),
+            threshold: header[8],
+            signers,
+        };
+
+        // 1 <= M <= N, with no signer listed twice.
+        if parsed.threshold == 0 || parsed.threshold as usize > parsed.signer_count() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        for i in 1..parsed.signer_count() {
+            if (0..i).any(|j| parsed.signer(j) == parsed.signer(i)) {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// CreateMultisig instruction: create an M-of-N multisig vault.
+pub struct CreateMultisig<'a> {
+    pub accounts: CreateMultisigAccounts<'a>,
+    pub instruction_data: CreateMultisigInstructionData<'a>,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for CreateMultisig<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = CreateMultisigAccounts::try_from(accounts)?;
+        let instruction_data = CreateMultisigInstructionData::try_from(data)?;
+
+        // Verify the PDA derived from [b"multisig", creator, seed].
+        let (multisig_key, bump) =
+            find_multisig_address(accounts.creator.key(), instruction_data.seed);
+        if accounts.multisig.key() != &multisig_key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+            bumps: [bump],
+        })
+    }
+}
+
+impl<'a> CreateMultisig<'a> {
+    /// CPI: create the PDA (PDA signs), then record threshold and signers.
+    pub fn process(&mut self) -> ProgramResult {
+        let seed_bytes = self.instruction_data.seed.to_le_bytes();
+        let seeds = [
+            Seed::from(b"multisig"),
+            Seed::from(self.accounts.creator.key().as_ref()),
+            Seed::from(&seed_bytes),
+            Seed::from(&self.bumps),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        CreateAccount {
+            from: self.accounts.creator,
+            to: self.accounts.multisig,
+            lamports: Rent::get()?.minimum_balance(MultisigVault::LEN),
+            space: MultisigVault::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+
+        let mut data = self.accounts.multisig.try_borrow_mut_data()?;
+        let multisig = MultisigVault::load_mut(&mut data)?;
+        multisig.creator = *self.accounts.creator.key();
+        multisig.seed = self.instruction_data.seed;
+        multisig.threshold = self.instruction_data.threshold;
+        multisig.signer_count = self.instruction_data.signer_count() as u8;
+        multisig.bump = self.bumps;
+        for i in 0..self.instruction_data.signer_count() {
+            multisig.signers[i] = self.instruction_data.signer(i);
+        }
+
+        Ok(())
+    }
+}
@@ -1,50 +1,56 @@
 use core::mem::size_of;
 use pinocchio::{
     program_error::ProgramError,
-    pubkey::find_program_address,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
     ProgramResult,
 };
 use pinocchio_system::instructions::Transfer;
 
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_vault, vault_state};
+use crate::state::VaultState;
+
 use pinocchio::account_info::AccountInfo;
 
-/// Deposit accounts: [owner (signer), vault PDA, system_program].
+/// Deposit accounts: [owner (signer), vault PDA, system_program, vault state?].
+/// Once the owner has initialized the vault state it records the deposit and the
+/// owner's activity.
 pub struct DepositAccounts<'a> {
     pub owner: &'a AccountInfo,
     pub vault: &'a AccountInfo,
+    pub state: Option<&'a AccountInfo>,
 }
 
 impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for DepositAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [owner, vault, _] = accounts else {
+        let [owner, vault, _, state @ ..] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
+        let (state, []) = vault_state(state, owner.key()) else {
+            return Err(ProgramError::InvalidArgument);
+        };
 
         // Owner must sign to authorize the transfer.
         if !owner.is_signer() {
             return Err(ProgramError::InvalidAccountOwner);
         }
 
-        // Vault must be a system-owned account (uninitialized PDA).
+        // Vault must be a system-owned account (uninitialized PDA). It may already
+        // hold lamports: repeat deposits top it up.
         if !vault.is_owned_by(&pinocchio_system::ID) {
             return Err(ProgramError::InvalidAccountOwner);
         }
 
-        // Vault must be empty to prevent double deposit.
-        if vault.lamports().ne(&0) {
-            return Err(ProgramError::InvalidAccountData);
-        }
-
         // Verify vault is the PDA derived from [b"vault", owner].
-        let (vault_key, _) =
-            find_program_address(&[b"vault", owner.key().as_ref()], &crate::ID);
-        if vault.key().ne(&vault_key) {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
+        check_vault(vault, owner.key(), state)?;
 
-        Ok(Self { owner, vault })
+        Ok(Self {
+            owner,
+            vault,
+            state,
+        })
     }
 }
 
@@ -72,6 +78,17 @@ impl<'a> core::convert::TryFrom<&'a [u8]> for DepositInstructionData {
     }
 }
 
+impl DepositInstructionData {
+    /// The first deposit into an empty vault must exceed `rent_exempt_minimum`, as
+    /// in the Anchor vault; top-ups of a funded vault may be any amount.
+    pub fn check_rent_exempt(&self, vault_balance: u64, rent_exempt_minimum: u64) -> ProgramResult {
+        if vault_balance == 0 && self.amount <= rent_exempt_minimum {
+            return Err(VaultError::NotRentExempt.into());
+        }
+        Ok(())
+    }
+}
+
 /// Deposit instruction: owner sends lamports to their vault PDA.
 pub struct Deposit<'a> {
     pub accounts: DepositAccounts<'a>,
@@ -95,6 +112,11 @@ impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for Deposit<'a> {
 impl<'a> Deposit<'a> {
     /// CPI: transfer lamports from owner to vault PDA.
     pub fn process(&mut self) -> ProgramResult {
+        self.instruction_data.check_rent_exempt(
+            self.accounts.vault.lamports(),
+            Rent::get()?.minimum_balance(0),
+        )?;
+
         Transfer {
             from: self.accounts.owner,
             to: self.accounts.vault,
@@ -102,6 +124,13 @@ impl<'a> Deposit<'a> {
         }
         .invoke()?;
 
+        if let Some(state) = self.accounts.state {
+            let mut data = state.try_borrow_mut_data()?;
+            let state = VaultState::load_mut(&mut data)?;
+            state.record_deposit(self.instruction_data.amount)?;
+            state.last_active = Clock::get()?.unix_timestamp;
+        }
+
         Ok(())
     }
 }
@@ -0,0 +1,112 @@
+use pinocchio::{
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::check_vault;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// ExecuteWithdraw accounts: [owner (signer), vault PDA, system_program, vault
+/// state], as for Withdraw. Bump stored for PDA signing.
+pub struct ExecuteWithdrawAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub state: &'a AccountInfo,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for ExecuteWithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, vault, _, state] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !vault.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // Verify the state belongs to the owner and the vault is theirs.
+        let bump = check_vault(vault, owner.key(), Some(state))?;
+
+        Ok(Self {
+            owner,
+            vault,
+            state,
+            bumps: [bump],
+        })
+    }
+}
+
+/// ExecuteWithdraw instruction: once unlocked, pay the pending amount from the
+/// vault to the owner. Whatever is left must stay rent-exempt, as for Withdraw.
+pub struct ExecuteWithdraw<'a> {
+    pub accounts: ExecuteWithdrawAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for ExecuteWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = ExecuteWithdrawAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> ExecuteWithdraw<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = {
+            let mut data = self.accounts.state.try_borrow_mut_data()?;
+            let state = VaultState::load_mut(&mut data)?;
+            let amount = state.pending_amount;
+            if amount == 0 {
+                return Err(VaultError::NoPendingWithdrawal.into());
+            }
+            let now = Clock::get()?.unix_timestamp;
+            if now < state.unlock_at {
+                return Err(VaultError::WithdrawalLocked.into());
+            }
+            state.pending_amount = 0;
+            state.unlock_at = 0;
+            state.record_withdrawal(amount)?;
+            state.last_active = now;
+            amount
+        };
+
+        let remaining = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        if remaining != 0 && remaining < Rent::get()?.minimum_balance(0) {
+            return Err(VaultError::NotRentExempt.into());
+        }
+
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.key().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        Transfer {
+            from: self.accounts.vault,
+            to: self.accounts.owner,
+            lamports: amount,
+        }
+        .invoke_signed(&signers)
+    }
+}
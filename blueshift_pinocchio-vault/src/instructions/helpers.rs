@@ -0,0 +1,211 @@
+//! PDA and state account helpers for the vault.
+
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction, Signer},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::{create_program_address, find_program_address, Pubkey},
+    ProgramResult,
+};
+
+use crate::state::VaultState;
+
+/// Derive the vault PDA and bump. Seeds: [b"vault", owner].
+pub fn find_vault_address(owner: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"vault", owner.as_ref()], &crate::ID)
+}
+
+/// Derive the token vault PDA and bump. Seeds: [b"vault", owner, mint]. It
+/// holds no data or lamports; it only owns the vault's token account.
+pub fn find_token_vault_address(owner: &Pubkey, mint: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"vault", owner.as_ref(), mint.as_ref()], &crate::ID)
+}
+
+/// Derive `wallet`'s associated token account for `mint` under `token_program`.
+pub fn find_associated_token_address(
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> (Pubkey, u8) {
+    find_program_address(
+        &[wallet.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+}
+
+/// Derive the vault state PDA and bump. Seeds: [b"state", owner].
+pub fn find_vault_state_address(owner: &Pubkey) -> (Pubkey, u8) {
+    find_program_address(&[b"state", owner.as_ref()], &crate::ID)
+}
+
+/// Derive the multisig vault PDA and bump. Seeds: [b"multisig", creator, seed_le_bytes].
+pub fn find_multisig_address(creator: &Pubkey, seed: u64) -> (Pubkey, u8) {
+    find_program_address(
+        &[b"multisig", creator.as_ref(), &seed.to_le_bytes()],
+        &crate::ID,
+    )
+}
+
+/// Split `owner`'s vault state off the optional trailing `accounts`, returning
+/// it, if initialized, and the accounts after it. The state may be left out, or
+/// be the uninitialized [b"state", owner] PDA, until the owner creates it; clients
+/// pass it from then on so its totals see the instruction. The program can't tell
+/// when an initialized state is left out.
+pub fn vault_state<'a>(
+    accounts: &'a [AccountInfo],
+    owner: &Pubkey,
+) -> (Option<&'a AccountInfo>, &'a [AccountInfo]) {
+    match accounts {
+        [state, rest @ ..] if state.is_owned_by(&crate::ID) => (Some(state), rest),
+        [state, rest @ ..] if state.key() == &find_vault_state_address(owner).0 => (None, rest),
+        _ => (None, accounts),
+    }
+}
+
+/// Check that `vault` is `owner`'s vault PDA and return its bump, read from
+/// `state` when one is passed instead of searching for it.
+pub fn check_vault(
+    vault: &AccountInfo,
+    owner: &Pubkey,
+    state: Option<&AccountInfo>,
+) -> Result<u8, ProgramError> {
+    let (vault_key, bump) = match state {
+        Some(state) => {
+            if !state.is_owned_by(&crate::ID) {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+            let data = state.try_borrow_data()?;
+            let state = VaultState::load(&data)?;
+            if &state.owner != owner {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+            let bump = state.vault_bump;
+            (
+                create_program_address(&[b"vault", owner.as_ref(), &bump], &crate::ID)?,
+                bump[0],
+            )
+        }
+        None => find_vault_address(owner),
+    };
+    if vault.key() != &vault_key {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    Ok(bump)
+}
+
+// TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA
+/// SPL Token program ID.
+pub const TOKEN_PROGRAM_ID: Pubkey = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xd7, 0x65, 0xa1, 0x93, 0xd9, 0xcb, 0xe1, 0x46, 0xce, 0xeb, 0x79, 0xac,
+    0x1c, 0xb4, 0x85, 0xed, 0x5f, 0x5b, 0x37, 0x91, 0x3a, 0x8c, 0xf5, 0x85, 0x7e, 0xff, 0x00, 0xa9,
+];
+
+// TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+/// Token-2022 program ID.
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = [
+    0x06, 0xdd, 0xf6, 0xe1, 0xee, 0x75, 0x8f, 0xde, 0x18, 0x42, 0x5d, 0xbc, 0xe4, 0x6c, 0xcd, 0xda,
+    0xb6, 0x1a, 0xfc, 0x4d, 0x83, 0xb9, 0x0d, 0x27, 0xfe, 0xbd, 0xf9, 0x28, 0xd8, 0xa1, 0x8b, 0xfc,
+];
+
+// ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL
+/// Associated token account program ID.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = [
+    0x8c, 0x97, 0x25, 0x8f, 0x4e, 0x24, 0x89, 0xf1, 0xbb, 0x3d, 0x10, 0x29, 0x14, 0x8e, 0x0d, 0x83,
+    0x0b, 0x5a, 0x13, 0x99, 0xda, 0xff, 0x10, 0x84, 0x04, 0x8e, 0x7b, 0xd8, 0xdb, 0xe9, 0xf8, 0x59,
+];
+
+/// Length of an SPL token account; Token-2022 accounts with extensions are longer
+/// but share this prefix.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+
+/// Check that `account` is a token account of `token_program` for `mint`, owned by
+/// `authority` if one is given, and return its balance. Layout: mint [0..32],
+/// owner [32..64], amount [64..72].
+pub fn check_token_account(
+    account: &AccountInfo,
+    token_program: &Pubkey,
+    mint: &Pubkey,
+    authority: Option<&Pubkey>,
+) -> Result<u64, ProgramError> {
+    if !account.is_owned_by(token_program) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let data = account.try_borrow_data()?;
+    if data.len() < TOKEN_ACCOUNT_LEN {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if &data[0..32] != mint.as_ref() {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    if authority.is_some_and(|authority| &data[32..64] != authority.as_ref()) {
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+/// Read a mint's decimals (byte 44), checking it belongs to `token_program`.
+pub fn mint_decimals(mint: &AccountInfo, token_program: &Pubkey) -> Result<u8, ProgramError> {
+    if !mint.is_owned_by(token_program) {
+        return Err(ProgramError::InvalidAccountOwner);
+    }
+    let data = mint.try_borrow_data()?;
+    data.get(44)
+        .copied()
+        .ok_or(ProgramError::InvalidAccountData)
+}
+
+/// TransferChecked CPI to either token program; instruction 12 is the same in
+/// SPL Token and Token-2022.
+///
+/// ### Accounts:
+///   0. `[WRITE]` Source token account
+///   1. `[]` Mint
+///   2. `[WRITE]` Destination token account
+///   3. `[SIGNER]` Source account's owner
+pub struct TransferChecked<'a> {
+    pub from: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub to: &'a AccountInfo,
+    pub authority: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TransferChecked<'_> {
+    #[inline(always)]
+    pub fn invoke(&self) -> ProgramResult {
+        self.invoke_signed(&[])
+    }
+
+    pub fn invoke_signed(&self, signers: &[Signer]) -> ProgramResult {
+        let account_metas: [AccountMeta; 4] = [
+            AccountMeta::writable(self.from.key()),
+            AccountMeta::readonly(self.mint.key()),
+            AccountMeta::writable(self.to.key()),
+            AccountMeta::readonly_signer(self.authority.key()),
+        ];
+
+        // instruction data
+        // -  [0]   : instruction discriminator
+        // -  [1..9]: amount
+        // -  [9]   : decimals
+        let mut instruction_data = [0; 10];
+        instruction_data[0] = 12;
+        instruction_data[1..9].copy_from_slice(&self.amount.to_le_bytes());
+        instruction_data[9] = self.decimals;
+
+        let instruction = Instruction {
+            program_id: self.token_program.key(),
+            accounts: &account_metas,
+            data: &instruction_data,
+        };
+
+        invoke_signed(
+            &instruction,
+            &[self.from, self.mint, self.to, self.authority],
+            signers,
+        )
+    }
+}
@@ -0,0 +1,99 @@
+use pinocchio::{
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::CreateAccount;
+
+use crate::instructions::helpers::{find_vault_address, find_vault_state_address};
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// Initialize accounts: [owner (signer), vault PDA, vault state PDA, system_program].
+pub struct InitializeAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub state: &'a AccountInfo,
+    pub vault_bump: [u8; 1],
+    pub bump: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for InitializeAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, vault, state, _] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Owner signs and pays for the state account.
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // Verify the vault and state PDAs; their bumps are stored in the state.
+        let (vault_key, vault_bump) = find_vault_address(owner.key());
+        if vault.key() != &vault_key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let (state_key, bump) = find_vault_state_address(owner.key());
+        if state.key() != &state_key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self {
+            owner,
+            state,
+            vault_bump: [vault_bump],
+            bump: [bump],
+        })
+    }
+}
+
+/// Initialize instruction: create the owner's vault state account. The vault
+/// itself may already be funded; the state's totals start at zero.
+pub struct Initialize<'a> {
+    pub accounts: InitializeAccounts<'a>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Initialize<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let accounts = InitializeAccounts::try_from(accounts)?;
+
+        Ok(Self { accounts })
+    }
+}
+
+impl<'a> Initialize<'a> {
+    /// CPI: create the state PDA (state PDA signs), then record owner, bumps and time.
+    pub fn process(&mut self) -> ProgramResult {
+        let seeds = [
+            Seed::from(b"state"),
+            Seed::from(self.accounts.owner.key().as_ref()),
+            Seed::from(&self.accounts.bump),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        CreateAccount {
+            from: self.accounts.owner,
+            to: self.accounts.state,
+            lamports: Rent::get()?.minimum_balance(VaultState::LEN),
+            space: VaultState::LEN as u64,
+            owner: &crate::ID,
+        }
+        .invoke_signed(&signers)?;
+
+        let mut data = self.accounts.state.try_borrow_mut_data()?;
+        VaultState::load_mut(&mut data)?.set_inner(
+            *self.accounts.owner.key(),
+            Clock::get()?.unix_timestamp,
+            self.accounts.vault_bump,
+            self.accounts.bump,
+        );
+
+        Ok(())
+    }
+}
@@ -1,5 +1,34 @@
+pub mod cancel_withdraw;
+pub mod claim;
+pub mod close;
+pub mod create_multisig;
 pub mod deposit;
+pub mod execute_withdraw;
+pub mod helpers;
+pub mod initialize;
+pub mod multisig_withdraw;
+pub mod request_withdraw;
+pub mod set_beneficiary;
+pub mod set_spending_limit;
+pub mod set_timelock;
+pub mod spend;
+pub mod token_deposit;
+pub mod token_withdraw;
 pub mod withdraw;
 
+pub use cancel_withdraw::*;
+pub use claim::*;
+pub use close::*;
+pub use create_multisig::*;
 pub use deposit::*;
+pub use execute_withdraw::*;
+pub use initialize::*;
+pub use multisig_withdraw::*;
+pub use request_withdraw::*;
+pub use set_beneficiary::*;
+pub use set_spending_limit::*;
+pub use set_timelock::*;
+pub use spend::*;
+pub use token_deposit::*;
+pub use token_withdraw::*;
 pub use withdraw::*;
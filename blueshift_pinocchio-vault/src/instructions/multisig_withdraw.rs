@@ -0,0 +1,132 @@
+use core::mem::size_of;
+use pinocchio::{
+    program_error::ProgramError,
+    sysvars::{rent::Rent, Sysvar},
+    ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::state::MultisigVault;
+
+use pinocchio::account_info::AccountInfo;
+
+/// MultisigWithdraw accounts: [multisig vault, recipient, signers...]. At least
+/// the vault's threshold of its signers must sign; other accounts are ignored.
+pub struct MultisigWithdrawAccounts<'a> {
+    pub multisig: &'a AccountInfo,
+    pub recipient: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for MultisigWithdrawAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [multisig, recipient, signers @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // The account is only ever created at a multisig PDA by CreateMultisig.
+        if !multisig.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let data = multisig.try_borrow_data()?;
+        let state = MultisigVault::load(&data)?;
+
+        // Count each listed signer once, however many times it is passed.
+        let approvals = state
+            .signers()
+            .iter()
+            .filter(|key| {
+                signers
+                    .iter()
+                    .any(|account| account.is_signer() && account.key() == *key)
+            })
+            .count();
+        if approvals < state.threshold as usize {
+            return Err(VaultError::NotEnoughSigners.into());
+        }
+
+        Ok(Self {
+            multisig,
+            recipient,
+        })
+    }
+}
+
+/// MultisigWithdraw instruction data: lamport amount (u64, little-endian).
+pub struct MultisigWithdrawInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for MultisigWithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        // Reject zero amount.
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// MultisigWithdraw instruction: pay `amount` from the multisig vault to the
+/// recipient the signers agreed on. Withdrawing the whole balance closes the
+/// vault; anything less must leave it rent-exempt.
+///
+/// The multisig vault has no single owner, so this isn't the creator's or any
+/// signer's activity: their own vault states, even if passed, are left untouched.
+pub struct MultisigWithdraw<'a> {
+    pub accounts: MultisigWithdrawAccounts<'a>,
+    pub instruction_data: MultisigWithdrawInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for MultisigWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = MultisigWithdrawAccounts::try_from(accounts)?;
+        let instruction_data = MultisigWithdrawInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> MultisigWithdraw<'a> {
+    /// The vault is program-owned, so lamports move directly, without a CPI.
+    pub fn process(&mut self) -> ProgramResult {
+        let multisig = self.accounts.multisig;
+        let recipient = self.accounts.recipient;
+        let amount = self.instruction_data.amount;
+
+        let remaining = multisig
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        if remaining != 0 && remaining < Rent::get()?.minimum_balance(MultisigVault::LEN) {
+            return Err(VaultError::NotRentExempt.into());
+        }
+
+        *recipient.try_borrow_mut_lamports()? = recipient
+            .lamports()
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        *multisig.try_borrow_mut_lamports()? = remaining;
+
+        if remaining == 0 {
+            multisig.close()?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use core::mem::size_of;
+use pinocchio::{
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// Withdrawal delay accounts: [owner (signer), vault state]. Shared by
+/// SetTimelock, RequestWithdraw and CancelWithdraw.
+pub struct TimelockAccounts<'a> {
+    pub state: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TimelockAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, state] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the vault owner may set the delay, request or cancel.
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !state.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if VaultState::load(&state.try_borrow_data()?)?.owner != *owner.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { state })
+    }
+}
+
+/// RequestWithdraw instruction data: lamport amount (u64, little-endian).
+pub struct RequestWithdrawInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for RequestWithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        // Reject zero amount.
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// RequestWithdraw instruction: start the delay for withdrawing `amount` from the
+/// vault. Replaces any pending request, restarting the delay. Only while a delay
+/// is set; without one, `Withdraw` pays out directly.
+pub struct RequestWithdraw<'a> {
+    pub accounts: TimelockAccounts<'a>,
+    pub instruction_data: RequestWithdrawInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for RequestWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = TimelockAccounts::try_from(accounts)?;
+        let instruction_data = RequestWithdrawInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> RequestWithdraw<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let now = Clock::get()?.unix_timestamp;
+
+        let mut data = self.accounts.state.try_borrow_mut_data()?;
+        let state = VaultState::load_mut(&mut data)?;
+        if state.delay == 0 {
+            return Err(VaultError::TimelockNotSet.into());
+        }
+        state.pending_amount = self.instruction_data.amount;
+        state.unlock_at = now.saturating_add(state.delay);
+        state.last_active = now;
+
+        Ok(())
+    }
+}
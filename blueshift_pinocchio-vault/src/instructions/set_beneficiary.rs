@@ -0,0 +1,108 @@
+use core::mem::size_of;
+use pinocchio::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// SetBeneficiary accounts: [owner (signer), vault state].
+pub struct SetBeneficiaryAccounts<'a> {
+    pub state: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for SetBeneficiaryAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, state] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !state.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if VaultState::load(&state.try_borrow_data()?)?.owner != *owner.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { state })
+    }
+}
+
+/// SetBeneficiary instruction data: beneficiary (32 bytes) and inactivity period
+/// (i64, seconds, little-endian). A zero period turns recovery off.
+pub struct SetBeneficiaryInstructionData {
+    pub beneficiary: Pubkey,
+    pub inactivity_period: i64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for SetBeneficiaryInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Pubkey>() + size_of::<i64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let beneficiary: Pubkey = data[0..32].try_into().unwrap();
+        let inactivity_period = i64::from_le_bytes(data[32..40].try_into().unwrap());
+
+        if inactivity_period < 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            beneficiary,
+            inactivity_period,
+        })
+    }
+}
+
+/// SetBeneficiary instruction: owner sets who may claim the vault and after how
+/// long without owner activity. Sending it unchanged just marks the owner active.
+/// Once a withdrawal delay is set, recovery is unavailable and a nonzero period is
+/// refused.
+pub struct SetBeneficiary<'a> {
+    pub accounts: SetBeneficiaryAccounts<'a>,
+    pub instruction_data: SetBeneficiaryInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for SetBeneficiary<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetBeneficiaryAccounts::try_from(accounts)?;
+        let instruction_data = SetBeneficiaryInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetBeneficiary<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut_data()?;
+        let state = VaultState::load_mut(&mut data)?;
+        // Claim is refused under a withdrawal delay; only turning it off is allowed.
+        if state.delay != 0 && self.instruction_data.inactivity_period != 0 {
+            return Err(VaultError::WithdrawalLocked.into());
+        }
+        state.beneficiary = self.instruction_data.beneficiary;
+        state.inactivity_period = self.instruction_data.inactivity_period;
+        state.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
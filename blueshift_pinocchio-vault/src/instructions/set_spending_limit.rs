@@ -0,0 +1,115 @@
+use core::mem::size_of;
+use pinocchio::{
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// SetSpendingLimit accounts: [owner (signer), vault state].
+pub struct SetSpendingLimitAccounts<'a> {
+    pub state: &'a AccountInfo,
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for SetSpendingLimitAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, state] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+
+        // Only the vault owner may change who spends and how much.
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !state.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if VaultState::load(&state.try_borrow_data()?)?.owner != *owner.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        Ok(Self { state })
+    }
+}
+
+/// SetSpendingLimit instruction data: spender (32 bytes), limit (u64) and period
+/// (i64, seconds), little-endian. A zero limit turns spending off; otherwise the
+/// period must be positive.
+pub struct SetSpendingLimitInstructionData {
+    pub spender: Pubkey,
+    pub limit: u64,
+    pub period: i64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for SetSpendingLimitInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<Pubkey>() + size_of::<u64>() + size_of::<i64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let spender: Pubkey = data[0..32].try_into().unwrap();
+        let limit = u64::from_le_bytes(data[32..40].try_into().unwrap());
+        let period = i64::from_le_bytes(data[40..48].try_into().unwrap());
+
+        if limit != 0 && period <= 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self {
+            spender,
+            limit,
+            period,
+        })
+    }
+}
+
+/// SetSpendingLimit instruction: owner sets the vault's spender, limit and period,
+/// starting a fresh window. Once a withdrawal delay is set, spending is
+/// unavailable and a nonzero limit is refused.
+pub struct SetSpendingLimit<'a> {
+    pub accounts: SetSpendingLimitAccounts<'a>,
+    pub instruction_data: SetSpendingLimitInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for SetSpendingLimit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SetSpendingLimitAccounts::try_from(accounts)?;
+        let instruction_data = SetSpendingLimitInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetSpendingLimit<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut_data()?;
+        let state = VaultState::load_mut(&mut data)?;
+        // Spend is refused under a withdrawal delay; only turning it off is allowed.
+        if state.delay != 0 && self.instruction_data.limit != 0 {
+            return Err(VaultError::WithdrawalLocked.into());
+        }
+        state.spender = self.instruction_data.spender;
+        state.spending_limit = self.instruction_data.limit;
+        state.period = self.instruction_data.period;
+        state.window_start = Clock::get()?.unix_timestamp;
+        state.window_spent = 0;
+        state.last_active = state.window_start;
+
+        Ok(())
+    }
+}
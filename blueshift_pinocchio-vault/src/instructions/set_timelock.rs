@@ -0,0 +1,72 @@
+use core::mem::size_of;
+use pinocchio::{
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::errors::VaultError;
+use crate::instructions::request_withdraw::TimelockAccounts;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// SetTimelock instruction data: withdrawal delay in seconds (i64, little-endian).
+pub struct SetTimelockInstructionData {
+    pub delay: i64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for SetTimelockInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<i64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let delay = i64::from_le_bytes(data.try_into().unwrap());
+
+        // A delay of zero would make the lock pointless.
+        if delay <= 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { delay })
+    }
+}
+
+/// SetTimelock instruction: owner sets the vault's withdrawal delay. The delay
+/// can be raised but never lowered, so it can't be shortened to get at the
+/// lamports sooner.
+pub struct SetTimelock<'a> {
+    pub accounts: TimelockAccounts<'a>,
+    pub instruction_data: SetTimelockInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for SetTimelock<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = TimelockAccounts::try_from(accounts)?;
+        let instruction_data = SetTimelockInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> SetTimelock<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let mut data = self.accounts.state.try_borrow_mut_data()?;
+        let state = VaultState::load_mut(&mut data)?;
+        if self.instruction_data.delay < state.delay {
+            return Err(VaultError::WithdrawalLocked.into());
+        }
+        state.delay = self.instruction_data.delay;
+        state.last_active = Clock::get()?.unix_timestamp;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+use core::mem::size_of;
+use pinocchio::{
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
+    ProgramResult,
+};
+use pinocchio_system::instructions::Transfer;
+
+use crate::errors::VaultError;
+use crate::instructions::helpers::check_vault;
+use crate::state::VaultState;
+
+use pinocchio::account_info::AccountInfo;
+
+/// Spend accounts: [spender (signer), owner, vault PDA, system_program, vault state,
+/// recipient?]. The lamports go to the recipient, a system-owned wallet, or to the
+/// spender without one.
+/// The owner need only sign for spends past the limit.
+pub struct SpendAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub state: &'a AccountInfo,
+    pub recipient: &'a AccountInfo,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for SpendAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [spender, owner, vault, _, state, recipient @ ..] = accounts else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let recipient = match recipient {
+            [] => spender,
+            // A recipient is a wallet, as for Withdraw.
+            [recipient] if recipient.is_owned_by(&pinocchio_system::ID) => recipient,
+            [_] => return Err(ProgramError::InvalidAccountOwner),
+            _ => return Err(ProgramError::InvalidArgument),
+        };
+
+        if !spender.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        if !vault.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // The state must name this spender; check_vault then ties it to the owner
+        // and verifies the vault PDA.
+        if !state.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if VaultState::load(&state.try_borrow_data()?)?.spender != *spender.key() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let bump = check_vault(vault, owner.key(), Some(state))?;
+
+        Ok(Self {
+            owner,
+            vault,
+            state,
+            recipient,
+            bumps: [bump],
+        })
+    }
+}
+
+/// Spend instruction data: lamport amount (u64, little-endian).
+pub struct SpendInstructionData {
+    pub amount: u64,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for SpendInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        if data.len() != size_of::<u64>() {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+        // Reject zero amount.
+        if amount == 0 {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+
+        Ok(Self { amount })
+    }
+}
+
+/// Spend instruction: the vault's spender takes lamports from it, within the
+/// window's limit unless the owner co-signs (PDA signs). Counts as a withdrawal
+/// in the state's totals. Refused while a withdrawal delay is set.
+pub struct Spend<'a> {
+    pub accounts: SpendAccounts<'a>,
+    pub instruction_data: SpendInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for Spend<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = SpendAccounts::try_from(accounts)?;
+        let instruction_data = SpendInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> Spend<'a> {
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self.instruction_data.amount;
+
+        // As with Withdraw, whatever is left must stay rent-exempt.
+        let remaining = self
+            .accounts
+            .vault
+            .lamports()
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        if remaining != 0 && remaining < Rent::get()?.minimum_balance(0) {
+            return Err(VaultError::NotRentExempt.into());
+        }
+
+        {
+            let mut data = self.accounts.state.try_borrow_mut_data()?;
+            let state = VaultState::load_mut(&mut data)?;
+            // As with Withdraw, a delay leaves RequestWithdraw as the only way out.
+            if state.delay != 0 {
+                return Err(VaultError::WithdrawalLocked.into());
+            }
+            let now = Clock::get()?.unix_timestamp;
+            let cosigned = self.accounts.owner.is_signer();
+            state.record_spend(amount, now, cosigned)?;
+            state.record_withdrawal(amount)?;
+            // The owner co-signing is owner activity; the spender alone is not.
+            if cosigned {
+                state.last_active = now;
+            }
+        }
+
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.key().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        Transfer {
+            from: self.accounts.vault,
+            to: self.accounts.recipient,
+            lamports: amount,
+        }
+        .invoke_signed(&signers)
+    }
+}
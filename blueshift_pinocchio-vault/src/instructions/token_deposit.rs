@@ -0,0 +1,170 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    program_error::ProgramError,
+    sysvars::{clock::Clock, Sysvar},
+    ProgramResult,
+};
+
+use crate::instructions::deposit::DepositInstructionData;
+use crate::instructions::helpers::{
+    check_token_account, find_associated_token_address, find_token_vault_address, mint_decimals,
+    vault_state, TransferChecked, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID,
+};
+use crate::state::VaultState;
+
+/// Token vault accounts, shared by TokenDeposit and TokenWithdraw: [owner (signer),
+/// token vault PDA, mint, owner_token_account, vault_token_account, token_program,
+/// vault state?, recipient_token_account?]. The vault token account is the
+/// PDA's associated token account, created idempotently by the client, and must
+/// already exist. As for Deposit, an initialized vault state records the owner's
+/// activity. Only TokenWithdraw takes a recipient: any token account for the
+/// mint, paid instead of the owner's. Bump stored for PDA signing.
+pub struct TokenVaultAccounts<'a> {
+    pub owner: &'a AccountInfo,
+    pub vault: &'a AccountInfo,
+    pub mint: &'a AccountInfo,
+    pub owner_token_account: &'a AccountInfo,
+    pub vault_token_account: &'a AccountInfo,
+    pub token_program: &'a AccountInfo,
+    pub state: Option<&'a AccountInfo>,
+    pub recipient_token_account: Option<&'a AccountInfo>,
+    /// Balance of the vault token account.
+    pub vault_balance: u64,
+    pub decimals: u8,
+    pub bumps: [u8; 1],
+}
+
+impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for TokenVaultAccounts<'a> {
+    type Error = ProgramError;
+
+    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+        let [owner, vault, mint, owner_token_account, vault_token_account, token_program, rest @ ..] =
+            accounts
+        else {
+            return Err(ProgramError::NotEnoughAccountKeys);
+        };
+        let (state, recipient_token_account) = vault_state(rest, owner.key());
+        let recipient_token_account = match recipient_token_account {
+            [] => None,
+            [recipient_token_account] => Some(recipient_token_account),
+            _ => return Err(ProgramError::InvalidArgument),
+        };
+
+        if !owner.is_signer() {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+
+        // SPL Token or Token-2022.
+        if token_program.key() != &TOKEN_PROGRAM_ID && token_program.key() != &TOKEN_2022_PROGRAM_ID
+        {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+
+        let decimals = mint_decimals(mint, token_program.key())?;
+        check_token_account(
+            owner_token_account,
+            token_program.key(),
+            mint.key(),
+            Some(owner.key()),
+        )?;
+        let vault_balance = check_token_account(
+            vault_token_account,
+            token_program.key(),
+            mint.key(),
+            Some(vault.key()),
+        )?;
+        if let Some(recipient_token_account) = recipient_token_account {
+            check_token_account(
+                recipient_token_account,
+                token_program.key(),
+                mint.key(),
+                None,
+            )?;
+        }
+
+        // Verify vault is the PDA derived from [b"vault", owner, mint], and the
+        // vault token account its associated token account.
+        let (vault_key, bump) = find_token_vault_address(owner.key(), mint.key());
+        if vault.key() != &vault_key {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        let (vault_token_account_key, _) =
+            find_associated_token_address(vault.key(), mint.key(), token_program.key());
+        if vault_token_account.key() != &vault_token_account_key {
+            return Err(ProgramError::InvalidSeeds);
+        }
+
+        if let Some(state) = state {
+            if VaultState::load(&state.try_borrow_data()?)?.owner != *owner.key() {
+                return Err(ProgramError::InvalidAccountOwner);
+            }
+        }
+
+        Ok(Self {
+            owner,
+            vault,
+            mint,
+            owner_token_account,
+            vault_token_account,
+            token_program,
+            state,
+            recipient_token_account,
+            vault_balance,
+            decimals,
+            bumps: [bump],
+        })
+    }
+}
+
+impl<'a> TokenVaultAccounts<'a> {
+    /// Record the owner's activity in their vault state, if they have one.
+    pub fn touch_state(&self) -> ProgramResult {
+        if let Some(state) = self.state {
+            let mut data = state.try_borrow_mut_data()?;
+            VaultState::load_mut(&mut data)?.last_active = Clock::get()?.unix_timestamp;
+        }
+        Ok(())
+    }
+}
+
+/// TokenDeposit instruction: owner sends `amount` tokens to their token vault.
+/// Same data as Deposit.
+pub struct TokenDeposit<'a> {
+    pub accounts: TokenVaultAccounts<'a>,
+    pub instruction_data: DepositInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for TokenDeposit<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = TokenVaultAccounts::try_from(accounts)?;
+        if accounts.recipient_token_account.is_some() {
+            return Err(ProgramError::InvalidArgument);
+        }
+        let instruction_data = DepositInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> TokenDeposit<'a> {
+    /// CPI: TransferChecked from the owner's token account to the vault's.
+    pub fn process(&mut self) -> ProgramResult {
+        TransferChecked {
+            from: self.accounts.owner_token_account,
+            mint: self.accounts.mint,
+            to: self.accounts.vault_token_account,
+            authority: self.accounts.owner,
+            token_program: self.accounts.token_program,
+            amount: self.instruction_data.amount,
+            decimals: self.accounts.decimals,
+        }
+        .invoke()?;
+
+        self.accounts.touch_state()
+    }
+}
@@ -0,0 +1,66 @@
+use pinocchio::{
+    account_info::AccountInfo,
+    instruction::{Seed, Signer},
+    program_error::ProgramError,
+    ProgramResult,
+};
+
+use crate::instructions::helpers::TransferChecked;
+use crate::instructions::token_deposit::TokenVaultAccounts;
+use crate::instructions::withdraw::WithdrawInstructionData;
+
+/// TokenWithdraw instruction: owner takes tokens from their token vault, to their
+/// own token account or a recipient's (PDA signs). Same data as Withdraw: empty
+/// for the whole balance, or an amount.
+pub struct TokenWithdraw<'a> {
+    pub accounts: TokenVaultAccounts<'a>,
+    pub instruction_data: WithdrawInstructionData,
+}
+
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for TokenWithdraw<'a> {
+    type Error = ProgramError;
+
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
+        let accounts = TokenVaultAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawInstructionData::try_from(data)?;
+
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
+    }
+}
+
+impl<'a> TokenWithdraw<'a> {
+    /// CPI: TransferChecked from the vault's token account to the recipient's, or
+    /// the owner's without one.
+    pub fn process(&mut self) -> ProgramResult {
+        let amount = self
+            .instruction_data
+            .token_amount(self.accounts.vault_balance)?;
+
+        let seeds = [
+            Seed::from(b"vault"),
+            Seed::from(self.accounts.owner.key().as_ref()),
+            Seed::from(self.accounts.mint.key().as_ref()),
+            Seed::from(&self.accounts.bumps),
+        ];
+        let signers = [Signer::from(&seeds)];
+
+        TransferChecked {
+            from: self.accounts.vault_token_account,
+            mint: self.accounts.mint,
+            to: self
+                .accounts
+                .recipient_token_account
+                .unwrap_or(self.accounts.owner_token_account),
+            authority: self.accounts.vault,
+            token_program: self.accounts.token_program,
+            amount,
+            decimals: self.accounts.decimals,
+        }
+        .invoke_signed(&signers)?;
+
+        self.accounts.touch_state()
+    }
+}
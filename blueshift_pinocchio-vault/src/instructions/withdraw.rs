@@ -1,16 +1,27 @@
+use core::mem::size_of;
 use pinocchio::{
     instruction::{Seed, Signer},
     program_error::ProgramError,
+    sysvars::{clock::Clock, rent::Rent, Sysvar},
     ProgramResult,
 };
 use pinocchio_system::instructions::Transfer;
 
+use crate::errors::VaultError;
+use crate::instructions::helpers::{check_vault, vault_state};
+use crate::state::VaultState;
+
 use pinocchio::account_info::AccountInfo;
 
-/// Withdraw accounts: [owner (signer), vault PDA, system_program]. Bump stored for PDA signing.
+/// Withdraw accounts: [owner (signer), vault PDA, system_program, vault state?,
+/// recipient?]. Once initialized, the vault state records the withdrawal and the
+/// owner's activity; the lamports go to the recipient, a system-owned wallet, or
+/// to the owner without one. Bump stored for PDA signing.
 pub struct WithdrawAccounts<'a> {
     pub owner: &'a AccountInfo,
     pub vault: &'a AccountInfo,
+    pub state: Option<&'a AccountInfo>,
+    pub recipient: &'a AccountInfo,
     pub bumps: [u8; 1],
 }
 
@@ -18,11 +29,19 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
     type Error = ProgramError;
 
     fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
-        let [owner, vault, _] = accounts else {
+        let [owner, vault, _, rest @ ..] = accounts else {
             return Err(ProgramError::NotEnoughAccountKeys);
         };
+        let (state, recipient) = vault_state(rest, owner.key());
+        let recipient = match recipient {
+            [] => owner,
+            // A recipient is a wallet, as in the Anchor vault.
+            [recipient] if recipient.is_owned_by(&pinocchio_system::ID) => recipient,
+            [_] => return Err(ProgramError::InvalidAccountOwner),
+            _ => return Err(ProgramError::InvalidArgument),
+        };
 
-        // Only the vault owner may withdraw.
+        // Only the vault owner may withdraw, wherever the lamports go.
         if !owner.is_signer() {
             return Err(ProgramError::InvalidAccountOwner);
         }
@@ -37,38 +56,120 @@ impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for WithdrawAccounts<'a> {
         }
 
         // Verify vault PDA and get bump for invoke_signed.
-        let (vault_key, bump) =
-            pinocchio::pubkey::find_program_address(&[b"vault", owner.key().as_ref()], &crate::ID);
-        if vault.key() != &vault_key {
-            return Err(ProgramError::InvalidAccountOwner);
-        }
+        let bump = check_vault(vault, owner.key(), state)?;
 
         Ok(Self {
             owner,
             vault,
+            state,
+            recipient,
             bumps: [bump],
         })
     }
 }
 
-/// Withdraw instruction: owner drains vault PDA back to themselves (PDA signs).
+/// Withdraw instruction data: empty to drain the vault, or a lamport amount
+/// (u64, little-endian) for a partial withdrawal.
+pub struct WithdrawInstructionData {
+    pub amount: Option<u64>,
+}
+
+impl<'a> core::convert::TryFrom<&'a [u8]> for WithdrawInstructionData {
+    type Error = ProgramError;
+
+    fn try_from(data: &'a [u8]) -> Result<Self, Self::Error> {
+        match data.len() {
+            0 => Ok(Self { amount: None }),
+            len if len == size_of::<u64>() => {
+                let amount = u64::from_le_bytes(data.try_into().unwrap());
+
+                // Reject zero amount.
+                if amount == 0 {
+                    return Err(ProgramError::InvalidInstructionData);
+                }
+
+                Ok(Self {
+                    amount: Some(amount),
+                })
+            }
+            _ => Err(ProgramError::InvalidInstructionData),
+        }
+    }
+}
+
+impl WithdrawInstructionData {
+    /// Lamports to transfer out of a vault holding `balance`: the requested amount,
+    /// or everything. A partial withdrawal must leave at least
+    /// `rent_exempt_minimum`.
+    pub fn lamports(&self, balance: u64, rent_exempt_minimum: u64) -> Result<u64, ProgramError> {
+        let Some(amount) = self.amount else {
+            return Ok(balance);
+        };
+
+        let remaining = balance
+            .checked_sub(amount)
+            .ok_or(ProgramError::InsufficientFunds)?;
+        if remaining != 0 && remaining < rent_exempt_minimum {
+            return Err(VaultError::NotRentExempt.into());
+        }
+
+        Ok(amount)
+    }
+
+    /// Tokens to transfer out of a token vault holding `vault_balance`: the
+    /// requested amount, or everything. There is no rent to keep, but an empty
+    /// vault has nothing to withdraw.
+    pub fn token_amount(&self, vault_balance: u64) -> Result<u64, ProgramError> {
+        let amount = self.amount.unwrap_or(vault_balance);
+        if amount == 0 || amount > vault_balance {
+            return Err(ProgramError::InsufficientFunds);
+        }
+        Ok(amount)
+    }
+}
+
+/// Withdraw instruction: owner takes lamports from vault PDA, to themselves or a
+/// recipient (PDA signs).
 pub struct Withdraw<'a> {
     pub accounts: WithdrawAccounts<'a>,
+    pub instruction_data: WithdrawInstructionData,
 }
 
-impl<'a> core::convert::TryFrom<&'a [AccountInfo]> for Withdraw<'a> {
+impl<'a> core::convert::TryFrom<(&'a [u8], &'a [AccountInfo])> for Withdraw<'a> {
     type Error = ProgramError;
 
-    fn try_from(accounts: &'a [AccountInfo]) -> Result<Self, Self::Error> {
+    fn try_from((data, accounts): (&'a [u8], &'a [AccountInfo])) -> Result<Self, Self::Error> {
         let accounts = WithdrawAccounts::try_from(accounts)?;
+        let instruction_data = WithdrawInstructionData::try_from(data)?;
 
-        Ok(Self { accounts })
+        Ok(Self {
+            accounts,
+            instruction_data,
+        })
     }
 }
 
 impl<'a> Withdraw<'a> {
-    /// PDA signs: transfer all lamports from vault back to owner via invoke_signed.
+    /// PDA signs: transfer lamports from vault to the recipient via invoke_signed.
+    /// A partial withdrawal must leave the vault rent-exempt.
     pub fn process(&mut self) -> ProgramResult {
+        let lamports = self.instruction_data.lamports(
+            self.accounts.vault.lamports(),
+            Rent::get()?.minimum_balance(0),
+        )?;
+
+        if let Some(state) = self.accounts.state {
+            let mut data = state.try_borrow_mut_data()?;
+            let state = VaultState::load_mut(&mut data)?;
+            // With a delay set, lamports leave only via RequestWithdraw and
+            // ExecuteWithdraw.
+            if state.delay != 0 {
+                return Err(VaultError::WithdrawalLocked.into());
+            }
+            state.record_withdrawal(lamports)?;
+            state.last_active = Clock::get()?.unix_timestamp;
+        }
+
         let seeds = [
             Seed::from(b"vault"),
             Seed::from(self.accounts.owner.key().as_ref()),
@@ -78,11 +179,9 @@ impl<'a> Withdraw<'a> {
 
         Transfer {
             from: self.accounts.vault,
-            to: self.accounts.owner,
-            lamports: self.accounts.vault.lamports(),
+            to: self.accounts.recipient,
+            lamports,
         }
-        .invoke_signed(&signers)?;
-
-        Ok(())
+        .invoke_signed(&signers)
     }
 }
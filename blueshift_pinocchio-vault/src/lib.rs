@@ -2,19 +2,31 @@
 
 use pinocchio::{
     account_info::AccountInfo,
-    entrypoint,
     program_error::ProgramError,
     pubkey::Pubkey,
     ProgramResult,
 };
 
+#[cfg(not(feature = "no-entrypoint"))]
+use pinocchio::entrypoint;
+
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
-#[cfg(not(target_arch = "bpfel"))]
+#[cfg(not(feature = "no-entrypoint"))]
 pinocchio::nostd_panic_handler!();
 
+// Without the entrypoint the host-side cdylib still needs a panic handler; the
+// program embedding this crate provides its own on-chain.
+#[cfg(all(feature = "no-entrypoint", not(target_os = "solana")))]
+extern crate std;
+
+pub mod errors;
 pub mod instructions;
+pub mod state;
+pub use errors::*;
 pub use instructions::*;
+pub use state::*;
 
 // 22222222222222222222222222222222222222222222
 /// Program ID (32 bytes). Replace with your deployed program ID for production.
@@ -23,15 +35,33 @@ pub const ID: Pubkey = [
     0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07, 0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
 ];
 
-/// Entrypoint: route by first byte (discriminator). 0 = Deposit, 1 = Withdraw.
-fn process_instruction(
+/// Entrypoint: route by first byte (discriminator). 0 = Deposit, 1 = Withdraw,
+/// 2 = Initialize (vault state), 3 = Close, 4 = SetTimelock, 5 = RequestWithdraw,
+/// 6 = ExecuteWithdraw, 7 = CancelWithdraw, 8 = CreateMultisig, 9 = MultisigWithdraw,
+/// 10 = TokenDeposit, 11 = TokenWithdraw, 12 = SetSpendingLimit, 13 = Spend,
+/// 14 = SetBeneficiary, 15 = Claim.
+pub fn process_instruction(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
     match instruction_data.split_first() {
         Some((d, data)) if *d == 0 => Deposit::try_from((data, accounts))?.process(),
-        Some((d, _)) if *d == 1 => Withdraw::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 1 => Withdraw::try_from((data, accounts))?.process(),
+        Some((d, _)) if *d == 2 => Initialize::try_from(accounts)?.process(),
+        Some((d, _)) if *d == 3 => Close::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 4 => SetTimelock::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 5 => RequestWithdraw::try_from((data, accounts))?.process(),
+        Some((d, _)) if *d == 6 => ExecuteWithdraw::try_from(accounts)?.process(),
+        Some((d, _)) if *d == 7 => CancelWithdraw::try_from(accounts)?.process(),
+        Some((d, data)) if *d == 8 => CreateMultisig::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 9 => MultisigWithdraw::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 10 => TokenDeposit::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 11 => TokenWithdraw::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 12 => SetSpendingLimit::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 13 => Spend::try_from((data, accounts))?.process(),
+        Some((d, data)) if *d == 14 => SetBeneficiary::try_from((data, accounts))?.process(),
+        Some((d, _)) if *d == 15 => Claim::try_from(accounts)?.process(),
         _ => Err(ProgramError::InvalidInstructionData),
     }
 }
@@ -0,0 +1,196 @@
+use core::mem::size_of;
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+use crate::errors::VaultError;
+
+/// Optional bookkeeping for a vault: a program-owned PDA at [b"state", owner]
+/// created by `Initialize` and closed by `Close`. Deposits and withdrawals that
+/// pass it, an optional trailing account clients pass once it exists, add to its
+/// totals, which count from initialization. It also holds the
+/// spending limit set by `SetSpendingLimit`, under which `spender` may `Spend`
+/// from the vault without the owner, the beneficiary set by `SetBeneficiary`,
+/// who may `Claim` the vault once the owner has been inactive for long enough,
+/// and the withdrawal delay set by `SetTimelock`. While a delay is set, lamports
+/// leave the vault only through `RequestWithdraw` followed, `delay` seconds later,
+/// by `ExecuteWithdraw`; `Withdraw`, `Spend` and `Claim` are refused.
+#[repr(C)]
+pub struct VaultState {
+    pub owner: Pubkey,
+    /// Unix timestamp of `Initialize`.
+    pub created_at: i64,
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    /// Key allowed to `Spend` up to `spending_limit` per window.
+    pub spender: Pubkey,
+    /// Lamports `spender` may take per window alone; 0 when spending is off.
+    pub spending_limit: u64,
+    /// Window length in seconds.
+    pub period: i64,
+    /// Unix timestamp the current window began.
+    pub window_start: i64,
+    /// Lamports spent in the current window.
+    pub window_spent: u64,
+    /// Key allowed to `Claim` the vault after `inactivity_period`.
+    pub beneficiary: Pubkey,
+    /// Seconds of owner inactivity before the beneficiary can claim; 0 when
+    /// recovery is off.
+    pub inactivity_period: i64,
+    /// Unix timestamp of the owner's last action that passed this state, token
+    /// vault ones included. `MultisigWithdraw` is no one owner's, so never counts.
+    pub last_active: i64,
+    /// Seconds between a withdrawal request and when it can execute; 0 when
+    /// withdrawals are immediate.
+    pub delay: i64,
+    /// Lamports requested; 0 when no withdrawal is pending.
+    pub pending_amount: u64,
+    /// Unix timestamp from which the pending withdrawal can execute.
+    pub unlock_at: i64,
+    /// Bump of the vault PDA [b"vault", owner].
+    pub vault_bump: [u8; 1],
+    /// Bump of this account's PDA [b"state", owner].
+    pub bump: [u8; 1],
+}
+
+impl VaultState {
+    pub const LEN: usize = size_of::<Pubkey>() * 3
+        + size_of::<i64>() * 7
+        + size_of::<u64>() * 5
+        + size_of::<[u8; 1]>() * 2;
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != VaultState::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != VaultState::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    #[inline(always)]
+    pub fn set_inner(
+        &mut self,
+        owner: Pubkey,
+        created_at: i64,
+        vault_bump: [u8; 1],
+        bump: [u8; 1],
+    ) {
+        self.owner = owner;
+        self.created_at = created_at;
+        self.total_deposited = 0;
+        self.total_withdrawn = 0;
+        self.spender = Pubkey::default();
+        self.spending_limit = 0;
+        self.period = 0;
+        self.window_start = 0;
+        self.window_spent = 0;
+        self.beneficiary = Pubkey::default();
+        self.inactivity_period = 0;
+        self.last_active = created_at;
+        self.delay = 0;
+        self.pending_amount = 0;
+        self.unlock_at = 0;
+        self.vault_bump = vault_bump;
+        self.bump = bump;
+    }
+
+    #[inline(always)]
+    pub fn record_deposit(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn record_withdrawal(&mut self, amount: u64) -> Result<(), ProgramError> {
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        Ok(())
+    }
+
+    /// Whether the beneficiary may claim at `now`.
+    #[inline(always)]
+    pub fn claimable(&self, now: i64) -> bool {
+        self.inactivity_period > 0 && now >= self.last_active.saturating_add(self.inactivity_period)
+    }
+
+    /// Count `amount` against the spending window at `now`, starting a new window
+    /// once `period` seconds have passed. Past the limit only when `cosigned` by
+    /// the owner.
+    #[inline(always)]
+    pub fn record_spend(&mut self, amount: u64, now: i64, cosigned: bool) -> ProgramResult {
+        if now >= self.window_start.saturating_add(self.period) {
+            self.window_start = now;
+            self.window_spent = 0;
+        }
+        let spent = self
+            .window_spent
+            .checked_add(amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        if spent > self.spending_limit && !cosigned {
+            return Err(VaultError::SpendingLimitExceeded.into());
+        }
+        self.window_spent = spent;
+        Ok(())
+    }
+}
+
+/// Most signers a multisig vault can have.
+pub const MAX_MULTISIG_SIGNERS: usize = 10;
+
+/// An M-of-N multisig vault: a program-owned PDA at [b"multisig", creator, seed]
+/// that holds its own lamports. Funded by plain system transfers; withdrawals
+/// need `threshold` of the `signers` to co-sign.
+#[repr(C)]
+pub struct MultisigVault {
+    pub creator: Pubkey,
+    pub seed: u64,
+    pub threshold: u8,
+    pub signer_count: u8,
+    pub bump: [u8; 1],
+    /// The first `signer_count` entries are the signers; the rest are zeroed.
+    pub signers: [Pubkey; MAX_MULTISIG_SIGNERS],
+}
+
+impl MultisigVault {
+    pub const LEN: usize = size_of::<Pubkey>()
+        + size_of::<u64>()
+        + size_of::<u8>() * 3
+        + size_of::<Pubkey>() * MAX_MULTISIG_SIGNERS;
+
+    #[inline(always)]
+    pub fn load_mut(bytes: &mut [u8]) -> Result<&mut Self, ProgramError> {
+        if bytes.len() != MultisigVault::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(unsafe { &mut *(bytes.as_mut_ptr() as *mut Self) })
+    }
+
+    #[inline(always)]
+    pub fn load(bytes: &[u8]) -> Result<&Self, ProgramError> {
+        if bytes.len() != MultisigVault::LEN {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let multisig = unsafe { &*(bytes.as_ptr() as *const Self) };
+        if multisig.signer_count as usize > MAX_MULTISIG_SIGNERS {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(multisig)
+    }
+
+    /// The vault's signers.
+    #[inline(always)]
+    pub fn signers(&self) -> &[Pubkey] {
+        &self.signers[..self.signer_count as usize]
+    }
+}
@@ -0,0 +1,66 @@
+//! Beneficiary recovery: claimable once the owner has been inactive for the whole
+//! period, and never while recovery is off.
+
+mod common;
+
+use blueshift_pinocchio_vault::{SetBeneficiaryInstructionData, VaultState};
+use common::StateData;
+use pinocchio::program_error::ProgramError;
+
+const PERIOD: i64 = 365 * 86_400;
+const LAST_ACTIVE: i64 = 1_700_000_000;
+
+/// A state with a beneficiary after `inactivity_period`, last active at
+/// `LAST_ACTIVE`.
+fn state(inactivity_period: i64) -> StateData {
+    StateData::new(LAST_ACTIVE, |state| {
+        state.beneficiary = [2; 32];
+        state.inactivity_period = inactivity_period;
+    })
+}
+
+#[test]
+fn claimable_once_the_period_has_elapsed() {
+    let data = state(PERIOD);
+    let state = VaultState::load(&data.0).unwrap();
+
+    assert!(!state.claimable(LAST_ACTIVE));
+    assert!(!state.claimable(LAST_ACTIVE + PERIOD - 1));
+    assert!(state.claimable(LAST_ACTIVE + PERIOD));
+    assert!(state.claimable(i64::MAX));
+}
+
+#[test]
+fn owner_activity_restarts_the_period() {
+    let mut data = state(PERIOD);
+    let state = VaultState::load_mut(&mut data.0).unwrap();
+    state.last_active = LAST_ACTIVE + PERIOD - 1;
+
+    assert!(!state.claimable(LAST_ACTIVE + PERIOD));
+    assert!(state.claimable(LAST_ACTIVE + 2 * PERIOD - 1));
+}
+
+#[test]
+fn zero_period_turns_recovery_off() {
+    let data = state(0);
+    let state = VaultState::load(&data.0).unwrap();
+
+    assert!(!state.claimable(LAST_ACTIVE));
+    assert!(!state.claimable(i64::MAX));
+}
+
+#[test]
+fn period_may_not_be_negative() {
+    let data = |period: i64| [&[2u8; 32][..], &period.to_le_bytes()].concat();
+    for period in [0, 1, PERIOD, i64::MAX] {
+        let parsed = SetBeneficiaryInstructionData::try_from(&data(period)[..]).unwrap();
+        assert_eq!(parsed.beneficiary, [2; 32]);
+        assert_eq!(parsed.inactivity_period, period);
+    }
+    for period in [-1, i64::MIN] {
+        assert_eq!(
+            SetBeneficiaryInstructionData::try_from(&data(period)[..]).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+}
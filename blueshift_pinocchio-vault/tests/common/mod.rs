@@ -0,0 +1,18 @@
+//! Fixtures shared by the vault state tests.
+
+use blueshift_pinocchio_vault::VaultState;
+
+/// Vault state account data, 8-byte aligned like the runtime's.
+#[repr(C, align(8))]
+pub struct StateData(pub [u8; VaultState::LEN]);
+
+impl StateData {
+    /// The state of owner [1; 32] initialized at `created_at`, after `edit`.
+    pub fn new(created_at: i64, edit: impl FnOnce(&mut VaultState)) -> Self {
+        let mut data = Self([0; VaultState::LEN]);
+        let state = VaultState::load_mut(&mut data.0).unwrap();
+        state.set_inner([1; 32], created_at, [255], [254]);
+        edit(state);
+        data
+    }
+}
@@ -0,0 +1,43 @@
+//! Deposit amounts: the first deposit funds the vault past rent exemption, top-ups
+//! may be any amount.
+
+use blueshift_pinocchio_vault::{DepositInstructionData, VaultError};
+use pinocchio::program_error::ProgramError;
+
+/// Rent-exempt minimum of a zero-data account.
+const RENT_EXEMPT_MINIMUM: u64 = 890_880;
+
+fn deposit(amount: u64, vault_balance: u64) -> Result<(), ProgramError> {
+    DepositInstructionData::try_from(&amount.to_le_bytes()[..])?
+        .check_rent_exempt(vault_balance, RENT_EXEMPT_MINIMUM)
+}
+
+#[test]
+fn first_deposit_above_rent_exemption_funds_the_vault() {
+    assert_eq!(deposit(RENT_EXEMPT_MINIMUM + 1, 0), Ok(()));
+}
+
+#[test]
+fn first_deposit_up_to_rent_exemption_is_rejected() {
+    for amount in [1, RENT_EXEMPT_MINIMUM - 1, RENT_EXEMPT_MINIMUM] {
+        assert_eq!(
+            deposit(amount, 0),
+            Err(ProgramError::Custom(VaultError::NotRentExempt as u32))
+        );
+    }
+}
+
+#[test]
+fn funded_vault_takes_top_ups_of_any_amount() {
+    for amount in [1, RENT_EXEMPT_MINIMUM, u64::MAX] {
+        assert_eq!(deposit(amount, RENT_EXEMPT_MINIMUM + 1), Ok(()));
+    }
+}
+
+#[test]
+fn zero_amount_is_rejected() {
+    assert_eq!(
+        deposit(0, RENT_EXEMPT_MINIMUM + 1),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
@@ -0,0 +1,77 @@
+//! CreateMultisig instruction data: 1 <= threshold <= signers, no signer twice.
+
+use blueshift_pinocchio_vault::{CreateMultisigInstructionData, MAX_MULTISIG_SIGNERS};
+use pinocchio::program_error::ProgramError;
+
+fn data(threshold: u8, signers: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = 7u64.to_le_bytes().to_vec();
+    data.push(threshold);
+    data.extend(signers.concat());
+    data
+}
+
+fn create(data: &[u8]) -> Result<CreateMultisigInstructionData<'_>, ProgramError> {
+    CreateMultisigInstructionData::try_from(data)
+}
+
+#[test]
+fn threshold_may_be_one_to_all_signers() {
+    let signers = [[1; 32], [2; 32], [3; 32]];
+    for threshold in 1..=3 {
+        let data = data(threshold, &signers);
+        let multisig = create(&data).unwrap();
+        assert_eq!(multisig.seed, 7);
+        assert_eq!(multisig.threshold, threshold);
+        assert_eq!(multisig.signer_count(), 3);
+        assert_eq!(multisig.signer(2), [3; 32]);
+    }
+}
+
+#[test]
+fn threshold_of_zero_or_above_the_signers_is_rejected() {
+    let signers = [[1; 32], [2; 32]];
+    for threshold in [0, 3, u8::MAX] {
+        assert_eq!(
+            create(&data(threshold, &signers)).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+}
+
+#[test]
+fn signer_listed_twice_is_rejected() {
+    for signers in [
+        [[1; 32], [1; 32], [2; 32]],
+        [[1; 32], [2; 32], [1; 32]],
+        [[1; 32], [2; 32], [2; 32]],
+    ] {
+        assert_eq!(
+            create(&data(1, &signers)).err(),
+            Some(ProgramError::InvalidInstructionData)
+        );
+    }
+}
+
+#[test]
+fn signer_count_is_bounded() {
+    assert_eq!(
+        create(&data(1, &[])).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+
+    let signers: Vec<[u8; 32]> = (1..=MAX_MULTISIG_SIGNERS as u8 + 1)
+        .map(|i| [i; 32])
+        .collect();
+    assert!(create(&data(1, &signers[..MAX_MULTISIG_SIGNERS])).is_ok());
+    assert_eq!(
+        create(&data(1, &signers)).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+
+    let mut partial_key = data(1, &[[1; 32]]);
+    partial_key.pop();
+    assert_eq!(
+        create(&partial_key).err(),
+        Some(ProgramError::InvalidInstructionData)
+    );
+}
@@ -0,0 +1,76 @@
+//! Spending windows: the spender alone may take up to the limit per period; the
+//! owner co-signing lifts the limit.
+
+mod common;
+
+use blueshift_pinocchio_vault::{VaultError, VaultState};
+use common::StateData;
+use pinocchio::program_error::ProgramError;
+
+const LIMIT: u64 = 1_000;
+const PERIOD: i64 = 86_400;
+const START: i64 = 1_700_000_000;
+
+/// A state whose spending window of `LIMIT` per `PERIOD` began at `START`.
+fn state() -> StateData {
+    StateData::new(START, |state| {
+        state.spending_limit = LIMIT;
+        state.period = PERIOD;
+        state.window_start = START;
+    })
+}
+
+fn limit_exceeded() -> Result<(), ProgramError> {
+    Err(VaultError::SpendingLimitExceeded.into())
+}
+
+#[test]
+fn spends_add_up_to_the_limit_within_a_window() {
+    let mut data = state();
+    let state = VaultState::load_mut(&mut data.0).unwrap();
+
+    assert_eq!(state.record_spend(400, START, false), Ok(()));
+    assert_eq!(state.record_spend(600, START + PERIOD - 1, false), Ok(()));
+    assert_eq!(state.window_spent, LIMIT);
+    assert_eq!(
+        state.record_spend(1, START + PERIOD - 1, false),
+        limit_exceeded()
+    );
+    assert_eq!(state.window_spent, LIMIT);
+}
+
+#[test]
+fn a_new_window_starts_once_the_period_has_passed() {
+    let mut data = state();
+    let state = VaultState::load_mut(&mut data.0).unwrap();
+
+    assert_eq!(state.record_spend(LIMIT, START, false), Ok(()));
+    assert_eq!(state.record_spend(LIMIT, START + PERIOD, false), Ok(()));
+    assert_eq!(state.window_start, START + PERIOD);
+    assert_eq!(state.window_spent, LIMIT);
+}
+
+#[test]
+fn owner_cosigning_lifts_the_limit() {
+    let mut data = state();
+    let state = VaultState::load_mut(&mut data.0).unwrap();
+
+    assert_eq!(
+        state.record_spend(LIMIT + 1, START, false),
+        limit_exceeded()
+    );
+    assert_eq!(state.record_spend(LIMIT + 1, START, true), Ok(()));
+    // The co-signed spend still counts towards the window.
+    assert_eq!(state.record_spend(1, START, false), limit_exceeded());
+}
+
+#[test]
+fn zero_limit_turns_spending_off() {
+    let mut data = state();
+    let state = VaultState::load_mut(&mut data.0).unwrap();
+    state.spending_limit = 0;
+    state.period = 0;
+
+    assert_eq!(state.record_spend(1, START, false), limit_exceeded());
+    assert_eq!(state.record_spend(1, START, true), Ok(()));
+}
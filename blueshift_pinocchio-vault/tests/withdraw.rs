@@ -0,0 +1,75 @@
+//! Withdrawal amounts: partial lamport withdrawals must leave the vault
+//! rent-exempt; token withdrawals may take up to the balance.
+
+use blueshift_pinocchio_vault::{VaultError, WithdrawInstructionData};
+use pinocchio::program_error::ProgramError;
+
+/// Rent-exempt minimum of a zero-data account.
+const RENT_EXEMPT_MINIMUM: u64 = 890_880;
+const BALANCE: u64 = 10 * RENT_EXEMPT_MINIMUM;
+
+fn withdraw(amount: u64) -> Result<u64, ProgramError> {
+    WithdrawInstructionData::try_from(&amount.to_le_bytes()[..])?
+        .lamports(BALANCE, RENT_EXEMPT_MINIMUM)
+}
+
+#[test]
+fn partial_withdrawal_leaves_the_rest() {
+    assert_eq!(withdraw(1_000), Ok(1_000));
+    assert_eq!(
+        withdraw(BALANCE - RENT_EXEMPT_MINIMUM),
+        Ok(BALANCE - RENT_EXEMPT_MINIMUM)
+    );
+}
+
+#[test]
+fn withdrawing_the_whole_balance_drains_the_vault() {
+    assert_eq!(withdraw(BALANCE), Ok(BALANCE));
+
+    let everything = WithdrawInstructionData::try_from(&[][..]).unwrap();
+    assert_eq!(everything.amount, None);
+    assert_eq!(
+        everything.lamports(BALANCE, RENT_EXEMPT_MINIMUM),
+        Ok(BALANCE)
+    );
+}
+
+#[test]
+fn remainder_below_rent_exemption_is_rejected() {
+    for remainder in [1, RENT_EXEMPT_MINIMUM - 1] {
+        assert_eq!(
+            withdraw(BALANCE - remainder),
+            Err(VaultError::NotRentExempt.into())
+        );
+    }
+}
+
+#[test]
+fn amount_above_the_balance_is_rejected() {
+    assert_eq!(withdraw(BALANCE + 1), Err(ProgramError::InsufficientFunds));
+    assert_eq!(withdraw(u64::MAX), Err(ProgramError::InsufficientFunds));
+}
+
+#[test]
+fn zero_amount_is_rejected() {
+    assert_eq!(withdraw(0), Err(ProgramError::InvalidInstructionData));
+}
+
+#[test]
+fn token_withdrawal_takes_up_to_the_balance() {
+    let everything = WithdrawInstructionData::try_from(&[][..]).unwrap();
+    assert_eq!(everything.token_amount(1_000), Ok(1_000));
+    assert_eq!(
+        everything.token_amount(0),
+        Err(ProgramError::InsufficientFunds)
+    );
+
+    for (amount, expected) in [
+        (1, Ok(1)),
+        (1_000, Ok(1_000)),
+        (1_001, Err(ProgramError::InsufficientFunds)),
+    ] {
+        let data = WithdrawInstructionData::try_from(&u64::to_le_bytes(amount)[..]).unwrap();
+        assert_eq!(data.token_amount(1_000), expected);
+    }
+}
@@ -1,7 +1,7 @@
 use core::convert::TryFrom;
 use pinocchio::{
     program_error::ProgramError,
-    pubkey::{find_program_address, Pubkey},
+    pubkey::find_program_address,
     ProgramResult,
 };
 use pinocchio_secp256r1_instruction::Secp256r1Pubkey;
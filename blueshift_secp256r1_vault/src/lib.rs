@@ -2,18 +2,25 @@
 
 use pinocchio::{
     account_info::AccountInfo,
-    entrypoint,
     program_error::ProgramError,
     pubkey::Pubkey,
     ProgramResult,
 };
 
+#[cfg(not(feature = "no-entrypoint"))]
+use pinocchio::entrypoint;
+
+#[cfg(not(feature = "no-entrypoint"))]
 entrypoint!(process_instruction);
 
-// Omit in SBF build to avoid duplicate lang item (entrypoint provides panic handling)
-#[cfg(not(target_arch = "bpfel"))]
+#[cfg(not(feature = "no-entrypoint"))]
 pinocchio::nostd_panic_handler!();
 
+// Without the entrypoint the host-side cdylib still needs a panic handler; the
+// program embedding this crate provides its own on-chain.
+#[cfg(all(feature = "no-entrypoint", not(target_os = "solana")))]
+extern crate std;
+
 pub mod instructions;
 pub use instructions::*;
 
@@ -23,7 +30,7 @@ pub const ID: Pubkey = [
     0x19, 0x92, 0xba, 0xe8, 0xaf, 0xd1, 0xcd, 0x07, 0x8e, 0xf8, 0xaf, 0x70, 0x47, 0xdc, 0x11, 0xf7,
 ];
 
-fn process_instruction(
+pub fn process_instruction(
     _program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],